@@ -6,4 +6,13 @@ pub struct Args {
     pub port: u16,
     #[clap(long, value_parser, default_value = "65536")]
     pub resend_budget: usize,
+    /// Simulates a battle recording (.dat) or scripted battle (.toml) without rendering, then exits
+    #[clap(long, value_parser)]
+    pub simulate: Option<String>,
+    /// Writes the simulation report to the specified file instead of stdout
+    #[clap(long, value_parser)]
+    pub simulation_output: Option<String>,
+    /// Stops the simulation after the specified number of frames
+    #[clap(long, value_parser, default_value = "216000")]
+    pub simulation_frame_limit: i64,
 }
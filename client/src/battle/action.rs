@@ -1,7 +1,7 @@
 use super::{
     ActionQueue, AttackContext, BattleAnimator, BattleCallback, BattleScriptContext,
-    BattleSimulation, Character, DerivedAnimationFrame, Entity, Field, Living, Movement, Player,
-    SharedBattleResources,
+    BattleSimulation, Character, DerivedAnimationFrame, Entity, Field, Living, LuaCallbackCategory,
+    Movement, Player, SharedBattleResources,
 };
 use crate::bindable::{
     ActionLockout, CardProperties, EntityId, GenerationalIndex, HitFlag, SpriteColorMode,
@@ -224,6 +224,7 @@ impl Action {
 
         // execute callback
         if let Some(callback) = action.execute_callback.take() {
+            let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::CardAction);
             callback.call(game_io, resources, simulation, ());
         }
 
@@ -569,6 +570,7 @@ impl Action {
             };
 
             if let Some(callback) = action.update_callback.clone() {
+                let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::CardAction);
                 callback.call(game_io, resources, simulation, ());
             }
 
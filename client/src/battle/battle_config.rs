@@ -19,6 +19,9 @@ pub struct BattleConfig {
     pub player_flippable: Vec<Option<bool>>,
     pub turn_limit: Option<u32>,
     pub automatic_turn_end: bool,
+    /// Players using a package that exceeds its instruction budget are deleted,
+    /// otherwise only the package's scripts are disabled
+    pub instruction_budget_forfeit: bool,
     // todo:
     // pub status_durations: [FrameTime; 3],
     // pub intangibility_duration: FrameTime,
@@ -46,6 +49,7 @@ impl BattleConfig {
             player_flippable: vec![None; player_count],
             turn_limit: None,
             automatic_turn_end: false,
+            instruction_budget_forfeit: false,
             // status_durations: [90, 120, 150],
             // intangibility_duration: 120,
             // super_effective_multiplier: 2.0,
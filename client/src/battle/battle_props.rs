@@ -7,7 +7,7 @@ use crate::saves::Deck;
 use crate::saves::PlayerInputBuffer;
 use framework::prelude::*;
 use packets::structures::InstalledSwitchDrive;
-use packets::structures::{BattleStatistics, Emotion, InstalledBlock};
+use packets::structures::{BattleReward, BattleStatistics, Emotion, InstalledBlock};
 use serde::{Deserialize, Serialize};
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -149,7 +149,11 @@ pub struct BattleProps {
     pub player_setups: Vec<PlayerSetup>,
     pub senders: Vec<NetplayPacketSender>,
     pub receivers: Vec<(Option<usize>, NetplayPacketReceiver)>,
+    /// Shares input with spectators through the server
+    pub spectator_sender: Option<NetplayPacketSender>,
     pub statistics_callback: Option<BattleStatisticsCallback>,
+    /// Rewards from the server, listed on the results screen
+    pub reward_receiver: Option<flume::Receiver<BattleReward>>,
     pub recording_enabled: bool,
 }
 
@@ -168,7 +172,9 @@ impl BattleProps {
             player_setups: vec![PlayerSetup::from_globals(game_io)],
             senders: Vec::new(),
             receivers: Vec::new(),
+            spectator_sender: None,
             statistics_callback: None,
+            reward_receiver: None,
             recording_enabled: true,
         }
     }
@@ -189,7 +195,9 @@ impl BattleProps {
             player_setups: recording.player_setups.clone(),
             senders: Vec::new(),
             receivers: Vec::new(),
+            spectator_sender: None,
             statistics_callback: None,
+            reward_receiver: None,
             recording_enabled: false,
         }
     }
@@ -36,6 +36,8 @@ pub struct BattleSimulation {
     pub time_freeze_tracker: TimeFreezeTracker,
     pub components: DenseSlotMap<Component>,
     pub pending_callbacks: Vec<BattleCallback>,
+    /// Indices of vms that exceeded their instruction budget, calls into these vms are skipped
+    pub disabled_vms: Vec<usize>,
     pub local_player_id: EntityId,
     pub local_health_ui: PlayerHealthUi,
     pub local_team: Team,
@@ -86,6 +88,7 @@ impl BattleSimulation {
             time_freeze_tracker: TimeFreezeTracker::new(),
             components: Default::default(),
             pending_callbacks: Vec::new(),
+            disabled_vms: Vec::new(),
             local_player_id: EntityId::DANGLING,
             local_health_ui: PlayerHealthUi::new(game_io),
             local_team: Team::Unset,
@@ -165,6 +168,7 @@ impl BattleSimulation {
             time_freeze_tracker: self.time_freeze_tracker.clone(),
             components: self.components.clone(),
             pending_callbacks: self.pending_callbacks.clone(),
+            disabled_vms: self.disabled_vms.clone(),
             local_player_id: self.local_player_id,
             local_health_ui: self.local_health_ui.clone(),
             local_team: self.local_team,
@@ -196,41 +200,101 @@ impl BattleSimulation {
     pub fn wrap_up_statistics(&mut self) {
         let entities = &mut self.entities;
 
-        if let Ok((entity, living)) =
-            entities.query_one_mut::<(&Entity, &Living)>(self.local_player_id.into())
+        if let Ok((entity, living, player)) = entities
+            .query_one_mut::<(&Entity, &Living, Option<&Player>)>(self.local_player_id.into())
         {
             self.local_team = entity.team;
             self.statistics.health = living.health;
-        }
 
-        for (id, (entity, living, name)) in
-            entities.query_mut::<(&Entity, &Living, Option<&EntityName>)>()
-        {
-            let entity_id: EntityId = id.into();
-
-            if entity_id != self.local_player_id {
-                continue;
+            if let Some(player) = player {
+                self.statistics.emotion = player.emotion_window.emotion().clone();
             }
+        }
 
-            let survivor_list = if entity.team == self.local_team {
-                &mut self.statistics.ally_survivors
-            } else if entity.team != Team::Other {
-                &mut self.statistics.enemy_survivors
-            } else {
-                &mut self.statistics.neutral_survivors
-            };
+        // final health for every player, allows servers to compare results between players
+        for (_, (living, player)) in entities.query_mut::<(&Living, &Player)>() {
+            self.statistics.player_mut(player.index).health = living.health;
+        }
 
-            let name = name.map(|n| n.0.clone()).unwrap_or_default();
+        let survivors = entities
+            .query_mut::<(&Entity, &Living, Option<&EntityName>)>()
+            .into_iter()
+            .map(|(id, (entity, living, name))| {
+                let survivor = BattleSurvivor {
+                    name: name.map(|n| n.0.clone()).unwrap_or_default(),
+                    health: living.health,
+                };
 
-            survivor_list.push(BattleSurvivor {
-                name,
-                health: living.health,
+                (id.into(), entity.team, survivor)
             });
-        }
+
+        list_survivors(
+            &mut self.statistics,
+            self.local_player_id,
+            self.local_team,
+            survivors,
+        );
 
         self.statistics.calculate_score();
     }
 
+    /// Describes state that should match on every client, used for desync detection
+    pub fn describe_synced_state(&mut self) -> String {
+        use rand::RngCore;
+        use std::fmt::Write;
+
+        let mut description = String::new();
+
+        // sampling a clone to avoid advancing the simulation's rng
+        let rng_sample = self.rng.clone().next_u64();
+        let _ = writeln!(description, "time: {}", self.time);
+        let _ = writeln!(description, "rng: {rng_sample:016x}");
+
+        // hecs iteration order isn't guaranteed to match, sort by id
+        let mut entities: Vec<_> = self
+            .entities
+            .query_mut::<(&Entity, Option<&Living>, Option<&EntityName>)>()
+            .into_iter()
+            .map(|(id, (entity, living, name))| {
+                let health = living.map(|living| (living.health, living.max_health));
+                let name = name.map(|name| name.0.as_str()).unwrap_or_default();
+
+                let line = format!(
+                    "entity {id:?} {name:?}: ({}, {}) {:?} health: {health:?} deleted: {} erased: {}",
+                    entity.x,
+                    entity.y,
+                    entity.team,
+                    entity.deleted,
+                    entity.erased,
+                );
+
+                (id.to_bits(), line)
+            })
+            .collect();
+
+        entities.sort_by_key(|(id, _)| *id);
+
+        for (_, line) in entities {
+            let _ = writeln!(description, "{line}");
+        }
+
+        for ((col, row), tile) in self.field.iter_mut() {
+            let state_name = self
+                .tile_states
+                .get(tile.state_index())
+                .map(|tile_state| tile_state.state_name.as_str())
+                .unwrap_or_default();
+
+            let _ = writeln!(
+                description,
+                "tile ({col}, {row}): {state_name} {:?}",
+                tile.team()
+            );
+        }
+
+        description
+    }
+
     pub fn handle_local_signals(&mut self, local_index: usize, resources: &SharedBattleResources) {
         let input = &self.inputs[local_index];
 
@@ -488,6 +552,57 @@ impl BattleSimulation {
         self.call_pending_callbacks(game_io, resources);
     }
 
+    /// Disables vms that ran out of instructions this frame, see `BattleConfig::instruction_budget_forfeit`
+    pub fn handle_exceeded_instruction_budgets(
+        &mut self,
+        game_io: &GameIO,
+        resources: &SharedBattleResources,
+    ) {
+        for (vm_index, vm) in resources.vm_manager.vms().iter().enumerate() {
+            if !vm.exceeded_instruction_budget() || self.disabled_vms.contains(&vm_index) {
+                continue;
+            }
+
+            self.disabled_vms.push(vm_index);
+
+            if resources
+                .reported_exhausted_vms
+                .borrow_mut()
+                .insert(vm_index)
+            {
+                log::error!(
+                    "{:?} exceeded the instruction budget, disabling its scripts",
+                    vm.package_id
+                );
+            }
+
+            if !self.config.instruction_budget_forfeit {
+                continue;
+            }
+
+            // only packages owned by a specific player can be blamed on that player
+            let player_ids: Vec<EntityId> = self
+                .entities
+                .query_mut::<&Character>()
+                .with::<&Player>()
+                .into_iter()
+                .filter(|(_, character)| {
+                    matches!(
+                        character.namespace,
+                        PackageNamespace::Local
+                            | PackageNamespace::RecordingLocal
+                            | PackageNamespace::Netplay(_)
+                    ) && vm.namespaces.contains(&character.namespace)
+                })
+                .map(|(id, _)| id.into())
+                .collect();
+
+            for id in player_ids {
+                Entity::delete(game_io, resources, self, id);
+            }
+        }
+    }
+
     pub fn call_pending_callbacks(&mut self, game_io: &GameIO, resources: &SharedBattleResources) {
         let callbacks = std::mem::take(&mut self.pending_callbacks);
 
@@ -1061,3 +1176,61 @@ impl BattleSimulation {
         assert!(held_action_count >= executed_action_count || time_is_frozen);
     }
 }
+
+/// Sorts surviving entities into ally, enemy, and neutral lists, the local player isn't listed as a survivor
+fn list_survivors(
+    statistics: &mut BattleStatistics,
+    local_player_id: EntityId,
+    local_team: Team,
+    survivors: impl IntoIterator<Item = (EntityId, Team, BattleSurvivor)>,
+) {
+    for (entity_id, team, survivor) in survivors {
+        if entity_id == local_player_id {
+            continue;
+        }
+
+        let survivor_list = if team == local_team {
+            &mut statistics.ally_survivors
+        } else if team != Team::Other {
+            &mut statistics.enemy_survivors
+        } else {
+            &mut statistics.neutral_survivors
+        };
+
+        survivor_list.push(survivor);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn survivor(name: &str, health: i32) -> BattleSurvivor {
+        BattleSurvivor {
+            name: name.to_string(),
+            health,
+        }
+    }
+
+    #[test]
+    fn survivors_exclude_the_local_player() {
+        let mut world = hecs::World::new();
+        let mut spawn_id = || -> EntityId { world.spawn(()).into() };
+
+        let local_player_id = spawn_id();
+        let mut statistics = BattleStatistics::default();
+
+        let survivors = [
+            (local_player_id, Team::Red, survivor("Local", 100)),
+            (spawn_id(), Team::Red, survivor("Ally", 80)),
+            (spawn_id(), Team::Blue, survivor("Enemy", 40)),
+            (spawn_id(), Team::Other, survivor("Rock", 10)),
+        ];
+
+        list_survivors(&mut statistics, local_player_id, Team::Red, survivors);
+
+        assert_eq!(statistics.ally_survivors, vec![survivor("Ally", 80)]);
+        assert_eq!(statistics.enemy_survivors, vec![survivor("Enemy", 40)]);
+        assert_eq!(statistics.neutral_survivors, vec![survivor("Rock", 10)]);
+    }
+}
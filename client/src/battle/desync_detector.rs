@@ -0,0 +1,248 @@
+use crate::render::FrameTime;
+use crate::resources::ResourcePaths;
+use packets::structures::FileHash;
+use std::collections::VecDeque;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// how often checksums are shared with other players
+const CHECKSUM_INTERVAL: FrameTime = 60;
+
+// old checksums are kept around for players lagging behind
+const LOCAL_CHECKSUM_LIMIT: usize = 10;
+
+struct LocalChecksum {
+    time: FrameTime,
+    checksum: u64,
+    state_description: String,
+}
+
+struct RemoteChecksum {
+    index: usize,
+    time: FrameTime,
+    checksum: u64,
+}
+
+/// Compares checksums of synced battle state with other players
+pub struct DesyncDetector {
+    next_checksum_time: FrameTime,
+    local_checksums: VecDeque<LocalChecksum>,
+    remote_checksums: Vec<RemoteChecksum>,
+    desync_time: Option<FrameTime>,
+    unsaved_report: Option<String>,
+}
+
+impl DesyncDetector {
+    pub fn new() -> Self {
+        Self {
+            next_checksum_time: CHECKSUM_INTERVAL,
+            local_checksums: VecDeque::new(),
+            remote_checksums: Vec::new(),
+            desync_time: None,
+            unsaved_report: None,
+        }
+    }
+
+    pub fn next_checksum_time(&self) -> FrameTime {
+        self.next_checksum_time
+    }
+
+    pub fn desync_time(&self) -> Option<FrameTime> {
+        self.desync_time
+    }
+
+    /// Used when the state for the next checksum time is no longer available
+    pub fn skip_checksum(&mut self) {
+        self.next_checksum_time += CHECKSUM_INTERVAL;
+    }
+
+    /// Tracks the final state for the next checksum time, returns the checksum to share with other players
+    pub fn track_local_state(&mut self, state_description: String) -> u64 {
+        let time = self.next_checksum_time;
+        let hash = FileHash::hash(state_description.as_bytes());
+        let checksum = u64::from_le_bytes(hash.as_bytes()[..8].try_into().unwrap());
+
+        self.local_checksums.push_back(LocalChecksum {
+            time,
+            checksum,
+            state_description,
+        });
+
+        if self.local_checksums.len() > LOCAL_CHECKSUM_LIMIT {
+            self.local_checksums.pop_front();
+        }
+
+        self.next_checksum_time += CHECKSUM_INTERVAL;
+        self.compare_checksums();
+
+        checksum
+    }
+
+    pub fn track_remote_checksum(&mut self, index: usize, time: FrameTime, checksum: u64) {
+        self.remote_checksums.push(RemoteChecksum {
+            index,
+            time,
+            checksum,
+        });
+
+        self.compare_checksums();
+    }
+
+    fn compare_checksums(&mut self) {
+        let remote_checksums = std::mem::take(&mut self.remote_checksums);
+
+        for remote in remote_checksums {
+            let local = self
+                .local_checksums
+                .iter()
+                .find(|local| local.time == remote.time);
+
+            let Some(local) = local else {
+                if remote.time >= self.next_checksum_time {
+                    // we haven't reached this time yet
+                    self.remote_checksums.push(remote);
+                }
+
+                continue;
+            };
+
+            if local.checksum != remote.checksum && self.desync_time.is_none() {
+                self.desync_time = Some(remote.time);
+
+                log::error!(
+                    "Desync detected with player {} on frame {}",
+                    remote.index,
+                    remote.time
+                );
+
+                self.unsaved_report = Some(Self::describe_desync(local, &remote));
+            }
+        }
+    }
+
+    fn describe_desync(local: &LocalChecksum, remote: &RemoteChecksum) -> String {
+        format!(
+            "version: {}\n\
+            local checksum: {:016x}\n\
+            player {} checksum: {:016x}\n\
+            \n\
+            {}",
+            env!("CARGO_PKG_VERSION"),
+            local.checksum,
+            remote.index,
+            remote.checksum,
+            local.state_description
+        )
+    }
+
+    /// Saves the state description for the detected desync in the background
+    pub fn save_report(&mut self) {
+        let (Some(text), Some(desync_time)) = (self.unsaved_report.take(), self.desync_time) else {
+            return;
+        };
+
+        let elapsed_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let folder_path = format!(
+            "{}{}",
+            ResourcePaths::game_folder(),
+            ResourcePaths::DESYNC_FOLDER
+        );
+        let file_path = format!(
+            "{folder_path}{}-frame-{}.txt",
+            elapsed_time.as_secs(),
+            desync_time
+        );
+
+        std::thread::spawn(move || {
+            let _ = std::fs::create_dir_all(&folder_path);
+
+            if let Err(e) = std::fs::write(&file_path, text) {
+                log::error!("Failed to save desync state to {:?}: {}", file_path, e);
+            } else {
+                log::info!("Saved desync state to {:?}", file_path);
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn matching_checksums() {
+        let mut detector = DesyncDetector::new();
+
+        let checksum = detector.track_local_state(String::from("state"));
+        detector.track_remote_checksum(1, CHECKSUM_INTERVAL, checksum);
+
+        assert_eq!(detector.desync_time(), None);
+        assert!(detector.unsaved_report.is_none());
+    }
+
+    #[test]
+    fn mismatched_checksums() {
+        let mut detector = DesyncDetector::new();
+
+        let checksum = detector.track_local_state(String::from("state"));
+        detector.track_remote_checksum(1, CHECKSUM_INTERVAL, checksum + 1);
+
+        assert_eq!(detector.desync_time(), Some(CHECKSUM_INTERVAL));
+
+        let report = detector.unsaved_report.as_deref().unwrap();
+        assert!(report.contains(&format!("local checksum: {checksum:016x}")));
+        assert!(report.contains(&format!("player 1 checksum: {:016x}", checksum + 1)));
+        assert!(report.ends_with("state"));
+
+        // only the first desync is reported
+        let checksum = detector.track_local_state(String::from("later state"));
+        detector.track_remote_checksum(1, CHECKSUM_INTERVAL * 2, checksum + 1);
+
+        assert_eq!(detector.desync_time(), Some(CHECKSUM_INTERVAL));
+        assert!(detector
+            .unsaved_report
+            .as_deref()
+            .unwrap()
+            .ends_with("\nstate"));
+    }
+
+    #[test]
+    fn early_remote_checksums_wait_for_local_state() {
+        let mut detector = DesyncDetector::new();
+
+        // remote player is ahead of us
+        detector.track_remote_checksum(1, CHECKSUM_INTERVAL, 0);
+        assert_eq!(detector.desync_time(), None);
+
+        detector.track_local_state(String::from("state"));
+        assert_eq!(detector.desync_time(), Some(CHECKSUM_INTERVAL));
+    }
+
+    #[test]
+    fn late_remote_checksums_compare_with_old_state() {
+        let mut detector = DesyncDetector::new();
+
+        let checksum = detector.track_local_state(String::from("state"));
+
+        for i in 1..LOCAL_CHECKSUM_LIMIT {
+            detector.track_local_state(format!("state {i}"));
+        }
+
+        // remote player is behind us, but within the local checksum limit
+        detector.track_remote_checksum(1, CHECKSUM_INTERVAL, checksum + 1);
+        assert_eq!(detector.desync_time(), Some(CHECKSUM_INTERVAL));
+    }
+
+    #[test]
+    fn expired_remote_checksums_are_dropped() {
+        let mut detector = DesyncDetector::new();
+
+        for i in 0..=LOCAL_CHECKSUM_LIMIT {
+            detector.track_local_state(format!("state {i}"));
+        }
+
+        // the first checksum was dropped, nothing to compare with
+        detector.track_remote_checksum(1, CHECKSUM_INTERVAL, 0);
+        assert_eq!(detector.desync_time(), None);
+        assert!(detector.remote_checksums.is_empty());
+    }
+}
@@ -177,6 +177,16 @@ impl Character {
             .satisfies::<&Player>(entity_id.into())
             .unwrap_or(false);
 
+        // track card usage for players
+        if let Ok((character, player)) =
+            entities.query_one_mut::<(&Character, &Player)>(entity_id.into())
+        {
+            if let Some(card_props) = character.cards.last() {
+                let player_statistics = simulation.statistics.player_mut(player.index);
+                player_statistics.track_card_use(&card_props.package_id);
+            }
+        }
+
         if is_player && !simulation.time_freeze_tracker.time_is_frozen() {
             Player::use_card(game_io, resources, simulation, entity_id);
             return;
@@ -236,6 +236,8 @@ impl Entity {
                 augment_iter.flat_map(|augment| augment.delete_callback.clone());
 
             simulation.pending_callbacks.extend(augment_callbacks);
+
+            let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::Delete);
             simulation.call_pending_callbacks(game_io, resources);
         }
 
@@ -246,6 +248,7 @@ impl Entity {
         simulation.pending_callbacks.extend(listener_callbacks);
         simulation.pending_callbacks.push(delete_callback);
 
+        let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::Delete);
         simulation.call_pending_callbacks(game_io, resources);
     }
 
@@ -194,6 +194,7 @@ impl Living {
 
         // start processing hits
         let defense_rules = living.defense_rules.clone();
+        let mut dealt_damage = Vec::new();
 
         for hit_props in &mut hit_prop_list {
             // filter statuses through defense rules
@@ -265,6 +266,13 @@ impl Living {
                 continue;
             }
 
+            // credited to the aggressor once the final damage is resolved
+            let aggressor_id = hit_props.context.aggressor;
+
+            if aggressor_id != entity_id && modified_hit_damage > 0 {
+                dealt_damage.push((aggressor_id, modified_hit_damage));
+            }
+
             // time freeze effects
             if entity.time_frozen {
                 hit_props.context.flags |= HitFlag::NO_COUNTER;
@@ -307,8 +315,18 @@ impl Living {
                         let callback = aggressor_entity.counter_callback.clone();
                         callback.call(game_io, resources, simulation, entity_id);
 
+                        // track statistics for players
+                        let entities = &mut simulation.entities;
+
+                        if let Ok(player) = entities.query_one_mut::<&Player>(aggressor_id.into()) {
+                            let player_statistics = simulation.statistics.player_mut(player.index);
+                            player_statistics.counters += 1;
+                        }
+
                         // play counter sfx if the attack was caused by the local player
                         if simulation.local_player_id == aggressor_id {
+                            simulation.statistics.counters += 1;
+
                             let globals = game_io.resource::<Globals>().unwrap();
                             simulation.play_sound(game_io, &globals.sfx.counter_hit);
                         }
@@ -412,6 +430,9 @@ impl Living {
             total_damage = modified_total_damage;
         }
 
+        // damage can't exceed the remaining health for statistics
+        let damage_taken = total_damage.min(living.health).max(0);
+
         // apply damage and health modifier
         living.set_health(living.health - total_damage + health_modifier);
 
@@ -424,9 +445,33 @@ impl Living {
             }
         }
 
+        Self::track_damage_dealt(simulation, dealt_damage, damage_taken);
+
         simulation.call_pending_callbacks(game_io, resources);
     }
 
+    fn track_damage_dealt(
+        simulation: &mut BattleSimulation,
+        dealt_damage: Vec<(EntityId, i32)>,
+        mut remaining_damage: i32,
+    ) {
+        for (aggressor_id, damage) in dealt_damage {
+            if remaining_damage <= 0 {
+                break;
+            }
+
+            let damage = damage.min(remaining_damage);
+            remaining_damage -= damage;
+
+            let entities = &mut simulation.entities;
+            let Ok(player) = entities.query_one_mut::<&Player>(aggressor_id.into()) else {
+                continue;
+            };
+
+            simulation.statistics.player_mut(player.index).damage_dealt += damage;
+        }
+    }
+
     pub fn intercept_action(
         game_io: &GameIO,
         resources: &SharedBattleResources,
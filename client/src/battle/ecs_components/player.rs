@@ -182,6 +182,9 @@ impl Player {
             }));
         living.add_aux_prop(statistics_aux_prop);
 
+        // include every player in the statistics, even if they never act
+        simulation.statistics.player_mut(setup.index);
+
         // resolve health boost
         // todo: move to Augment?
         let grid = BlockGrid::new(namespace).with_blocks(game_io, blocks);
@@ -0,0 +1,232 @@
+use super::{BattleProps, PlayerSetup};
+use crate::args::Args;
+use crate::packages::PackageNamespace;
+use crate::render::{Background, FrameTime};
+use crate::resources::Globals;
+use crate::saves::{BattleRecording, Card, Deck, PlayerInputBuffer};
+use crate::scenes::BattleScene;
+use framework::logging::{LogLevel, LogRecord};
+use framework::prelude::*;
+use packets::structures::{BattleStatistics, Emotion, Input, PackageId};
+use packets::NetplayBufferItem;
+use serde::{Deserialize, Serialize};
+
+/// A battle described by hand, for testing packages without a recording
+#[derive(Deserialize)]
+struct ScriptedBattle {
+    encounter: Option<PackageId>,
+    data: Option<String>,
+    #[serde(default)]
+    seed: u64,
+    players: Vec<ScriptedPlayer>,
+}
+
+#[derive(Deserialize)]
+struct ScriptedPlayer {
+    package_id: PackageId,
+    health: Option<i32>,
+    #[serde(default)]
+    cards: Vec<Card>,
+    #[serde(default)]
+    inputs: Vec<ScriptedInput>,
+}
+
+#[derive(Deserialize)]
+struct ScriptedInput {
+    frames: usize,
+    #[serde(default)]
+    pressed: Vec<Input>,
+}
+
+#[derive(Serialize)]
+pub struct HeadlessBattleReport {
+    pub frames: FrameTime,
+    pub completed: bool,
+    pub statistics: Option<BattleStatistics>,
+    /// Errors logged during the simulation, such as Lua errors
+    pub errors: Vec<String>,
+}
+
+impl HeadlessBattleReport {
+    pub fn exit_code(&self) -> i32 {
+        if self.errors.is_empty() {
+            0
+        } else {
+            1
+        }
+    }
+}
+
+pub struct HeadlessBattleConfig {
+    pub path: String,
+    pub output_path: Option<String>,
+    pub frame_limit: FrameTime,
+}
+
+impl HeadlessBattleConfig {
+    pub fn from_args(args: &Args) -> Option<Self> {
+        Some(Self {
+            path: args.simulate.clone()?,
+            output_path: args.simulation_output.clone(),
+            frame_limit: args.simulation_frame_limit,
+        })
+    }
+
+    /// Simulates the battle without rendering, expects packages to be loaded
+    pub fn run(
+        &self,
+        game_io: &mut GameIO,
+        log_receiver: &flume::Receiver<LogRecord>,
+    ) -> HeadlessBattleReport {
+        // ignore logs from before the simulation
+        while log_receiver.try_recv().is_ok() {}
+
+        let mut report = HeadlessBattleReport {
+            frames: 0,
+            completed: false,
+            statistics: None,
+            errors: Vec::new(),
+        };
+
+        match self.load_props(game_io) {
+            Ok(props) => {
+                let mut battle_scene = BattleScene::new_playback(game_io, props);
+                battle_scene.simulate_headless(game_io, self.frame_limit);
+
+                let simulation = battle_scene.simulation();
+                report.frames = simulation.time;
+                report.completed = simulation.exit;
+                report.statistics = Some(simulation.statistics.clone());
+            }
+            Err(err) => {
+                log::error!("{err}");
+            }
+        }
+
+        while let Ok(record) = log_receiver.try_recv() {
+            if matches!(record.level, LogLevel::Error) {
+                report.errors.push(record.message);
+            }
+        }
+
+        report
+    }
+
+    pub fn output_report(&self, report: &HeadlessBattleReport) {
+        let text = match serde_json::to_string_pretty(report) {
+            Ok(text) => text,
+            Err(err) => {
+                log::error!("Failed to serialize simulation report: {err}");
+                return;
+            }
+        };
+
+        let Some(output_path) = &self.output_path else {
+            println!("{text}");
+            return;
+        };
+
+        if let Err(err) = std::fs::write(output_path, text) {
+            log::error!("Failed to save simulation report to {output_path:?}: {err}");
+        }
+    }
+
+    /// The game loop doesn't hand control back to `main`, so buffered output is flushed before exiting here
+    pub fn exit(&self, report: &HeadlessBattleReport) -> ! {
+        use std::io::Write;
+
+        log::logger().flush();
+        let _ = std::io::stdout().flush();
+        let _ = std::io::stderr().flush();
+
+        std::process::exit(report.exit_code())
+    }
+
+    fn load_props(&self, game_io: &mut GameIO) -> Result<BattleProps, String> {
+        let bytes = std::fs::read(&self.path)
+            .map_err(|err| format!("Failed to read {:?}: {err}", self.path))?;
+
+        if self.path.ends_with(".toml") {
+            let text = String::from_utf8_lossy(&bytes);
+            let scripted_battle: ScriptedBattle = toml::from_str(&text)
+                .map_err(|err| format!("Failed to parse {:?}: {err}", self.path))?;
+
+            return Self::scripted_battle_props(game_io, scripted_battle);
+        }
+
+        let recording = BattleRecording::decode(&bytes)
+            .map_err(|err| format!("Failed to parse recording {:?}: {err}", self.path))?;
+
+        recording.load_packages(game_io, Vec::new());
+
+        Ok(BattleProps::from_recording(game_io, &recording))
+    }
+
+    fn scripted_battle_props(
+        game_io: &GameIO,
+        scripted_battle: ScriptedBattle,
+    ) -> Result<BattleProps, String> {
+        let globals = game_io.resource::<Globals>().unwrap();
+        let ns = PackageNamespace::Local;
+
+        if let Some(id) = &scripted_battle.encounter {
+            if globals.encounter_packages.package(ns, id).is_none() {
+                return Err(format!("Missing encounter package: {id}"));
+            }
+        }
+
+        let mut player_setups = Vec::with_capacity(scripted_battle.players.len());
+
+        for (index, player) in scripted_battle.players.into_iter().enumerate() {
+            let Some(player_package) = globals.player_packages.package(ns, &player.package_id)
+            else {
+                return Err(format!("Missing player package: {}", player.package_id));
+            };
+
+            let mut deck = Deck::new(String::new());
+            deck.cards = player.cards;
+
+            let mut buffer = PlayerInputBuffer::default();
+
+            for input in player.inputs {
+                for _ in 0..input.frames {
+                    buffer.push_last(NetplayBufferItem {
+                        pressed: input.pressed.clone(),
+                        signals: Vec::new(),
+                    });
+                }
+            }
+
+            let health = player.health.unwrap_or(player_package.health);
+
+            player_setups.push(PlayerSetup {
+                package_id: player.package_id,
+                script_enabled: true,
+                health,
+                base_health: health,
+                emotion: Emotion::default(),
+                deck,
+                recipes: Vec::new(),
+                blocks: Vec::new(),
+                drives: Vec::new(),
+                index,
+                local: index == 0,
+                buffer,
+            });
+        }
+
+        Ok(BattleProps {
+            encounter_package_pair: scripted_battle.encounter.map(|id| (ns, id)),
+            data: scripted_battle.data,
+            seed: scripted_battle.seed,
+            background: Background::new_battle(game_io),
+            player_setups,
+            senders: Vec::new(),
+            receivers: Vec::new(),
+            spectator_sender: None,
+            statistics_callback: None,
+            reward_receiver: None,
+            recording_enabled: false,
+        })
+    }
+}
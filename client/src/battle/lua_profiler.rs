@@ -0,0 +1,294 @@
+use super::RollbackVM;
+use crate::packages::PackageId;
+use crate::resources::ResourcePaths;
+use std::cell::{Cell, RefCell};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum LuaCallbackCategory {
+    Update,
+    Attack,
+    Delete,
+    CardAction,
+    #[default]
+    Other,
+}
+
+impl LuaCallbackCategory {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LuaCallbackCategory::Update => "update",
+            LuaCallbackCategory::Attack => "attack",
+            LuaCallbackCategory::Delete => "delete",
+            LuaCallbackCategory::CardAction => "card action",
+            LuaCallbackCategory::Other => "other",
+        }
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct Sample {
+    time: Duration,
+    resimulation_time: Duration,
+    calls: u32,
+}
+
+impl Sample {
+    fn add(&mut self, other: Sample) {
+        self.time += other.time;
+        self.resimulation_time += other.resimulation_time;
+        self.calls += other.calls;
+    }
+}
+
+#[derive(Default, Clone, Copy)]
+struct Totals {
+    sample: Sample,
+    peak_frame_time: Duration,
+}
+
+#[derive(Clone)]
+pub struct LuaProfileEntry {
+    pub package_id: PackageId,
+    pub category: LuaCallbackCategory,
+    /// Time spent in this vm during the frame, includes `resimulation_time`
+    pub time: Duration,
+    pub resimulation_time: Duration,
+    pub calls: u32,
+}
+
+/// Tracks time spent running scripts in each vm, time spent in nested calls to other vms is excluded
+#[derive(Default)]
+pub struct LuaProfiler {
+    category: Cell<LuaCallbackCategory>,
+    nested_time_stack: RefCell<Vec<Duration>>,
+    frame_samples: RefCell<HashMap<(usize, LuaCallbackCategory), Sample>>,
+    totals: RefCell<HashMap<(usize, LuaCallbackCategory), Totals>>,
+    frames: Cell<u32>,
+}
+
+impl LuaProfiler {
+    /// Attributes calls to `category` until the returned guard is dropped
+    pub fn scope(&self, category: LuaCallbackCategory) -> LuaProfilerScope<'_> {
+        LuaProfilerScope {
+            profiler: self,
+            previous_category: self.category.replace(category),
+        }
+    }
+
+    pub fn measure<T>(
+        &self,
+        vm_index: usize,
+        resimulating: bool,
+        callback: impl FnOnce() -> T,
+    ) -> T {
+        self.nested_time_stack.borrow_mut().push(Duration::ZERO);
+
+        let start = Instant::now();
+        let value = callback();
+        let elapsed = start.elapsed();
+
+        let mut nested_time_stack = self.nested_time_stack.borrow_mut();
+        let nested_time = nested_time_stack.pop().unwrap_or_default();
+
+        if let Some(parent_nested_time) = nested_time_stack.last_mut() {
+            *parent_nested_time += elapsed;
+        }
+
+        let time = elapsed.saturating_sub(nested_time);
+        let key = (vm_index, self.category.get());
+
+        let mut frame_samples = self.frame_samples.borrow_mut();
+        let sample = frame_samples.entry(key).or_default();
+        sample.time += time;
+        sample.calls += 1;
+
+        if resimulating {
+            sample.resimulation_time += time;
+        }
+
+        value
+    }
+
+    /// Call once per rendered frame, returns the frame's samples sorted by time
+    pub fn end_frame(&self, vms: &[RollbackVM]) -> Vec<LuaProfileEntry> {
+        let frame_samples = std::mem::take(&mut *self.frame_samples.borrow_mut());
+        let mut totals = self.totals.borrow_mut();
+
+        self.frames.set(self.frames.get() + 1);
+
+        let mut entries: Vec<_> = frame_samples
+            .into_iter()
+            .map(|((vm_index, category), sample)| {
+                let vm_totals = totals.entry((vm_index, category)).or_default();
+                vm_totals.sample.add(sample);
+                vm_totals.peak_frame_time = vm_totals.peak_frame_time.max(sample.time);
+
+                LuaProfileEntry {
+                    package_id: vms[vm_index].package_id.clone(),
+                    category,
+                    time: sample.time,
+                    resimulation_time: sample.resimulation_time,
+                    calls: sample.calls,
+                }
+            })
+            .collect();
+
+        entries.sort_by_key(|entry| Reverse(entry.time));
+        entries
+    }
+
+    /// Saves totals for every frame so far as a csv file in the profiles folder
+    pub fn save_report(&self, vms: &[RollbackVM]) {
+        let frames = self.frames.get().max(1);
+
+        let mut totals: Vec<_> = self.totals.borrow().clone().into_iter().collect();
+        totals.sort_by_key(|(_, vm_totals)| Reverse(vm_totals.sample.time));
+
+        let mut text = String::from(
+            "package_id,namespace,category,total_ms,resimulation_ms,calls,average_frame_ms,peak_frame_ms\n",
+        );
+
+        for ((vm_index, category), vm_totals) in totals {
+            let vm = &vms[vm_index];
+            let sample = vm_totals.sample;
+
+            text.push_str(&format!(
+                "{},{:?},{},{:.3},{:.3},{},{:.3},{:.3}\n",
+                vm.package_id,
+                vm.preferred_namespace(),
+                category.as_str(),
+                sample.time.as_secs_f64() * 1000.0,
+                sample.resimulation_time.as_secs_f64() * 1000.0,
+                sample.calls,
+                sample.time.as_secs_f64() * 1000.0 / frames as f64,
+                vm_totals.peak_frame_time.as_secs_f64() * 1000.0,
+            ));
+        }
+
+        let elapsed_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+        let folder_path = format!(
+            "{}{}",
+            ResourcePaths::game_folder(),
+            ResourcePaths::PROFILE_FOLDER
+        );
+        let file_path = format!("{folder_path}{}.csv", elapsed_time.as_secs());
+
+        std::thread::spawn(move || {
+            let _ = std::fs::create_dir_all(&folder_path);
+
+            if let Err(e) = std::fs::write(&file_path, text) {
+                log::error!("Failed to save Lua profile to {:?}: {}", file_path, e);
+            } else {
+                log::info!("Saved Lua profile to {file_path}");
+            }
+        });
+    }
+}
+
+pub struct LuaProfilerScope<'a> {
+    profiler: &'a LuaProfiler,
+    previous_category: LuaCallbackCategory,
+}
+
+impl Drop for LuaProfilerScope<'_> {
+    fn drop(&mut self) {
+        self.profiler.category.set(self.previous_category);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packages::PackageNamespace;
+    use crate::resources::{BATTLE_VM_MEMORY, INPUT_BUFFER_LIMIT};
+
+    fn create_vm(package_id: &str) -> RollbackVM {
+        RollbackVM {
+            package_id: PackageId::from(package_id),
+            namespaces: vec![PackageNamespace::Local],
+            path: String::new(),
+            lua: rollback_mlua::Lua::new_rollback(BATTLE_VM_MEMORY, INPUT_BUFFER_LIMIT),
+            instruction_budget: Default::default(),
+        }
+    }
+
+    fn find_entry<'a>(entries: &'a [LuaProfileEntry], package_id: &str) -> &'a LuaProfileEntry {
+        entries
+            .iter()
+            .find(|entry| entry.package_id == PackageId::from(package_id))
+            .unwrap()
+    }
+
+    #[test]
+    fn excludes_nested_time() {
+        let vms = [create_vm("outer"), create_vm("inner")];
+        let profiler = LuaProfiler::default();
+
+        let start = Instant::now();
+
+        profiler.measure(0, false, || {
+            std::thread::sleep(Duration::from_millis(5));
+
+            profiler.measure(1, false, || {
+                std::thread::sleep(Duration::from_millis(10));
+            });
+        });
+
+        let elapsed = start.elapsed();
+        let entries = profiler.end_frame(&vms);
+        let outer = find_entry(&entries, "outer");
+        let inner = find_entry(&entries, "inner");
+
+        assert!(outer.time >= Duration::from_millis(5));
+        assert!(inner.time >= Duration::from_millis(10));
+        // counting the inner call twice would exceed the real time spent
+        assert!(outer.time + inner.time <= elapsed);
+        assert_eq!((outer.calls, inner.calls), (1, 1));
+
+        // samples are cleared for the next frame
+        assert!(profiler.end_frame(&vms).is_empty());
+    }
+
+    #[test]
+    fn splits_resimulation_time() {
+        let vms = [create_vm("test")];
+        let profiler = LuaProfiler::default();
+
+        {
+            let _scope = profiler.scope(LuaCallbackCategory::Update);
+
+            profiler.measure(0, false, || {
+                std::thread::sleep(Duration::from_millis(5));
+            });
+
+            profiler.measure(0, true, || {
+                std::thread::sleep(Duration::from_millis(5));
+            });
+        }
+
+        profiler.measure(0, true, || {});
+
+        let entries = profiler.end_frame(&vms);
+
+        let update_entry = entries
+            .iter()
+            .find(|entry| entry.category == LuaCallbackCategory::Update)
+            .unwrap();
+
+        assert_eq!(update_entry.calls, 2);
+        assert!(update_entry.resimulation_time >= Duration::from_millis(5));
+        assert!(update_entry.resimulation_time < update_entry.time);
+
+        // the scope no longer applies
+        let other_entry = entries
+            .iter()
+            .find(|entry| entry.category == LuaCallbackCategory::Other)
+            .unwrap();
+
+        assert_eq!(other_entry.calls, 1);
+        assert_eq!(other_entry.resimulation_time, other_entry.time);
+    }
+}
@@ -13,11 +13,15 @@ mod card_select_restriction;
 mod component;
 mod defense_rule;
 mod derived_animation_state;
+mod desync_detector;
 mod ecs_components;
 mod emotion_ui;
 mod field;
+mod headless_battle;
 mod intangibility;
+mod lua_profiler;
 mod ownership_tracking;
+mod playback_timeline;
 mod player_fallback_resources;
 mod player_form;
 mod player_input;
@@ -49,11 +53,15 @@ pub use card_select_restriction::*;
 pub use component::*;
 pub use defense_rule::*;
 pub use derived_animation_state::*;
+pub use desync_detector::*;
 pub use ecs_components::*;
 pub use emotion_ui::*;
 pub use field::*;
+pub use headless_battle::*;
 pub use intangibility::*;
+pub use lua_profiler::*;
 pub use ownership_tracking::*;
+pub use playback_timeline::*;
 pub use player_fallback_resources::*;
 pub use player_form::*;
 pub use player_input::*;
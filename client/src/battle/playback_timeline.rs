@@ -0,0 +1,239 @@
+use super::{BattleSimulation, Character, Living, Player};
+use crate::render::ui::{FontName, Text};
+use crate::render::{FrameTime, SpriteColorQueue};
+use crate::resources::{AssetManager, Globals, ResourcePaths, RESOLUTION_F};
+use framework::prelude::*;
+
+const BAR_MARGIN: f32 = 8.0;
+const BAR_HEIGHT: f32 = 2.0;
+const MARKER_HEIGHT: f32 = 5.0;
+const EVENT_LABEL_DURATION: FrameTime = 60;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum TimelineEventKind {
+    CardUse,
+    Hit,
+}
+
+impl TimelineEventKind {
+    fn color(self) -> Color {
+        match self {
+            TimelineEventKind::CardUse => Color::GREEN,
+            TimelineEventKind::Hit => Color::RED,
+        }
+    }
+}
+
+struct TimelineEvent {
+    time: FrameTime,
+    kind: TimelineEventKind,
+    label: String,
+}
+
+#[derive(Clone)]
+struct TrackedPlayer {
+    index: usize,
+    health: i32,
+    card_count: usize,
+    next_card_name: String,
+}
+
+/// Tracks card use and hits during recording playback, drawn as markers on a seek bar
+pub struct PlaybackTimeline {
+    total_frames: FrameTime,
+    events: Vec<TimelineEvent>,
+    tracked_time: FrameTime,
+    tracked_players: Vec<TrackedPlayer>,
+    sprite: Sprite,
+    text: Text,
+}
+
+impl PlaybackTimeline {
+    pub fn new(game_io: &GameIO, total_frames: FrameTime) -> Self {
+        let globals = game_io.resource::<Globals>().unwrap();
+        let assets = &globals.assets;
+
+        let mut text = Text::new(game_io, FontName::Thin);
+        text.style.shadow_color = Color::BLACK;
+
+        Self {
+            total_frames,
+            events: Vec::new(),
+            tracked_time: 0,
+            tracked_players: Vec::new(),
+            sprite: assets.new_sprite(game_io, ResourcePaths::WHITE_PIXEL),
+            text,
+        }
+    }
+
+    pub fn total_frames(&self) -> FrameTime {
+        self.total_frames
+    }
+
+    /// Should be called after every simulated frame, events are only recorded the first time a frame is seen
+    pub fn track(&mut self, simulation: &mut BattleSimulation) {
+        let time = simulation.time;
+        let record_events = time > self.tracked_time;
+
+        let mut tracked_players: Vec<_> = simulation
+            .entities
+            .query_mut::<(&Player, &Living, Option<&Character>)>()
+            .into_iter()
+            .map(|(_, (player, living, character))| {
+                let cards = character.map(|character| character.cards.as_slice());
+                let cards = cards.unwrap_or_default();
+
+                TrackedPlayer {
+                    index: player.index,
+                    health: living.health,
+                    card_count: cards.len(),
+                    // cards are stored reversed
+                    next_card_name: (cards.last())
+                        .map(|card| card.short_name.to_string())
+                        .unwrap_or_default(),
+                }
+            })
+            .collect();
+
+        tracked_players.sort_by_key(|tracked| tracked.index);
+
+        if record_events {
+            for tracked in &tracked_players {
+                let previous = self
+                    .tracked_players
+                    .iter()
+                    .find(|previous| previous.index == tracked.index);
+
+                let Some(previous) = previous else {
+                    continue;
+                };
+
+                if tracked.card_count < previous.card_count {
+                    self.events.push(TimelineEvent {
+                        time,
+                        kind: TimelineEventKind::CardUse,
+                        label: format!("P{} {}", tracked.index + 1, previous.next_card_name),
+                    });
+                }
+
+                if tracked.health < previous.health {
+                    self.events.push(TimelineEvent {
+                        time,
+                        kind: TimelineEventKind::Hit,
+                        label: format!(
+                            "P{} -{}",
+                            tracked.index + 1,
+                            previous.health - tracked.health
+                        ),
+                    });
+                }
+            }
+
+            self.tracked_time = time;
+        }
+
+        self.tracked_players = tracked_players;
+    }
+
+    pub fn previous_event_time(&self, time: FrameTime) -> Option<FrameTime> {
+        self.events
+            .iter()
+            .rev()
+            .map(|event| event.time)
+            .find(|&event_time| event_time < time)
+    }
+
+    pub fn next_event_time(&self, time: FrameTime) -> Option<FrameTime> {
+        self.events
+            .iter()
+            .map(|event| event.time)
+            .find(|&event_time| event_time > time)
+    }
+
+    pub fn draw(
+        &mut self,
+        game_io: &GameIO,
+        sprite_queue: &mut SpriteColorQueue,
+        time: FrameTime,
+        paused: bool,
+        fast_forwarding: bool,
+    ) {
+        let bar_width = RESOLUTION_F.x - BAR_MARGIN * 2.0;
+        let bar_y = RESOLUTION_F.y - BAR_MARGIN;
+        let total_frames = self.total_frames.max(1) as f32;
+        let time_to_x = |time: FrameTime| BAR_MARGIN + bar_width * time as f32 / total_frames;
+
+        // bar
+        self.sprite.set_color(Color::BLACK.multiply_alpha(0.5));
+        self.sprite
+            .set_bounds(Rect::new(BAR_MARGIN, bar_y, bar_width, BAR_HEIGHT));
+        sprite_queue.draw_sprite(&self.sprite);
+
+        // progress
+        self.sprite.set_color(Color::WHITE);
+        self.sprite.set_bounds(Rect::new(
+            BAR_MARGIN,
+            bar_y,
+            time_to_x(time) - BAR_MARGIN,
+            BAR_HEIGHT,
+        ));
+        sprite_queue.draw_sprite(&self.sprite);
+
+        // event markers
+        let marker_y = bar_y + BAR_HEIGHT - MARKER_HEIGHT;
+
+        for event in &self.events {
+            self.sprite.set_color(event.kind.color());
+            self.sprite.set_bounds(Rect::new(
+                time_to_x(event.time),
+                marker_y,
+                1.0,
+                MARKER_HEIGHT,
+            ));
+            sprite_queue.draw_sprite(&self.sprite);
+        }
+
+        // status
+        let status = if paused {
+            "PAUSED"
+        } else if fast_forwarding {
+            ">>"
+        } else {
+            ">"
+        };
+
+        self.text.text = format!(
+            "{status} {} / {}",
+            format_time(time),
+            format_time(self.total_frames)
+        );
+        self.text.style.color = Color::WHITE;
+
+        let text_size = self.text.measure().size;
+        let text_y = bar_y - MARKER_HEIGHT - text_size.y;
+        (self.text.style.bounds).set_position(Vec2::new(BAR_MARGIN, text_y));
+        self.text.draw(game_io, sprite_queue);
+
+        // label for the latest event
+        let recent_event = self
+            .events
+            .iter()
+            .rev()
+            .find(|event| event.time <= time && time - event.time < EVENT_LABEL_DURATION);
+
+        if let Some(event) = recent_event {
+            self.text.text.clone_from(&event.label);
+            self.text.style.color = event.kind.color();
+
+            let text_size = self.text.measure().size;
+            let text_x = RESOLUTION_F.x - BAR_MARGIN - text_size.x;
+            (self.text.style.bounds).set_position(Vec2::new(text_x, text_y));
+            self.text.draw(game_io, sprite_queue);
+        }
+    }
+}
+
+pub fn format_time(time: FrameTime) -> String {
+    let seconds = time / 60;
+    format!("{}:{:02}.{:02}", seconds / 60, seconds % 60, time % 60)
+}
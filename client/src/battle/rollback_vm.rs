@@ -1,13 +1,25 @@
+use crate::lua_api::InstructionBudget;
 use crate::packages::{PackageId, PackageNamespace};
+use std::sync::Arc;
 
 pub struct RollbackVM {
     pub package_id: PackageId,
     pub namespaces: Vec<PackageNamespace>,
     pub path: String,
     pub lua: rollback_mlua::Lua,
+    /// Shared with the instruction hook, see `limit_instructions`
+    pub instruction_budget: Arc<InstructionBudget>,
 }
 
 impl RollbackVM {
+    pub fn reset_instruction_budget(&self) {
+        self.instruction_budget.reset();
+    }
+
+    pub fn exceeded_instruction_budget(&self) -> bool {
+        self.instruction_budget.exceeded()
+    }
+
     pub fn preferred_namespace(&self) -> PackageNamespace {
         *self
             .namespaces
@@ -12,12 +12,15 @@ use framework::prelude::{Color, GameIO, Sprite, Texture};
 use packets::structures::PackageCategory;
 use std::borrow::Cow;
 use std::cell::{Cell, RefCell};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 
 /// Resources that are shared between battle snapshots
 pub struct SharedBattleResources {
     pub vm_manager: BattleVmManager,
+    pub lua_profiler: LuaProfiler,
+    /// Vms logged for exceeding the instruction budget, kept outside of snapshots to log once
+    pub reported_exhausted_vms: RefCell<HashSet<usize>>,
     pub status_registry: StatusRegistry,
     pub statuses_texture: Arc<Texture>,
     pub statuses_animator: RefCell<Animator>,
@@ -53,6 +56,8 @@ impl SharedBattleResources {
 
         let mut resources = Self {
             vm_manager: BattleVmManager::new(),
+            lua_profiler: LuaProfiler::default(),
+            reported_exhausted_vms: Default::default(),
             status_registry: StatusRegistry::new(),
             statuses_texture: assets.texture(game_io, ResourcePaths::BATTLE_STATUSES),
             statuses_animator: RefCell::new(Animator::load_new(
@@ -8,6 +8,8 @@ use std::borrow::Cow;
 use ui::BattleBannerMessage;
 
 const TOTAL_MESSAGE_TIME: FrameTime = 3 * 60;
+/// Enemies deleted within this many frames of each other count towards a kill chain
+const KILL_CHAIN_WINDOW: FrameTime = 20;
 
 #[derive(Clone)]
 pub struct BattleState {
@@ -15,6 +17,8 @@ pub struct BattleState {
     end_message: BattleBannerMessage,
     complete: bool,
     out_of_time: bool,
+    kill_chain: usize,
+    last_kill_time: FrameTime,
 }
 
 impl State for BattleState {
@@ -157,6 +161,8 @@ impl BattleState {
             end_message: BattleBannerMessage::default(),
             complete: false,
             out_of_time: false,
+            kill_chain: 0,
+            last_kill_time: 0,
         }
     }
 
@@ -259,8 +265,6 @@ impl BattleState {
         }
 
         // detect success
-        // todo: score screen
-
         let enemies_alive = simulation
             .entities
             .query_mut::<(&Entity, &Character)>()
@@ -365,8 +369,12 @@ impl BattleState {
         }
 
         // execute update functions
-        for callback in callbacks {
-            callback.call(game_io, resources, simulation, ());
+        {
+            let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::Update);
+
+            for callback in callbacks {
+                callback.call(game_io, resources, simulation, ());
+            }
         }
     }
 
@@ -540,6 +548,7 @@ impl BattleState {
                 }
 
                 // spell attack callback
+                let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::Attack);
                 attack_callback.call(game_io, resources, simulation, id.into());
             }
         }
@@ -584,20 +593,54 @@ impl BattleState {
         simulation: &mut BattleSimulation,
     ) {
         let mut pending_deletion = Vec::new();
+        let mut enemies_deleted = 0;
+
+        // simulation.local_team is only updated while rendering, read the team from the player instead
+        let local_team = (simulation.entities)
+            .query_one_mut::<&Entity>(simulation.local_player_id.into())
+            .map(|entity| entity.team)
+            .ok();
+
+        type Query<'a> = (&'a Entity, &'a Living, Option<&'a Character>);
 
-        for (id, living) in simulation.entities.query_mut::<&Living>() {
+        for (id, (entity, living, character)) in simulation.entities.query_mut::<Query>() {
             if living.max_health == 0 || living.health > 0 {
                 continue;
             }
 
+            let is_enemy = character.is_some()
+                && local_team.is_some_and(|team| entity.team != team)
+                && entity.team != Team::Other;
+
+            if is_enemy && !entity.deleted {
+                enemies_deleted += 1;
+            }
+
             pending_deletion.push(id);
         }
 
+        if enemies_deleted > 0 {
+            self.track_kill_chain(simulation, enemies_deleted);
+        }
+
         for id in pending_deletion {
             Entity::delete(game_io, resources, simulation, id.into());
         }
     }
 
+    fn track_kill_chain(&mut self, simulation: &mut BattleSimulation, enemies_deleted: usize) {
+        if self.kill_chain > 0 && self.time - self.last_kill_time <= KILL_CHAIN_WINDOW {
+            self.kill_chain += enemies_deleted;
+        } else {
+            self.kill_chain = enemies_deleted;
+        }
+
+        self.last_kill_time = self.time;
+
+        let statistics = &mut simulation.statistics;
+        statistics.max_kill_chain = statistics.max_kill_chain.max(self.kill_chain);
+    }
+
     fn update_artifacts(
         &mut self,
         game_io: &GameIO,
@@ -624,8 +667,12 @@ impl BattleState {
         }
 
         // execute update functions
-        for callback in callbacks {
-            callback.call(game_io, resources, simulation, ());
+        {
+            let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::Update);
+
+            for callback in callbacks {
+                callback.call(game_io, resources, simulation, ());
+            }
         }
     }
 
@@ -830,8 +877,12 @@ impl BattleState {
         }
 
         // execute update functions
-        for callback in callbacks {
-            callback.call(game_io, resources, simulation, ());
+        {
+            let _scope = (resources.lua_profiler).scope(LuaCallbackCategory::Update);
+
+            for callback in callbacks {
+                callback.call(game_io, resources, simulation, ());
+            }
         }
     }
 
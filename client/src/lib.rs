@@ -64,6 +64,10 @@ pub fn main(app: WinitPlatformApp) -> anyhow::Result<()> {
 
     log::info!("Version {}", env!("CARGO_PKG_VERSION"));
 
+    if args.simulate.is_some() {
+        return headless_main(app, args, log_receiver);
+    }
+
     let random_title = TITLE_LIST.choose(&mut rand::thread_rng()).unwrap();
     let game = Game::<WinitGameLoop>::new(random_title, TRUE_RESOLUTION.into())
         .with_platform_app(app)
@@ -90,6 +94,26 @@ pub fn main(app: WinitPlatformApp) -> anyhow::Result<()> {
     Ok(())
 }
 
+// branches off before any post processing, overlays, or window settings are applied
+// the boot scene exits the process after the simulation completes
+fn headless_main(
+    app: WinitPlatformApp,
+    args: Args,
+    log_receiver: flume::Receiver<LogRecord>,
+) -> anyhow::Result<()> {
+    let game = Game::<WinitGameLoop>::new("Hub OS: Simulation", TRUE_RESOLUTION.into())
+        .with_platform_app(app)
+        .with_setup(|game_io| {
+            let globals = Globals::new(game_io, args);
+            game_io.set_resource(globals);
+        })
+        .with_service(SupportingService::new);
+
+    game.run(|game_io| BootScene::new(game_io, log_receiver))?;
+
+    Ok(())
+}
+
 #[cfg(target_os = "android")]
 #[no_mangle]
 pub fn android_main(app: WinitPlatformApp) {
@@ -136,7 +160,7 @@ fn update_resources(app: &WinitPlatformApp) {
     let _ = std::fs::remove_dir_all("resources");
 
     // extract zip
-    zip::extract(zip_bytes, |path, mut file| {
+    let result = zip::extract(zip_bytes, &zip::ExtractionLimits::TRUSTED, |path, file| {
         use std::io::Read;
 
         if !file.is_file() {
@@ -153,6 +177,11 @@ fn update_resources(app: &WinitPlatformApp) {
         std::fs::write(path, &bytes).unwrap();
     });
 
+    if let Err(err) = result {
+        log::error!("Failed to extract resources: {err}");
+        return;
+    }
+
     // update stored hash
     std::fs::write("resources-hash", resources_hash.as_bytes()).unwrap();
 }
@@ -385,6 +385,21 @@ impl BattleLuaApi {
     ) where
         F: FnOnce(&'lua rollback_mlua::Lua) -> rollback_mlua::Result<()>,
     {
+        let (resources, vm_index, resimulating) = {
+            let api_ctx = api_ctx.borrow();
+
+            // skip vms that exceeded their instruction budget
+            if api_ctx.simulation.disabled_vms.contains(&api_ctx.vm_index) {
+                return;
+            }
+
+            (
+                api_ctx.resources,
+                api_ctx.vm_index,
+                api_ctx.simulation.is_resimulation,
+            )
+        };
+
         let dynamic_api = DynamicApiCtx::new(api_ctx, &self.dynamic_functions);
         let old_dynamic_api = lua.set_app_data(Rc::new(unsafe {
             // unsafe if remove_app_data is never called,
@@ -394,7 +409,11 @@ impl BattleLuaApi {
         }));
 
         // call the function
-        if let Err(err) = wrapped_fn(lua) {
+        let result = resources
+            .lua_profiler
+            .measure(vm_index, resimulating, || wrapped_fn(lua));
+
+        if let Err(err) = result {
             log::error!("{err}");
         }
 
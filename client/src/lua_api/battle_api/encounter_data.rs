@@ -0,0 +1,588 @@
+const MAX_DEPTH: usize = 128;
+
+/// Data sent by servers for `encounter_init`, parsed without executing any code.
+///
+/// Accepts the Lua table constructors sent by existing servers as well as JSON.
+/// For compatibility, bare names (`inf`, `null`, `Function`, etc) resolve to nil,
+/// matching how these were resolved when the data was evaluated as Lua.
+#[derive(Debug, Clone, PartialEq)]
+pub enum EncounterData {
+    Nil,
+    Boolean(bool),
+    Integer(i64),
+    Number(f64),
+    String(Vec<u8>),
+    Table(Vec<(EncounterData, EncounterData)>),
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct EncounterDataError {
+    line: usize,
+    column: usize,
+    message: String,
+}
+
+impl std::fmt::Display for EncounterDataError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: {}",
+            self.line, self.column, self.message
+        )
+    }
+}
+
+impl EncounterData {
+    pub fn parse(source: &str) -> Result<Self, EncounterDataError> {
+        let mut parser = Parser {
+            source: source.as_bytes(),
+            index: 0,
+        };
+
+        let value = parser.parse_value(0)?;
+        parser.skip_whitespace();
+
+        if parser.index < parser.source.len() {
+            return Err(parser.error("expected end of data"));
+        }
+
+        Ok(value)
+    }
+}
+
+impl<'lua> rollback_mlua::IntoLua<'lua> for EncounterData {
+    fn into_lua(
+        self,
+        lua: &'lua rollback_mlua::Lua,
+    ) -> rollback_mlua::Result<rollback_mlua::Value<'lua>> {
+        match self {
+            EncounterData::Nil => Ok(rollback_mlua::Value::Nil),
+            EncounterData::Boolean(b) => Ok(rollback_mlua::Value::Boolean(b)),
+            EncounterData::Integer(i) => Ok(rollback_mlua::Value::Integer(i)),
+            EncounterData::Number(n) => Ok(rollback_mlua::Value::Number(n)),
+            EncounterData::String(bytes) => {
+                Ok(rollback_mlua::Value::String(lua.create_string(&bytes)?))
+            }
+            EncounterData::Table(pairs) => {
+                let table = lua.create_table()?;
+
+                for (key, value) in pairs {
+                    table.raw_set(key, value)?;
+                }
+
+                Ok(rollback_mlua::Value::Table(table))
+            }
+        }
+    }
+}
+
+struct Parser<'a> {
+    source: &'a [u8],
+    index: usize,
+}
+
+impl Parser<'_> {
+    fn error(&self, message: &str) -> EncounterDataError {
+        let consumed = &self.source[..self.index.min(self.source.len())];
+        let line_start = consumed
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map(|i| i + 1)
+            .unwrap_or_default();
+
+        EncounterDataError {
+            line: consumed.iter().filter(|&&b| b == b'\n').count() + 1,
+            column: String::from_utf8_lossy(&consumed[line_start..])
+                .chars()
+                .count()
+                + 1,
+            message: message.to_string(),
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.source.get(self.index).cloned()
+    }
+
+    fn peek_at(&self, offset: usize) -> Option<u8> {
+        self.source.get(self.index + offset).cloned()
+    }
+
+    fn skip_whitespace(&mut self) {
+        while self.peek().is_some_and(|b| b.is_ascii_whitespace()) {
+            self.index += 1;
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> Result<(), EncounterDataError> {
+        self.skip_whitespace();
+
+        if self.peek() != Some(b) {
+            return Err(self.error(&format!("expected '{}'", b as char)));
+        }
+
+        self.index += 1;
+        Ok(())
+    }
+
+    fn parse_value(&mut self, depth: usize) -> Result<EncounterData, EncounterDataError> {
+        if depth > MAX_DEPTH {
+            return Err(self.error("data is nested too deeply"));
+        }
+
+        self.skip_whitespace();
+
+        match self.peek() {
+            Some(b'{') => self.parse_table(depth),
+            Some(b'[') => self.parse_array(depth),
+            Some(b'"' | b'\'') => Ok(EncounterData::String(self.parse_string()?)),
+            Some(b'-') => {
+                self.index += 1;
+                self.skip_whitespace();
+
+                if self.peek() == Some(b'-') {
+                    // would be a comment in Lua
+                    return Err(self.error("unexpected '-'"));
+                }
+
+                match self.parse_value(depth + 1)? {
+                    EncounterData::Integer(i) => Ok(EncounterData::Integer(i.wrapping_neg())),
+                    EncounterData::Number(n) => Ok(EncounterData::Number(-n)),
+                    EncounterData::Nil => Ok(EncounterData::Nil),
+                    _ => Err(self.error("expected a number after '-'")),
+                }
+            }
+            Some(b'0'..=b'9' | b'.') => self.parse_number(),
+            Some(b) if b.is_ascii_alphabetic() || b == b'_' => {
+                let name = self.parse_name();
+
+                Ok(match name {
+                    "true" => EncounterData::Boolean(true),
+                    "false" => EncounterData::Boolean(false),
+                    _ => EncounterData::Nil,
+                })
+            }
+            Some(_) => Err(self.error("expected a value")),
+            None => Err(self.error("unexpected end of data")),
+        }
+    }
+
+    fn parse_name(&mut self) -> &str {
+        let start = self.index;
+
+        while self
+            .peek()
+            .is_some_and(|b| b.is_ascii_alphanumeric() || b == b'_')
+        {
+            self.index += 1;
+        }
+
+        // only ascii was consumed
+        std::str::from_utf8(&self.source[start..self.index]).unwrap()
+    }
+
+    fn parse_table(&mut self, depth: usize) -> Result<EncounterData, EncounterDataError> {
+        self.expect(b'{')?;
+
+        let mut pairs = Vec::new();
+        let mut next_index = 1;
+
+        loop {
+            self.skip_whitespace();
+
+            if self.peek() == Some(b'}') {
+                self.index += 1;
+                break;
+            }
+
+            let (key, value) = match self.peek() {
+                // lua: [key] = value
+                Some(b'[') => {
+                    self.index += 1;
+                    let key = self.parse_value(depth + 1)?;
+                    self.expect(b']')?;
+                    self.expect(b'=')?;
+                    (Some(key), self.parse_value(depth + 1)?)
+                }
+                // lua: name = value, or a bare name as a value
+                Some(b) if b.is_ascii_alphabetic() || b == b'_' => {
+                    let start = self.index;
+                    let name = self.parse_name().as_bytes().to_vec();
+                    self.skip_whitespace();
+
+                    if self.peek() == Some(b'=') && self.peek_at(1) != Some(b'=') {
+                        self.index += 1;
+                        let key = EncounterData::String(name);
+                        (Some(key), self.parse_value(depth + 1)?)
+                    } else {
+                        self.index = start;
+                        (None, self.parse_value(depth + 1)?)
+                    }
+                }
+                // json: "key": value
+                Some(b'"') => {
+                    let value = self.parse_value(depth + 1)?;
+                    self.skip_whitespace();
+
+                    if self.peek() == Some(b':') {
+                        self.index += 1;
+                        (Some(value), self.parse_value(depth + 1)?)
+                    } else {
+                        (None, value)
+                    }
+                }
+                _ => (None, self.parse_value(depth + 1)?),
+            };
+
+            let key = match key {
+                Some(EncounterData::Nil) => return Err(self.error("table key is nil")),
+                Some(EncounterData::Number(n)) if n.is_nan() => {
+                    return Err(self.error("table key is NaN"))
+                }
+                Some(key) => key,
+                None => {
+                    let key = EncounterData::Integer(next_index);
+                    next_index += 1;
+                    key
+                }
+            };
+
+            // assigning nil is a no-op for lua tables
+            if value != EncounterData::Nil {
+                pairs.push((key, value));
+            }
+
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',' | b';') => self.index += 1,
+                Some(b'}') => {}
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+
+        Ok(EncounterData::Table(pairs))
+    }
+
+    fn parse_array(&mut self, depth: usize) -> Result<EncounterData, EncounterDataError> {
+        self.expect(b'[')?;
+
+        let mut pairs = Vec::new();
+        let mut next_index = 1;
+
+        loop {
+            self.skip_whitespace();
+
+            if self.peek() == Some(b']') {
+                self.index += 1;
+                break;
+            }
+
+            let value = self.parse_value(depth + 1)?;
+
+            if value != EncounterData::Nil {
+                pairs.push((EncounterData::Integer(next_index), value));
+            }
+
+            next_index += 1;
+
+            self.skip_whitespace();
+
+            match self.peek() {
+                Some(b',') => self.index += 1,
+                Some(b']') => {}
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+
+        Ok(EncounterData::Table(pairs))
+    }
+
+    fn parse_number(&mut self) -> Result<EncounterData, EncounterDataError> {
+        let start = self.index;
+
+        if self.peek() == Some(b'0') && matches!(self.peek_at(1), Some(b'x' | b'X')) {
+            self.index += 2;
+            let digits_start = self.index;
+
+            while self.peek().is_some_and(|b| b.is_ascii_hexdigit()) {
+                self.index += 1;
+            }
+
+            let digits = std::str::from_utf8(&self.source[digits_start..self.index]).unwrap();
+
+            if digits.is_empty() || digits.len() > 16 {
+                self.index = start;
+                return Err(self.error("malformed number"));
+            }
+
+            // hex integers wrap around in lua
+            let value = u64::from_str_radix(digits, 16).unwrap() as i64;
+            return Ok(EncounterData::Integer(value));
+        }
+
+        let mut is_float = false;
+
+        while let Some(b) = self.peek() {
+            match b {
+                b'0'..=b'9' => {}
+                b'.' => is_float = true,
+                b'e' | b'E' => {
+                    is_float = true;
+
+                    if matches!(self.peek_at(1), Some(b'+' | b'-')) {
+                        self.index += 1;
+                    }
+                }
+                _ => break,
+            }
+
+            self.index += 1;
+        }
+
+        let text = std::str::from_utf8(&self.source[start..self.index]).unwrap();
+
+        if !is_float {
+            if let Ok(value) = text.parse::<i64>() {
+                return Ok(EncounterData::Integer(value));
+            }
+        }
+
+        // also handles integers too large for i64, which become floats in lua
+        match text.parse::<f64>() {
+            Ok(value) => Ok(EncounterData::Number(value)),
+            Err(_) => {
+                self.index = start;
+                Err(self.error("malformed number"))
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<Vec<u8>, EncounterDataError> {
+        let quote = self.peek().unwrap();
+        self.index += 1;
+
+        let mut bytes = Vec::new();
+
+        loop {
+            let Some(b) = self.peek() else {
+                return Err(self.error("unfinished string"));
+            };
+
+            self.index += 1;
+
+            match b {
+                b'\n' => return Err(self.error("unfinished string")),
+                b'\\' => self.parse_escape(&mut bytes)?,
+                _ if b == quote => break,
+                _ => bytes.push(b),
+            }
+        }
+
+        Ok(bytes)
+    }
+
+    fn parse_escape(&mut self, bytes: &mut Vec<u8>) -> Result<(), EncounterDataError> {
+        let Some(b) = self.peek() else {
+            return Err(self.error("unfinished string"));
+        };
+
+        self.index += 1;
+
+        match b {
+            b'n' | b'\n' => bytes.push(b'\n'),
+            b't' => bytes.push(b'\t'),
+            b'r' => bytes.push(b'\r'),
+            b'a' => bytes.push(0x07),
+            b'b' => bytes.push(0x08),
+            b'f' => bytes.push(0x0C),
+            b'v' => bytes.push(0x0B),
+            b'\\' | b'"' | b'\'' | b'/' => bytes.push(b),
+            b'z' => self.skip_whitespace(),
+            b'x' => {
+                let value = self
+                    .take_digits(2, 16)
+                    .filter(|(_, len)| *len == 2)
+                    .ok_or_else(|| self.error("hexadecimal digit expected"))?;
+
+                bytes.push(value.0 as u8);
+            }
+            b'0'..=b'9' => {
+                self.index -= 1;
+
+                let (value, _) = self.take_digits(3, 10).unwrap();
+
+                if value > 255 {
+                    return Err(self.error("decimal escape too large"));
+                }
+
+                bytes.push(value as u8);
+            }
+            b'u' => {
+                let code_point = if self.peek() == Some(b'{') {
+                    // lua: \u{XXX}
+                    self.index += 1;
+                    let (value, _) = self
+                        .take_digits(8, 16)
+                        .ok_or_else(|| self.error("hexadecimal digit expected"))?;
+                    self.expect(b'}')?;
+                    value
+                } else {
+                    // json: \uXXXX, with surrogate pairs
+                    let high = self.take_utf16_unit()?;
+
+                    match high {
+                        0xD800..=0xDBFF => {
+                            if self.peek() != Some(b'\\') || self.peek_at(1) != Some(b'u') {
+                                return Err(self.error("unpaired surrogate"));
+                            }
+
+                            self.index += 2;
+                            let low = self.take_utf16_unit()?;
+
+                            if !(0xDC00..=0xDFFF).contains(&low) {
+                                return Err(self.error("invalid surrogate pair"));
+                            }
+
+                            0x10000 + ((high - 0xD800) << 10) + (low - 0xDC00)
+                        }
+                        0xDC00..=0xDFFF => return Err(self.error("unpaired surrogate")),
+                        _ => high,
+                    }
+                };
+
+                let c = char::from_u32(code_point)
+                    .ok_or_else(|| self.error("invalid unicode escape"))?;
+
+                bytes.extend(c.encode_utf8(&mut [0; 4]).as_bytes());
+            }
+            _ => return Err(self.error("invalid escape sequence")),
+        }
+
+        Ok(())
+    }
+
+    fn take_utf16_unit(&mut self) -> Result<u32, EncounterDataError> {
+        self.take_digits(4, 16)
+            .filter(|(_, len)| *len == 4)
+            .map(|(value, _)| value)
+            .ok_or_else(|| self.error("hexadecimal digit expected"))
+    }
+
+    /// Returns the value and the count of digits read
+    fn take_digits(&mut self, max_len: usize, radix: u32) -> Option<(u32, usize)> {
+        let mut value = 0;
+        let mut len = 0;
+
+        while len < max_len {
+            let Some(digit) = self.peek().and_then(|b| (b as char).to_digit(radix)) else {
+                break;
+            };
+
+            value = value * radix + digit;
+            len += 1;
+            self.index += 1;
+        }
+
+        if len == 0 {
+            return None;
+        }
+
+        Some((value, len))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn string(s: &str) -> EncounterData {
+        EncounterData::String(s.as_bytes().to_vec())
+    }
+
+    #[test]
+    fn lua_tables() {
+        // format used by servers
+        let data = EncounterData::parse(r#"{["name"] = "Mettaur",[1] = 5,["hp"] = 40.5}"#);
+
+        assert_eq!(
+            data,
+            Ok(EncounterData::Table(vec![
+                (string("name"), string("Mettaur")),
+                (EncounterData::Integer(1), EncounterData::Integer(5)),
+                (string("hp"), EncounterData::Number(40.5)),
+            ]))
+        );
+
+        let data = EncounterData::parse("{ 1, -2; x = 'a\\n', { true } }");
+
+        assert_eq!(
+            data,
+            Ok(EncounterData::Table(vec![
+                (EncounterData::Integer(1), EncounterData::Integer(1)),
+                (EncounterData::Integer(2), EncounterData::Integer(-2)),
+                (string("x"), string("a\n")),
+                (
+                    EncounterData::Integer(3),
+                    EncounterData::Table(vec![(
+                        EncounterData::Integer(1),
+                        EncounterData::Boolean(true)
+                    )])
+                ),
+            ]))
+        );
+    }
+
+    #[test]
+    fn json() {
+        let data = EncounterData::parse(r#"{"enemies": ["a", null, 3e1], "boss": false}"#);
+
+        assert_eq!(
+            data,
+            Ok(EncounterData::Table(vec![
+                (
+                    string("enemies"),
+                    EncounterData::Table(vec![
+                        (EncounterData::Integer(1), string("a")),
+                        (EncounterData::Integer(3), EncounterData::Number(30.0)),
+                    ])
+                ),
+                (string("boss"), EncounterData::Boolean(false)),
+            ]))
+        );
+
+        assert_eq!(EncounterData::parse(r#""é\u{e9}""#), Ok(string("éé")));
+    }
+
+    #[test]
+    fn surrogate_pairs() {
+        assert_eq!(
+            EncounterData::parse(r#""\uD83D\uDE00\u00e9""#),
+            Ok(string("😀é"))
+        );
+
+        // unpaired
+        assert!(EncounterData::parse(r#""\uD800""#).is_err());
+        assert!(EncounterData::parse(r#""\uD800a""#).is_err());
+        assert!(EncounterData::parse(r#""\uDC00""#).is_err());
+
+        // the second half must be a low surrogate
+        assert!(EncounterData::parse(r#""\uD800\u0041""#).is_err());
+        assert!(EncounterData::parse(r#""\uD800\uD800""#).is_err());
+        assert!(EncounterData::parse(r#""\uD800\uFFFF""#).is_err());
+    }
+
+    #[test]
+    fn code_is_not_executed() {
+        // bare names are treated as nil, calls are rejected
+        assert_eq!(
+            EncounterData::parse("{inf}"),
+            Ok(EncounterData::Table(vec![]))
+        );
+        assert!(EncounterData::parse("os.exit()").is_err());
+        assert!(EncounterData::parse("{(function() end)()}").is_err());
+
+        let err = EncounterData::parse("{\n  x = 1 +\n}").unwrap_err();
+        assert_eq!(err.to_string(), "line 2, column 9: expected ',' or '}'");
+    }
+}
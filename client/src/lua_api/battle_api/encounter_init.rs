@@ -1,3 +1,4 @@
+use super::encounter_data::EncounterData;
 use super::field_api::get_field_table;
 use super::{create_entity_table, BattleLuaApi, ENCOUNTER_TABLE, MUTATOR_TABLE, SPAWNER_TABLE};
 use crate::battle::{BattleInitMusic, BattleScriptContext, Character, Entity};
@@ -7,6 +8,8 @@ use crate::packages::PackageId;
 use crate::render::{Animator, Background};
 use crate::resources::{AssetManager, Globals};
 use framework::prelude::Vec2;
+use packets::structures::BattleReward;
+use rollback_mlua::IntoLua;
 use std::cell::RefCell;
 
 pub fn encounter_init(api_ctx: BattleScriptContext, data: Option<&str>) {
@@ -25,14 +28,13 @@ pub fn encounter_init(api_ctx: BattleScriptContext, data: Option<&str>) {
         }
     };
 
-    let chunk = data.and_then(|data| {
-        let chunk: Option<rollback_mlua::Value> = lua.load(data).eval().ok();
-
-        if chunk.is_none() {
-            log::error!("Failed to read data from server:\n{data}");
+    // parsed as data, evaluating would allow servers to run code outside of their packages
+    let chunk = data.and_then(|data| match EncounterData::parse(data) {
+        Ok(encounter_data) => encounter_data.into_lua(lua).ok(),
+        Err(err) => {
+            log::error!("Malformed data from server at {err}:\n{data}");
+            None
         }
-
-        chunk
     });
 
     let context = RefCell::new(api_ctx);
@@ -167,6 +169,20 @@ pub fn inject_encounter_init_api(lua_api: &mut BattleLuaApi) {
         },
     );
 
+    lua_api.add_dynamic_function(
+        ENCOUNTER_TABLE,
+        "enable_instruction_budget_forfeit",
+        |api_ctx, lua, params| {
+            let (_, enabled): (rollback_mlua::Table, Option<bool>) = lua.unpack_multi(params)?;
+
+            let mut api_ctx = api_ctx.borrow_mut();
+            let simulation = &mut api_ctx.simulation;
+            simulation.config.instruction_budget_forfeit = enabled.unwrap_or(true);
+
+            lua.pack_multi(())
+        },
+    );
+
     lua_api.add_dynamic_function(ENCOUNTER_TABLE, "set_turn_limit", |api_ctx, lua, params| {
         let (_, turn_limit): (rollback_mlua::Table, u32) = lua.unpack_multi(params)?;
 
@@ -209,6 +225,34 @@ pub fn inject_encounter_init_api(lua_api: &mut BattleLuaApi) {
 
         lua.pack_multi(())
     });
+
+    lua_api.add_dynamic_function(ENCOUNTER_TABLE, "add_reward", |api_ctx, lua, params| {
+        let (_, text): (rollback_mlua::Table, String) = lua.unpack_multi(params)?;
+
+        let mut api_ctx = api_ctx.borrow_mut();
+        let simulation = &mut api_ctx.simulation;
+
+        (simulation.statistics.rewards).push(BattleReward::Text(text));
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function(
+        ENCOUNTER_TABLE,
+        "add_reward_item",
+        |api_ctx, lua, params| {
+            let (_, name, count): (rollback_mlua::Table, String, Option<isize>) =
+                lua.unpack_multi(params)?;
+
+            let mut api_ctx = api_ctx.borrow_mut();
+            let simulation = &mut api_ctx.simulation;
+
+            let count = count.unwrap_or(1);
+            (simulation.statistics.rewards).push(BattleReward::Item { name, count });
+
+            lua.pack_multi(())
+        },
+    );
 }
 
 fn inject_spawner_api(lua_api: &mut BattleLuaApi) {
@@ -7,6 +7,7 @@ mod card_select_api;
 mod card_select_button_api;
 mod component_api;
 mod defense_rule_api;
+mod encounter_data;
 mod encounter_init;
 mod entity_api;
 mod errors;
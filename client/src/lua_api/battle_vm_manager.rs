@@ -5,11 +5,60 @@ use crate::battle::{
 use crate::bindable::EntityId;
 use crate::packages::{PackageInfo, PackageNamespace};
 use crate::resources::{
-    AssetManager, Globals, ResourcePaths, BATTLE_VM_MEMORY, INPUT_BUFFER_LIMIT,
+    AssetManager, Globals, ResourcePaths, BATTLE_VM_INSTRUCTION_BUDGET, BATTLE_VM_MEMORY,
+    INPUT_BUFFER_LIMIT,
 };
 use framework::prelude::GameIO;
 use packets::structures::PackageId;
 use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+use std::sync::Arc;
+
+// how often the instruction budget is checked, a smaller interval is more precise but slower
+const INSTRUCTION_HOOK_INTERVAL: u32 = 1000;
+
+/// Instructions a vm can run each frame, shared with the vm's instruction hook
+pub struct InstructionBudget {
+    remaining: AtomicU32,
+    /// Set once a script is interrupted, protected calls rethrow until the budget is reset
+    exceeded: AtomicBool,
+}
+
+impl Default for InstructionBudget {
+    fn default() -> Self {
+        Self {
+            remaining: AtomicU32::new(BATTLE_VM_INSTRUCTION_BUDGET),
+            exceeded: AtomicBool::new(false),
+        }
+    }
+}
+
+impl InstructionBudget {
+    pub fn reset(&self) {
+        self.remaining
+            .store(BATTLE_VM_INSTRUCTION_BUDGET, Ordering::Relaxed);
+        self.exceeded.store(false, Ordering::Relaxed);
+    }
+
+    pub fn exceeded(&self) -> bool {
+        self.exceeded.load(Ordering::Relaxed)
+    }
+
+    /// Returns false once the budget is spent
+    fn consume(&self, instructions: u32) -> bool {
+        let remaining = self.remaining.load(Ordering::Relaxed);
+
+        if remaining == 0 {
+            self.exceeded.store(true, Ordering::Relaxed);
+            return false;
+        }
+
+        let remaining = remaining.saturating_sub(instructions);
+        self.remaining.store(remaining, Ordering::Relaxed);
+
+        true
+    }
+}
 
 #[derive(Default)]
 pub struct InternalScripts {
@@ -107,6 +156,9 @@ impl BattleVmManager {
         )
         .unwrap();
 
+        let instruction_budget = Arc::new(InstructionBudget::default());
+        limit_instructions(&lua, &package_info.id, instruction_budget.clone()).unwrap();
+
         let vm = RollbackVM {
             lua,
             package_id: package_info.id.clone(),
@@ -116,6 +168,7 @@ impl BattleVmManager {
                 vec![namespace, package_info.namespace]
             },
             path: package_info.script_path.clone(),
+            instruction_budget,
         };
 
         let vms = &mut resources.vm_manager.vms;
@@ -172,6 +225,13 @@ impl BattleVmManager {
         Ok(vm_index)
     }
 
+    /// Called at the start of every frame, including resimulated frames
+    pub fn reset_instruction_budgets(&self) {
+        for vm in &self.vms {
+            vm.reset_instruction_budget();
+        }
+    }
+
     pub fn snap(&mut self) {
         for vm in &mut self.vms {
             vm.lua.snap();
@@ -232,6 +292,60 @@ impl BattleVmManager {
     }
 }
 
+/// Interrupts scripts that run past the budget, instruction counts are deterministic so every client interrupts at the same point.
+/// `pcall` and `xpcall` rethrow the interruption, scripts can't recover until the budget is reset
+fn limit_instructions(
+    lua: &rollback_mlua::Lua,
+    package_id: &PackageId,
+    budget: Arc<InstructionBudget>,
+) -> rollback_mlua::Result<()> {
+    let error_message = format!("{package_id:?} exceeded the instruction budget for this frame");
+
+    let hook_budget = budget.clone();
+    let hook_error_message = error_message.clone();
+
+    lua.set_hook(
+        rollback_mlua::HookTriggers::new().every_nth_instruction(INSTRUCTION_HOOK_INTERVAL),
+        move |_, _| {
+            if !hook_budget.consume(INSTRUCTION_HOOK_INTERVAL) {
+                return Err(rollback_mlua::Error::RuntimeError(
+                    hook_error_message.clone(),
+                ));
+            }
+
+            Ok(())
+        },
+    );
+
+    let globals = lua.globals();
+
+    for name in ["pcall", "xpcall"] {
+        let Some(function) = globals.get::<_, Option<rollback_mlua::Function>>(name)? else {
+            continue;
+        };
+
+        let key = lua.create_registry_value(function)?;
+        let budget = budget.clone();
+        let error_message = error_message.clone();
+
+        let guarded_function =
+            lua.create_function(move |lua, args: rollback_mlua::MultiValue| {
+                let function: rollback_mlua::Function = lua.registry_value(&key)?;
+                let results: rollback_mlua::MultiValue = function.call(args)?;
+
+                if budget.exceeded() {
+                    return Err(rollback_mlua::Error::RuntimeError(error_message.clone()));
+                }
+
+                Ok(results)
+            })?;
+
+        globals.set(name, guarded_function)?;
+    }
+
+    Ok(())
+}
+
 fn load_root_script(
     lua: &rollback_mlua::Lua,
     assets: &impl AssetManager,
@@ -253,3 +367,52 @@ fn load_root_script(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_limited_lua() -> (rollback_mlua::Lua, Arc<InstructionBudget>) {
+        let lua = rollback_mlua::Lua::new_rollback(BATTLE_VM_MEMORY, INPUT_BUFFER_LIMIT);
+        let budget = Arc::new(InstructionBudget::default());
+
+        limit_instructions(&lua, &PackageId::from("test"), budget.clone()).unwrap();
+
+        (lua, budget)
+    }
+
+    #[test]
+    fn protected_calls_cannot_catch_exhaustion() {
+        let (lua, budget) = create_limited_lua();
+
+        let result = lua
+            .load("while true do pcall(function() while true do end end) end")
+            .exec();
+
+        assert!(result.is_err());
+        assert!(budget.exceeded());
+
+        budget.reset();
+        assert!(!budget.exceeded());
+
+        let result = lua
+            .load("while true do xpcall(function() while true do end end, print) end")
+            .exec();
+
+        assert!(result.is_err());
+        assert!(budget.exceeded());
+    }
+
+    #[test]
+    fn protected_calls_catch_script_errors() {
+        let (lua, budget) = create_limited_lua();
+
+        let caught: bool = lua
+            .load("return not pcall(error, 'expected')")
+            .eval()
+            .unwrap();
+
+        assert!(caught);
+        assert!(!budget.exceeded());
+    }
+}
@@ -1,7 +1,9 @@
 // not really a scene, but similar
 
-use crate::render::Camera;
-use crate::resources::{Globals, RESOLUTION_F};
+use crate::bindable::SpriteColorMode;
+use crate::render::ui::{FontName, Text};
+use crate::render::{Camera, SpriteColorQueue};
+use crate::resources::{Globals, RESOLUTION_F, TEXT_TRANSPARENT_SHADOW_COLOR};
 use framework::prelude::*;
 use std::collections::VecDeque;
 
@@ -9,12 +11,16 @@ pub struct DebugOverlay {
     camera: Camera,
     rectangle: FlatModel,
     history: VecDeque<f32>,
+    network_text: Text,
+    profile_text: Text,
     last_key_pressed: Option<Key>,
 }
 
 const RECT_WIDTH: usize = 1;
 const RECT_HEIGHT: usize = 16;
 const ALPHA: f32 = 0.95;
+const TEXT_MARGIN: f32 = 2.0;
+const MAX_PROFILE_LINES: usize = 8;
 
 impl DebugOverlay {
     pub fn new(game_io: &GameIO) -> Self {
@@ -24,14 +30,82 @@ impl DebugOverlay {
         let mut rectangle = FlatModel::new_square_model(game_io);
         rectangle.set_origin(Vec2::new(-0.5, 0.5));
 
+        let mut network_text = Text::new(game_io, FontName::Thin);
+        network_text.style.color = Color::WHITE;
+        network_text.style.shadow_color = TEXT_TRANSPARENT_SHADOW_COLOR;
+        network_text
+            .style
+            .bounds
+            .set_position(Vec2::new(TEXT_MARGIN, TEXT_MARGIN));
+
+        let mut profile_text = Text::new(game_io, FontName::Thin);
+        profile_text.style.color = Color::WHITE;
+        profile_text.style.shadow_color = TEXT_TRANSPARENT_SHADOW_COLOR;
+
         Self {
             camera,
             rectangle,
             history: VecDeque::new(),
+            network_text,
+            profile_text,
             last_key_pressed: None,
         }
     }
 
+    fn update_network_text(&mut self, globals: &Globals) {
+        self.network_text.text.clear();
+
+        for (address, stats) in globals.network.connection_stats() {
+            let line = format!(
+                "{address}\n RTT: {}ms +-{}ms LOSS: {:.1}%\n UP: {:.1}KB/s ACKED: {:.1}KB/s WINDOW: {}/{}KB\n",
+                stats.rtt.as_millis(),
+                stats.rtt_variance.as_millis(),
+                stats.packet_loss * 100.0,
+                stats.send_rate / 1024.0,
+                stats.delivery_rate / 1024.0,
+                stats.bytes_in_flight / 1024,
+                stats.congestion_window / 1024,
+            );
+
+            self.network_text.text.push_str(&line);
+        }
+    }
+
+    fn update_profile_text(&mut self, globals: &Globals) {
+        self.profile_text.text.clear();
+
+        if globals.battle_lua_profile.is_empty() {
+            return;
+        }
+
+        self.profile_text.text.push_str("Lua (F3 + P to save)\n");
+
+        for entry in globals.battle_lua_profile.iter().take(MAX_PROFILE_LINES) {
+            let line = format!(
+                " {} {}: {:.2}ms RESIM: {:.2}ms CALLS: {}\n",
+                entry.package_id,
+                entry.category.as_str(),
+                entry.time.as_secs_f32() * 1000.0,
+                entry.resimulation_time.as_secs_f32() * 1000.0,
+                entry.calls,
+            );
+
+            self.profile_text.text.push_str(&line);
+        }
+
+        // place below the connection stats
+        let network_text_height = if self.network_text.text.is_empty() {
+            0.0
+        } else {
+            self.network_text.measure().size.y
+        };
+
+        self.profile_text
+            .style
+            .bounds
+            .set_position(Vec2::new(TEXT_MARGIN, TEXT_MARGIN + network_text_height));
+    }
+
     fn detect_debug_hotkeys(&self, game_io: &GameIO) {
         let input = game_io.input();
 
@@ -131,5 +205,15 @@ impl GameOverlay for DebugOverlay {
         queue.draw_model(&self.rectangle);
 
         render_pass.consume_queue(queue);
+
+        // draw connection stats and battle profile
+        self.update_network_text(globals);
+        self.update_profile_text(globals);
+
+        let mut sprite_queue =
+            SpriteColorQueue::new(game_io, &self.camera, SpriteColorMode::Multiply);
+        self.network_text.draw(game_io, &mut sprite_queue);
+        self.profile_text.draw(game_io, &mut sprite_queue);
+        render_pass.consume_queue(sprite_queue);
     }
 }
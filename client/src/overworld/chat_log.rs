@@ -0,0 +1,62 @@
+use crate::render::ui::LogBox;
+use crate::render::SpriteColorQueue;
+use crate::resources::RESOLUTION_F;
+use framework::logging::{LogLevel, LogRecord};
+use framework::prelude::*;
+
+const HISTORY_LIMIT: usize = 64;
+
+/// How long new messages stay on screen while the chat is closed
+const DISPLAY_DURATION: FrameTime = 60 * 6;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub enum ChatLineKind {
+    Area,
+    Whisper,
+    Notice,
+}
+
+pub struct ChatLog {
+    log_box: LogBox,
+    last_line_time: Option<FrameTime>,
+}
+
+impl ChatLog {
+    pub fn new(game_io: &GameIO) -> Self {
+        Self {
+            log_box: LogBox::new(game_io, Self::bounds()).with_record_limit(HISTORY_LIMIT),
+            last_line_time: None,
+        }
+    }
+
+    /// The area covered by the log, the chat input is placed just below
+    pub fn bounds() -> Rect {
+        Rect::new(4.0, RESOLUTION_F.y - 76.0, RESOLUTION_F.x - 8.0, 52.0)
+    }
+
+    pub fn push_line(&mut self, time: FrameTime, kind: ChatLineKind, text: String) {
+        // reusing log levels for coloring
+        let level = match kind {
+            ChatLineKind::Area => LogLevel::Info,
+            ChatLineKind::Whisper => LogLevel::Warn,
+            ChatLineKind::Notice => LogLevel::Debug,
+        };
+
+        self.log_box.push_record(LogRecord {
+            level,
+            message: text,
+            target: String::new(),
+        });
+
+        self.last_line_time = Some(time);
+    }
+
+    pub fn has_recent_lines(&self, time: FrameTime) -> bool {
+        self.last_line_time
+            .is_some_and(|last_time| time - last_time < DISPLAY_DURATION)
+    }
+
+    pub fn draw(&self, game_io: &GameIO, sprite_queue: &mut SpriteColorQueue) {
+        self.log_box.draw(game_io, sprite_queue);
+    }
+}
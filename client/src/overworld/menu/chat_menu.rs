@@ -0,0 +1,154 @@
+use super::Menu;
+use crate::overworld::{ChatLog, OverworldArea, OverworldEvent};
+use crate::render::ui::{
+    build_9patch, FontName, NinePatch, TextInput, Textbox, UiInputTracker, UiLayout, UiLayoutNode,
+    UiStyle,
+};
+use crate::render::{Animator, SpriteColorQueue};
+use crate::resources::{AssetManager, Globals, ResourcePaths, RESOLUTION_F};
+use framework::prelude::*;
+use packets::structures::MAX_CHAT_MESSAGE_LEN;
+
+const INPUT_HEIGHT: f32 = 16.0;
+
+pub struct ChatMenu {
+    background_sprite: Sprite,
+    input_9patch: NinePatch,
+    input_layout: Option<UiLayout>,
+    event_sender: flume::Sender<String>,
+    event_receiver: flume::Receiver<String>,
+    ui_input_tracker: UiInputTracker,
+    open: bool,
+}
+
+impl ChatMenu {
+    pub fn new(game_io: &GameIO) -> Self {
+        let globals = game_io.resource::<Globals>().unwrap();
+        let assets = &globals.assets;
+
+        // darken the log for readability
+        let log_bounds = ChatLog::bounds();
+        let mut background_sprite = assets.new_sprite(game_io, ResourcePaths::WHITE_PIXEL);
+        background_sprite.set_color(Color::BLACK.multiply_alpha(0.5));
+        background_sprite.set_position(Vec2::new(0.0, log_bounds.y - 2.0));
+        background_sprite.set_size(Vec2::new(
+            RESOLUTION_F.x,
+            log_bounds.height + INPUT_HEIGHT + 6.0,
+        ));
+
+        // reusing the emote menu's search box
+        let mut ui_animator =
+            Animator::load_new(assets, ResourcePaths::OVERWORLD_EMOTE_UI_ANIMATION);
+        ui_animator.set_state("SEARCH");
+
+        let input_texture = assets.texture(game_io, ResourcePaths::OVERWORLD_EMOTE_UI);
+        let input_9patch = build_9patch!(game_io, input_texture, &ui_animator, "SEARCH_BOX");
+
+        // events
+        let (event_sender, event_receiver) = flume::unbounded();
+
+        Self {
+            background_sprite,
+            input_9patch,
+            input_layout: None,
+            event_sender,
+            event_receiver,
+            ui_input_tracker: UiInputTracker::new(),
+            open: false,
+        }
+    }
+
+    fn input_bounds() -> Rect {
+        let log_bounds = ChatLog::bounds();
+
+        Rect::new(
+            log_bounds.x,
+            log_bounds.bottom() + 2.0,
+            log_bounds.width,
+            INPUT_HEIGHT,
+        )
+    }
+}
+
+impl Menu for ChatMenu {
+    fn is_fullscreen(&self) -> bool {
+        false
+    }
+
+    fn is_open(&self) -> bool {
+        self.open
+    }
+
+    fn open(&mut self, game_io: &mut GameIO, _area: &mut OverworldArea) {
+        self.open = true;
+
+        let event_sender = self.event_sender.clone();
+
+        self.input_layout = Some(UiLayout::new_horizontal(
+            Self::input_bounds(),
+            vec![UiLayoutNode::new(
+                TextInput::new(game_io, FontName::ThinSmall)
+                    .with_color(Color::WHITE)
+                    .with_character_limit(MAX_CHAT_MESSAGE_LEN)
+                    .with_silent(true)
+                    .with_active(true)
+                    .on_change(move |value| event_sender.send(value.to_string()).unwrap()),
+            )
+            .with_style(UiStyle {
+                flex_grow: 1.0,
+                flex_shrink: 1.0,
+                nine_patch: Some(self.input_9patch.clone()),
+                ..Default::default()
+            })],
+        ));
+    }
+
+    fn update(&mut self, _game_io: &mut GameIO, _area: &mut OverworldArea) {}
+
+    fn handle_input(
+        &mut self,
+        game_io: &mut GameIO,
+        area: &mut OverworldArea,
+        _textbox: &mut Textbox,
+    ) {
+        self.ui_input_tracker.update(game_io);
+
+        if let Some(layout) = &mut self.input_layout {
+            layout.update(game_io, &self.ui_input_tracker);
+        }
+
+        // the text input reports its value when it closes, for both submitting and cancelling
+        let Ok(message) = self.event_receiver.try_recv() else {
+            return;
+        };
+
+        let input = game_io.input();
+        let submitted =
+            input.was_key_just_pressed(Key::Return) || input.is_key_repeated(Key::Return);
+
+        if submitted && !message.trim().is_empty() {
+            area.event_sender
+                .send(OverworldEvent::ChatSubmitted(message))
+                .unwrap();
+        }
+
+        self.input_layout = None;
+        self.open = false;
+    }
+
+    fn draw(
+        &mut self,
+        game_io: &GameIO,
+        _render_pass: &mut RenderPass,
+        sprite_queue: &mut SpriteColorQueue,
+        area: &OverworldArea,
+    ) {
+        sprite_queue.draw_sprite(&self.background_sprite);
+
+        area.chat_log.draw(game_io, sprite_queue);
+
+        if let Some(layout) = &mut self.input_layout {
+            layout.draw(game_io, sprite_queue);
+        }
+    }
+}
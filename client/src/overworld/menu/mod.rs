@@ -1,4 +1,5 @@
 mod bbs;
+mod chat_menu;
 mod emote_menu;
 mod items_menu;
 mod map_menu;
@@ -7,6 +8,7 @@ mod overworld_menu_manager;
 mod shop;
 
 pub use bbs::*;
+pub use chat_menu::*;
 pub use emote_menu::*;
 pub use items_menu::*;
 pub use map_menu::*;
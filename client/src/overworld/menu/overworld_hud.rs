@@ -7,6 +7,7 @@ use framework::prelude::*;
 pub struct OverworldHud {
     visible: bool,
     map_name_visible: bool,
+    chat_visible: bool,
     health_ui: PlayerHealthUi,
 }
 
@@ -15,6 +16,7 @@ impl OverworldHud {
         Self {
             visible: true,
             map_name_visible: true,
+            chat_visible: true,
             health_ui: PlayerHealthUi::new(game_io)
                 .with_max_health(health)
                 .with_health(health),
@@ -29,13 +31,22 @@ impl OverworldHud {
         self.map_name_visible = visible;
     }
 
+    pub fn set_chat_visible(&mut self, visible: bool) {
+        self.chat_visible = visible;
+    }
+
     pub fn update(&mut self, area: &OverworldArea) {
         self.health_ui.set_health(area.player_data.health);
         self.health_ui.set_max_health(area.player_data.max_health());
         self.health_ui.update();
     }
 
-    pub fn draw(&self, game_io: &GameIO, sprite_queue: &mut SpriteColorQueue, map: &Map) {
+    pub fn draw(
+        &self,
+        game_io: &GameIO,
+        sprite_queue: &mut SpriteColorQueue,
+        area: &OverworldArea,
+    ) {
         if !self.visible {
             return;
         }
@@ -44,7 +55,12 @@ impl OverworldHud {
         draw_clock(game_io, sprite_queue);
 
         if self.map_name_visible {
-            draw_map_name(game_io, sprite_queue, map);
+            draw_map_name(game_io, sprite_queue, &area.map);
+        }
+
+        // briefly display new chat messages
+        if self.chat_visible && area.chat_log.has_recent_lines(area.world_time) {
+            area.chat_log.draw(game_io, sprite_queue);
         }
     }
 }
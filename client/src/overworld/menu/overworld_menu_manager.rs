@@ -79,7 +79,7 @@ impl OverworldMenuManager {
                 SceneOption::Items,
                 SceneOption::Library,
                 SceneOption::Character,
-                // SceneOption::Email,
+                SceneOption::Email,
                 SceneOption::KeyItems,
                 SceneOption::BattleSelect,
                 SceneOption::Config,
@@ -419,6 +419,11 @@ impl OverworldMenuManager {
                     }
                 });
 
+        if let Some(SceneOption::Email) = navigation_selection {
+            // the scene owns the server assets used for mugs
+            area.event_sender.send(OverworldEvent::OpenMail).unwrap();
+        }
+
         if let Some(SceneOption::Items) = navigation_selection {
             let menu_event_sender = self.event_sender.clone();
             let area_event_sender = area.event_sender.clone();
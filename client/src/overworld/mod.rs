@@ -1,5 +1,6 @@
 mod background_properties;
 mod camera_controller;
+mod chat_log;
 pub mod components;
 mod custom_properties;
 mod identity;
@@ -19,6 +20,7 @@ mod tiles;
 
 pub use background_properties::*;
 pub use camera_controller::*;
+pub use chat_log::*;
 pub use custom_properties::*;
 pub use identity::*;
 pub use map::*;
@@ -22,6 +22,7 @@ pub struct OverworldArea {
     pub event_receiver: flume::Receiver<OverworldEvent>,
     pub world_time: FrameTime,
     pub visible: bool,
+    pub chat_log: ChatLog,
     input_locks: usize,
     background: Background,
     foreground: Background,
@@ -77,6 +78,7 @@ impl OverworldArea {
             world_time: 0,
             input_locks: 0,
             visible: false,
+            chat_log: ChatLog::new(game_io),
             background: Background::new_blank(game_io),
             foreground: Background::new_blank(game_io),
             camera_controller: CameraController::new(player_entity),
@@ -10,7 +10,11 @@ pub enum OverworldEvent {
     },
     Callback(Box<dyn FnOnce(&mut GameIO, &mut OverworldArea) + Send + Sync>),
     EmoteSelected(String),
+    ChatSubmitted(String),
     ItemUse(String),
+    OpenMail,
+    MailRead(String),
+    MailClaim(String),
     TextboxResponse(u8),
     PromptResponse(String),
     BattleStatistics(Option<BattleStatistics>),
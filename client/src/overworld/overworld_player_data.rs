@@ -3,7 +3,7 @@ use crate::packages::PackageNamespace;
 use crate::resources::Globals;
 use crate::saves::BlockGrid;
 use framework::prelude::{GameIO, Vec3};
-use packets::structures::{ActorId, Inventory, PackageId};
+use packets::structures::{ActorId, Inventory, Mail, PackageId};
 
 pub struct OverworldPlayerData {
     pub entity: hecs::Entity,
@@ -14,6 +14,7 @@ pub struct OverworldPlayerData {
     pub emotion: Emotion,
     pub money: u32,
     pub inventory: Inventory,
+    pub mail: Vec<Mail>,
     pub actor_interaction: Option<ActorId>,
     pub object_interaction: Option<u32>,
     pub tile_interaction: Option<Vec3>,
@@ -30,6 +31,7 @@ impl OverworldPlayerData {
             emotion: Emotion::default(),
             money: 0,
             inventory: Inventory::new(),
+            mail: Vec::new(),
             actor_interaction: None,
             object_interaction: None,
             tile_interaction: None,
@@ -195,6 +195,36 @@ impl Package for CardPackage {
 }
 
 impl CardPackage {
+    /// Display name for menus, `card_properties.short_name` stays untranslated for recipes and scripts
+    pub fn localized_name(&self, language: &str) -> &str {
+        self.package_info
+            .translation(language)
+            .and_then(|translation| translation.name.as_deref())
+            .unwrap_or(&self.card_properties.short_name)
+    }
+
+    pub fn localized_description(&self, language: &str) -> &str {
+        self.package_info
+            .translation(language)
+            .and_then(|translation| translation.description.as_deref())
+            .unwrap_or(&self.description)
+    }
+
+    /// Falls back to the description when there's no long description
+    pub fn localized_long_description(&self, language: &str) -> &str {
+        let translation = self.package_info.translation(language);
+
+        let long_description = translation
+            .and_then(|translation| translation.long_description.as_deref())
+            .unwrap_or(&self.long_description);
+
+        if long_description.is_empty() {
+            self.localized_description(language)
+        } else {
+            long_description
+        }
+    }
+
     // used in netplay, luckily we shouldnt see what remotes have, so using local namespace is fine
     pub fn draw_icon(
         game_io: &GameIO,
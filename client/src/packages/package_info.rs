@@ -1,6 +1,8 @@
 use super::*;
 use crate::resources::{AssetManager, LocalAssetManager, ResourcePaths};
 use packets::structures::FileHash;
+use serde::Deserialize;
+use std::collections::HashMap;
 
 pub struct ChildPackageInfo {
     pub parent_type: PackageCategory,
@@ -9,6 +11,15 @@ pub struct ChildPackageInfo {
     pub path: String,
 }
 
+/// Read from `[package.translations.<language>]`, missing fields fall back to the untranslated value
+#[derive(Deserialize, Default, Clone)]
+#[serde(default)]
+pub struct PackageTranslation {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub long_description: Option<String>,
+}
+
 #[derive(Default, Clone)]
 pub struct PackageInfo {
     pub id: PackageId,
@@ -21,6 +32,7 @@ pub struct PackageInfo {
     pub parent_package: Option<(PackageCategory, PackageId)>, // stores id, namespace should be the same
     pub child_id_path_pairs: Vec<(PackageId, String)>, // stores id and entry path, namespace should be the same
     pub requirements: Vec<(PackageCategory, PackageId)>, // stores id, namespace should be the same or fallback
+    pub translations: HashMap<String, PackageTranslation>, // stores language code and translation
 }
 
 impl PackageInfo {
@@ -32,6 +44,10 @@ impl PackageInfo {
         self.base_path.starts_with(ResourcePaths::VIRTUAL_PREFIX)
     }
 
+    pub fn translation(&self, language: &str) -> Option<&PackageTranslation> {
+        self.translations.get(language)
+    }
+
     pub fn child_packages(&self) -> impl Iterator<Item = ChildPackageInfo> + '_ {
         self.child_id_path_pairs
             .iter()
@@ -54,6 +70,11 @@ impl PackageInfo {
             return None;
         }
 
+        self.parse_toml_text(&toml_text)
+    }
+
+    /// Same as `parse_toml`, for package.toml files read outside of the asset manager
+    pub(crate) fn parse_toml_text(&mut self, toml_text: &str) -> Option<toml::Table> {
         let meta_table: toml::Table = match toml_text.parse() {
             Ok(toml) => toml,
             Err(e) => {
@@ -92,6 +113,16 @@ impl PackageInfo {
 
         self.id = package_table.get("id")?.as_str()?.to_string().into();
 
+        if let Some(translations) = package_table.get("translations") {
+            match translations.clone().try_into() {
+                Ok(translations) => self.translations = translations,
+                Err(e) => log::error!(
+                    "Failed to parse translations in {:?}:\n{e}",
+                    ResourcePaths::shorten(&self.toml_path)
+                ),
+            }
+        }
+
         Some(package_table)
     }
 
@@ -193,6 +193,7 @@ impl<T: Package> PackageManager<T> {
             parent_package: None,
             child_id_path_pairs: Vec::new(),
             requirements: Vec::new(),
+            translations: HashMap::new(),
         })
     }
 
@@ -98,3 +98,13 @@ impl Package for PlayerPackage {
         package
     }
 }
+
+impl PlayerPackage {
+    /// Display name for menus, `name` stays untranslated for scripts
+    pub fn localized_name(&self, language: &str) -> &str {
+        self.package_info
+            .translation(language)
+            .and_then(|translation| translation.name.as_deref())
+            .unwrap_or(&self.name)
+    }
+}
@@ -1,13 +1,19 @@
-use super::PackageNamespace;
+use super::{PackageInfo, PackageNamespace};
 use crate::render::ui::PackageListing;
 use crate::resources::{Globals, ResourcePaths};
 use framework::prelude::{AsyncTask, GameIO};
 use packets::address_parsing::uri_encode;
-use packets::structures::{PackageCategory, PackageId};
+use packets::structures::{FileHash, PackageCategory, PackageId};
+use packets::zip::ExtractionLimits;
+use std::path::Path;
+
+/// Lists packages swapped in by an install that hasn't finished, see `swap_in_packages`
+const INSTALL_JOURNAL: &str = "install_journal.txt";
 
 enum Event {
     ReceivedListing(Box<PackageListing>),
-    InstallPackage,
+    /// Includes the staged package's requirements
+    PackageStaged(Vec<(PackageCategory, PackageId)>),
     Failed,
     Success,
 }
@@ -26,7 +32,7 @@ pub struct RepoPackageUpdater {
     task: Option<AsyncTask<Event>>,
     queue: Vec<PackageId>,
     queue_position: usize,
-    install_required: Vec<(PackageCategory, PackageId, PackageId)>,
+    install_required: Vec<(PackageCategory, PackageId, PackageId, FileHash)>,
     install_position: usize,
 }
 
@@ -54,7 +60,7 @@ impl RepoPackageUpdater {
         self.queue_position
     }
 
-    pub fn begin<I>(&mut self, game_io: &GameIO, into_iter: I)
+    pub fn begin<I>(&mut self, game_io: &mut GameIO, into_iter: I)
     where
         I: IntoIterator<Item = PackageId>,
     {
@@ -98,12 +104,13 @@ impl RepoPackageUpdater {
 
                     let requires_update = existing_hash != Some(listing.hash);
                     let already_updating =
-                        (self.install_required.iter()).any(|(_, _, id)| *id == latest_id);
+                        (self.install_required.iter()).any(|(_, _, id, _)| *id == latest_id);
 
                     if requires_update && !already_updating {
                         // save package id for install pass
                         let old_id = id.clone();
-                        self.install_required.push((category, old_id, latest_id));
+                        self.install_required
+                            .push((category, old_id, latest_id, listing.hash));
                     }
                 }
 
@@ -111,10 +118,27 @@ impl RepoPackageUpdater {
                 self.queue_position += 1;
                 self.request_latest_listing(game_io);
             }
-            Event::InstallPackage => {
-                self.install_package(game_io);
+            Event::PackageStaged(requirements) => {
+                self.install_position += 1;
+
+                // queue missing dependencies, the repo listing may not match the package's own list
+                let globals = game_io.resource::<Globals>().unwrap();
+
+                for (category, id) in requirements {
+                    let installed = PackageNamespace::Local
+                        .find_with_fallback(|ns| globals.package_info(category, ns, &id))
+                        .is_some();
+
+                    if !installed && !self.queue.contains(&id) {
+                        self.queue.push(id);
+                    }
+                }
+
+                // continue working on queue
+                self.request_latest_listing(game_io);
             }
             Event::Failed => {
+                self.discard_staged_packages();
                 self.status = UpdateStatus::Failed;
             }
             Event::Success => {
@@ -123,7 +147,7 @@ impl RepoPackageUpdater {
         }
     }
 
-    fn request_latest_listing(&mut self, game_io: &GameIO) {
+    fn request_latest_listing(&mut self, game_io: &mut GameIO) {
         let Some(id) = self.queue.get(self.queue_position) else {
             self.download_package(game_io);
             return;
@@ -151,11 +175,11 @@ impl RepoPackageUpdater {
         self.status = UpdateStatus::CheckingForUpdate;
     }
 
-    fn download_package(&mut self, game_io: &GameIO) {
-        let Some(&(category, ref old_id, ref id)) =
-            self.install_required.get(self.install_position)
+    fn download_package(&mut self, game_io: &mut GameIO) {
+        let Some((_, _, id, expected_hash)) = self.install_required.get(self.install_position)
         else {
-            self.status = UpdateStatus::Success;
+            // every package and requirement is staged
+            self.install_packages(game_io);
             return;
         };
 
@@ -164,7 +188,8 @@ impl RepoPackageUpdater {
         let encoded_id = uri_encode(id.as_str());
 
         let uri = format!("{repo}/api/mods/{encoded_id}");
-        let base_path = globals.resolve_package_download_path(category, old_id);
+        let expected_hash = *expected_hash;
+        let staging_path = staging_path(expected_hash);
 
         let task = game_io.spawn_local_task(async move {
             let Some(zip_bytes) = crate::http::request(&uri).await else {
@@ -172,52 +197,391 @@ impl RepoPackageUpdater {
                 return Event::Failed;
             };
 
-            let _ = std::fs::remove_dir_all(&base_path);
-
-            packets::zip::extract(&zip_bytes, |path, mut virtual_file| {
-                let path = format!("{base_path}{path}");
+            let hash = FileHash::hash(&zip_bytes);
 
-                if let Some(parent_path) = ResourcePaths::parent(&path) {
-                    if let Err(err) = std::fs::create_dir_all(parent_path) {
-                        log::error!("Failed to create directory {parent_path:?}: {}", err);
-                    }
-                }
-
-                let res = std::fs::File::create(&path)
-                    .and_then(|mut file| std::io::copy(&mut virtual_file, &mut file));
+            if hash != expected_hash {
+                log::error!("Downloaded {uri:?} with hash {hash}, expected {expected_hash}");
+                return Event::Failed;
+            }
 
-                if let Err(err) = res {
-                    log::error!("Failed to write to {path:?}: {}", err);
-                }
-            });
+            if let Err(err) = stage_zip(&zip_bytes, &staging_path) {
+                log::error!("Failed to stage {uri:?}: {err}");
+                return Event::Failed;
+            }
 
-            Event::InstallPackage
+            Event::PackageStaged(staged_requirements(&staging_path))
         });
 
         self.task = Some(task);
         self.status = UpdateStatus::DownloadingPackage;
     }
 
-    fn install_package(&mut self, game_io: &mut GameIO) {
-        let (category, old_id, new_id) = &self.install_required[self.install_position];
-        let category = *category;
-        self.install_position += 1;
+    /// Swaps in every staged package at once, the installed packages are restored if any swap fails
+    fn install_packages(&mut self, game_io: &mut GameIO) {
+        if self.install_required.is_empty() {
+            self.status = UpdateStatus::Success;
+            return;
+        }
 
-        // reload package
         let globals = game_io.resource_mut::<Globals>().unwrap();
-        let path = globals.resolve_package_download_path(category, old_id);
 
-        globals.unload_package(category, PackageNamespace::Local, old_id);
-        globals.load_package(category, PackageNamespace::Local, &path);
+        let staged_packages: Vec<_> = (self.install_required.iter())
+            .map(|(category, old_id, _, hash)| StagedPackage {
+                staging_path: staging_path(*hash),
+                base_path: globals.resolve_package_download_path(*category, old_id),
+            })
+            .collect();
 
-        // update save
-        if old_id != new_id {
-            let global_save = &mut globals.global_save;
-            global_save.update_package_id(old_id, new_id);
-            global_save.save();
+        let staging_folder = ResourcePaths::PACKAGE_STAGING_FOLDER;
+
+        if let Err(err) = swap_in_packages(staging_folder, &staged_packages) {
+            log::error!("Failed to install packages: {err}");
+            self.status = UpdateStatus::Failed;
+            return;
         }
 
-        // continue working on queue
-        self.request_latest_listing(game_io);
+        for ((category, old_id, new_id, _), staged_package) in
+            self.install_required.iter().zip(staged_packages)
+        {
+            // reload package
+            globals.unload_package(*category, PackageNamespace::Local, old_id);
+            globals.load_package(
+                *category,
+                PackageNamespace::Local,
+                &staged_package.base_path,
+            );
+
+            // update save
+            if old_id != new_id {
+                let global_save = &mut globals.global_save;
+                global_save.update_package_id(old_id, new_id);
+                global_save.save();
+            }
+        }
+
+        finish_install(staging_folder);
+
+        self.status = UpdateStatus::Success;
+    }
+
+    fn discard_staged_packages(&self) {
+        for (_, _, _, hash) in &self.install_required[..self.install_position] {
+            let _ = std::fs::remove_dir_all(staging_path(*hash));
+        }
+    }
+}
+
+fn staging_path(hash: FileHash) -> String {
+    format!("{}{hash}/", ResourcePaths::PACKAGE_STAGING_FOLDER)
+}
+
+/// Restores packages left behind by an update that was interrupted, call before loading packages
+pub fn recover_interrupted_installs() {
+    roll_back_install(ResourcePaths::PACKAGE_STAGING_FOLDER);
+}
+
+/// Extracts the package into the staging path, clearing files from an interrupted install
+fn stage_zip(zip_bytes: &[u8], staging_path: &str) -> Result<(), String> {
+    let _ = std::fs::remove_dir_all(staging_path);
+
+    if let Err(err) = std::fs::create_dir_all(staging_path) {
+        return Err(format!("Failed to create {staging_path:?}: {err}"));
+    }
+
+    let mut write_error = None;
+
+    let limits = ExtractionLimits::PACKAGE;
+    let result = packets::zip::extract(zip_bytes, &limits, |path, virtual_file| {
+        if write_error.is_some() {
+            return;
+        }
+
+        let path = format!("{staging_path}{path}");
+
+        if let Some(parent_path) = ResourcePaths::parent(&path) {
+            if let Err(err) = std::fs::create_dir_all(parent_path) {
+                write_error = Some(format!("Failed to create {parent_path:?}: {err}"));
+                return;
+            }
+        }
+
+        let res = std::fs::File::create(&path)
+            .and_then(|mut file| std::io::copy(virtual_file, &mut file));
+
+        if let Err(err) = res {
+            write_error = Some(format!("Failed to write to {path:?}: {err}"));
+        }
+    });
+
+    let error = match result {
+        Err(err) => Some(err.to_string()),
+        Ok(()) => write_error,
+    };
+
+    if let Some(error) = error {
+        let _ = std::fs::remove_dir_all(staging_path);
+        return Err(error);
+    }
+
+    Ok(())
+}
+
+/// Requirements read from a staged package.toml, excluding packages defined by the package itself
+fn staged_requirements(staging_path: &str) -> Vec<(PackageCategory, PackageId)> {
+    let mut package_info = PackageInfo {
+        base_path: staging_path.to_string(),
+        toml_path: format!("{staging_path}package.toml"),
+        ..Default::default()
+    };
+
+    let toml_text = std::fs::read_to_string(&package_info.toml_path).unwrap_or_default();
+
+    if package_info.parse_toml_text(&toml_text).is_none() {
+        return Vec::new();
+    }
+
+    let PackageInfo {
+        requirements,
+        child_id_path_pairs,
+        ..
+    } = package_info;
+
+    requirements
+        .into_iter()
+        .filter(|(_, id)| {
+            !child_id_path_pairs
+                .iter()
+                .any(|(child_id, _)| child_id == id)
+        })
+        .collect()
+}
+
+struct StagedPackage {
+    staging_path: String,
+    base_path: String,
+}
+
+impl StagedPackage {
+    fn staging_dir(&self) -> &str {
+        self.staging_path.trim_end_matches('/')
+    }
+
+    fn base_dir(&self) -> &str {
+        self.base_path.trim_end_matches('/')
+    }
+
+    fn backup_dir(&self) -> String {
+        format!("{}.old", self.staging_dir())
+    }
+}
+
+/// Swaps the staged packages with the installed packages, restoring every installed package if
+/// any swap fails. Replaced packages are kept as backups until `finish_install`, and are restored
+/// by `recover_interrupted_installs` if the game closes first
+fn swap_in_packages(staging_folder: &str, packages: &[StagedPackage]) -> Result<(), String> {
+    // record what to restore before moving anything, fresh installs have no backup
+    let mut journal = String::new();
+
+    for package in packages {
+        let backup_dir = if Path::new(package.base_dir()).exists() {
+            package.backup_dir()
+        } else {
+            String::new()
+        };
+
+        journal.push_str(&format!("{}\t{backup_dir}\n", package.base_dir()));
+    }
+
+    let journal_path = format!("{staging_folder}{INSTALL_JOURNAL}");
+
+    if let Err(err) = std::fs::write(&journal_path, journal) {
+        return Err(format!("Failed to write {journal_path:?}: {err}"));
+    }
+
+    for package in packages {
+        if let Err(err) = swap_in_package(package) {
+            roll_back_install(staging_folder);
+            return Err(err);
+        }
+    }
+
+    Ok(())
+}
+
+fn swap_in_package(package: &StagedPackage) -> Result<(), String> {
+    let staging_dir = package.staging_dir();
+    let base_dir = package.base_dir();
+    let backup_dir = package.backup_dir();
+
+    if Path::new(base_dir).exists() {
+        if let Err(err) = std::fs::rename(base_dir, &backup_dir) {
+            return Err(format!("Failed to move {base_dir:?}: {err}"));
+        }
+    } else if let Some(parent_path) = ResourcePaths::parent(base_dir) {
+        let _ = std::fs::create_dir_all(parent_path);
+    }
+
+    if let Err(err) = std::fs::rename(staging_dir, base_dir) {
+        return Err(format!(
+            "Failed to move {staging_dir:?} to {base_dir:?}: {err}"
+        ));
+    }
+
+    Ok(())
+}
+
+/// Deletes the backups and leftover files once every package is swapped in
+fn finish_install(staging_folder: &str) {
+    // the install is final once the journal is gone
+    let _ = std::fs::remove_file(format!("{staging_folder}{INSTALL_JOURNAL}"));
+    let _ = std::fs::remove_dir_all(staging_folder);
+}
+
+/// Restores the packages recorded by `swap_in_packages`, and clears the staging folder
+fn roll_back_install(staging_folder: &str) {
+    let journal_path = format!("{staging_folder}{INSTALL_JOURNAL}");
+    let journal = std::fs::read_to_string(&journal_path).unwrap_or_default();
+    let mut restored_every_package = true;
+
+    for line in journal.lines() {
+        let Some((base_dir, backup_dir)) = line.split_once('\t') else {
+            continue;
+        };
+
+        if backup_dir.is_empty() {
+            // the package wasn't installed before
+            let _ = std::fs::remove_dir_all(base_dir);
+        } else if Path::new(backup_dir).exists() {
+            let _ = std::fs::remove_dir_all(base_dir);
+
+            if let Err(err) = std::fs::rename(backup_dir, base_dir) {
+                log::error!("Failed to restore {base_dir:?} from {backup_dir:?}: {err}");
+                restored_every_package = false;
+            }
+        }
+    }
+
+    if restored_every_package {
+        // keep the journal and backups otherwise, to try again on the next launch
+        let _ = std::fs::remove_file(&journal_path);
+        let _ = std::fs::remove_dir_all(staging_folder);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_folder(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!(
+            "repo_package_updater_{name}_{}",
+            std::process::id()
+        ));
+
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).unwrap();
+
+        format!("{}/", path.to_str().unwrap())
+    }
+
+    fn write_package(path: &str, text: &str) {
+        std::fs::create_dir_all(path).unwrap();
+        std::fs::write(format!("{path}entry.lua"), text).unwrap();
+    }
+
+    fn read_package(path: &str) -> Option<String> {
+        std::fs::read_to_string(format!("{path}entry.lua")).ok()
+    }
+
+    fn create_package(
+        folder: &str,
+        name: &str,
+        installed: Option<&str>,
+        staged: Option<&str>,
+    ) -> StagedPackage {
+        let package = StagedPackage {
+            staging_path: format!("{folder}staging/{name}/"),
+            base_path: format!("{folder}mods/{name}/"),
+        };
+
+        if let Some(text) = installed {
+            write_package(&package.base_path, text);
+        }
+
+        if let Some(text) = staged {
+            write_package(&package.staging_path, text);
+        }
+
+        package
+    }
+
+    #[test]
+    fn swaps_in_staged_packages() {
+        let folder = create_test_folder("swap");
+        let staging_folder = format!("{folder}staging/");
+
+        let packages = [
+            create_package(&folder, "a", Some("old a"), Some("new a")),
+            create_package(&folder, "b", None, Some("new b")),
+        ];
+
+        swap_in_packages(&staging_folder, &packages).unwrap();
+
+        assert_eq!(read_package(&packages[0].base_path).unwrap(), "new a");
+        assert_eq!(read_package(&packages[1].base_path).unwrap(), "new b");
+
+        // backups are kept until the install is finished
+        assert!(Path::new(&packages[0].backup_dir()).exists());
+
+        finish_install(&staging_folder);
+
+        assert!(!Path::new(&staging_folder).exists());
+        assert_eq!(read_package(&packages[0].base_path).unwrap(), "new a");
+
+        let _ = std::fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn rolls_back_every_package_when_a_swap_fails() {
+        let folder = create_test_folder("rollback");
+        let staging_folder = format!("{folder}staging/");
+
+        let packages = [
+            create_package(&folder, "a", Some("old a"), Some("new a")),
+            // missing staged files will fail to swap in
+            create_package(&folder, "b", Some("old b"), None),
+            create_package(&folder, "c", None, Some("new c")),
+        ];
+
+        assert!(swap_in_packages(&staging_folder, &packages).is_err());
+
+        assert_eq!(read_package(&packages[0].base_path).unwrap(), "old a");
+        assert_eq!(read_package(&packages[1].base_path).unwrap(), "old b");
+        assert_eq!(read_package(&packages[2].base_path), None);
+        assert!(!Path::new(&staging_folder).exists());
+
+        let _ = std::fs::remove_dir_all(folder);
+    }
+
+    #[test]
+    fn recovers_interrupted_installs() {
+        let folder = create_test_folder("recover");
+        let staging_folder = format!("{folder}staging/");
+
+        let packages = [
+            create_package(&folder, "a", Some("old a"), Some("new a")),
+            create_package(&folder, "b", None, Some("new b")),
+        ];
+
+        swap_in_packages(&staging_folder, &packages).unwrap();
+
+        // closing the game before finish_install, then starting it again
+        roll_back_install(&staging_folder);
+
+        assert_eq!(read_package(&packages[0].base_path).unwrap(), "old a");
+        assert_eq!(read_package(&packages[1].base_path), None);
+        assert!(!Path::new(&staging_folder).exists());
+
+        let _ = std::fs::remove_dir_all(folder);
     }
 }
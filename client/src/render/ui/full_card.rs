@@ -239,7 +239,8 @@ impl FullCard {
 
             // draw description
             if let Some(package) = package {
-                let description = &package.description;
+                let globals = game_io.resource::<Globals>().unwrap();
+                let description = package.localized_description(globals.localization.language());
 
                 self.description_style
                     .draw(game_io, sprite_queue, description);
@@ -7,6 +7,7 @@ pub struct LogBox {
     bounds: Rect,
     text_style: TextStyle,
     records: Vec<LogRecord>,
+    record_limit: usize,
     error_count: usize,
     warning_count: usize,
 }
@@ -20,11 +21,18 @@ impl LogBox {
             text_style,
             bounds,
             records: Vec::new(),
+            record_limit: usize::MAX,
             error_count: 0,
             warning_count: 0,
         }
     }
 
+    /// Older lines are dropped past this limit
+    pub fn with_record_limit(mut self, limit: usize) -> Self {
+        self.record_limit = limit;
+        self
+    }
+
     pub fn error_count(&self) -> usize {
         self.error_count
     }
@@ -54,12 +62,18 @@ impl LogBox {
         });
 
         self.records.extend(new_records);
+
+        if self.records.len() > self.record_limit {
+            let excess = self.records.len() - self.record_limit;
+            self.records.drain(..excess);
+        }
     }
 
-    pub fn draw(&mut self, game_io: &GameIO, sprite_queue: &mut SpriteColorQueue) {
-        self.text_style.bounds.x = self.bounds.x;
+    pub fn draw(&self, game_io: &GameIO, sprite_queue: &mut SpriteColorQueue) {
+        let mut text_style = self.text_style.clone();
+        text_style.bounds.x = self.bounds.x;
 
-        let line_height = self.text_style.line_height();
+        let line_height = text_style.line_height();
         let max_lines = (self.bounds.height / line_height) as usize;
 
         let bottom = self.bounds.top() + line_height * self.records.len().min(max_lines) as f32;
@@ -76,11 +90,11 @@ impl LogBox {
                 _ => Color::WHITE,
             };
 
-            self.text_style.color = color;
+            text_style.color = color;
 
-            self.text_style.bounds.y = bottom - i as f32 * line_height - line_height;
+            text_style.bounds.y = bottom - i as f32 * line_height - line_height;
 
-            self.text_style.draw(game_io, sprite_queue, &record.message);
+            text_style.draw(game_io, sprite_queue, &record.message);
         }
     }
 }
@@ -14,6 +14,7 @@ pub enum SceneOption {
     Items,
     Library,
     Character,
+    Email,
     KeyItems,
     BattleSelect,
     Config,
@@ -46,6 +47,7 @@ impl SceneOption {
             SceneOption::Items => "ITEMS_LABEL",
             SceneOption::Library => "LIBRARY_LABEL",
             SceneOption::Character => "CHARACTER_LABEL",
+            SceneOption::Email => "MAIL_LABEL",
             SceneOption::KeyItems => "KEY_ITEMS_LABEL",
             SceneOption::BattleSelect => "BATTLE_SELECT_LABEL",
             SceneOption::Config => "CONFIG_LABEL",
@@ -59,6 +61,7 @@ impl SceneOption {
             SceneOption::Items => "ITEMS_LABEL_BLINK",
             SceneOption::Library => "LIBRARY_LABEL_BLINK",
             SceneOption::Character => "CHARACTER_LABEL_BLINK",
+            SceneOption::Email => "MAIL_LABEL_BLINK",
             SceneOption::KeyItems => "KEY_ITEMS_LABEL_BLINK",
             SceneOption::BattleSelect => "BATTLE_SELECT_LABEL_BLINK",
             SceneOption::Config => "CONFIG_LABEL_BLINK",
@@ -66,7 +69,7 @@ impl SceneOption {
     }
 
     fn uses_false_scene(&self) -> bool {
-        matches!(self, SceneOption::Items)
+        matches!(self, SceneOption::Items | SceneOption::Email)
     }
 }
 
@@ -1,5 +1,6 @@
 use super::{ElementSprite, FontName, TextStyle, UiNode};
 use crate::bindable::CardClass;
+use crate::packages::PackageTranslation;
 use crate::render::ui::PackagePreviewData;
 use crate::render::{Animator, SpriteColorQueue};
 use crate::resources::{AssetManager, Globals, ResourcePaths};
@@ -18,6 +19,27 @@ pub struct PackageListing {
     pub dependencies: Vec<(PackageCategory, PackageId)>,
 }
 
+impl PackageListing {
+    pub fn with_translation(mut self, translation: Option<&PackageTranslation>) -> Self {
+        let Some(translation) = translation else {
+            return self;
+        };
+
+        if let Some(name) = &translation.name {
+            self.name.clone_from(name);
+        }
+
+        let description =
+            (translation.long_description.as_ref()).or(translation.description.as_ref());
+
+        if let Some(description) = description {
+            self.description.clone_from(description);
+        }
+
+        self
+    }
+}
+
 impl From<&json::Value> for PackageListing {
     fn from(value: &json::Value) -> Self {
         let Some(package_table) = value.get("package") else {
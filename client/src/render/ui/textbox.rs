@@ -290,12 +290,17 @@ impl Textbox {
             .map(|pair| (&*pair.texture, &*pair.animation))
             .unwrap_or_default();
 
-        let mut animator = Animator::load_new(assets, animation_path);
+        let animator = Animator::load_new(assets, animation_path);
+        let sprite = assets.new_sprite(game_io, texture_path);
+
+        self.set_next_avatar_sprite(animator, sprite);
+    }
+
+    /// For avatars loaded ahead of time from an asset manager that won't be available later
+    pub fn set_next_avatar_sprite(&mut self, mut animator: Animator, sprite: Sprite) {
         animator.set_state("IDLE");
         animator.set_loop_mode(AnimatorLoopMode::Loop);
 
-        let sprite = assets.new_sprite(game_io, texture_path);
-
         if let Some((_, _, 0)) = self.avatar_queue.back() {
             // no interface is using this avatar, we'll just drop it
             self.avatar_queue.pop_back();
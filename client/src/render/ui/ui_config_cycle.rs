@@ -13,7 +13,7 @@ struct LockedState {
 }
 
 pub struct UiConfigCycle<T> {
-    name: &'static str,
+    name: String,
     selection: usize,
     options: Vec<(String, T)>,
     config: Rc<RefCell<Config>>,
@@ -23,14 +23,14 @@ pub struct UiConfigCycle<T> {
 
 impl<T: Copy + PartialEq> UiConfigCycle<T> {
     pub fn new(
-        name: &'static str,
+        name: &str,
         value: T,
         config: Rc<RefCell<Config>>,
         options: &[(&str, T)],
         callback: impl Fn(&mut GameIO, RefMut<Config>, T, bool) + 'static,
     ) -> Self {
         Self {
-            name,
+            name: name.to_string(),
             selection: options
                 .iter()
                 .position(|(_, v)| *v == value)
@@ -58,7 +58,7 @@ impl<T: Copy> UiNode for UiConfigCycle<T> {
         text_style.bounds.set_position(bounds.position());
 
         // draw name
-        text_style.draw(game_io, sprite_queue, self.name);
+        text_style.draw(game_io, sprite_queue, &self.name);
 
         // draw value
         let text = &self.options[self.selection].0;
@@ -13,7 +13,7 @@ struct LockedState {
 }
 
 pub struct UiConfigPercentage {
-    name: &'static str,
+    name: String,
     value: u8,
     value_text: String,
     lower_bound: u8,
@@ -27,13 +27,13 @@ pub struct UiConfigPercentage {
 
 impl UiConfigPercentage {
     pub fn new(
-        name: &'static str,
+        name: &str,
         value: u8,
         config: Rc<RefCell<Config>>,
         callback: impl Fn(&mut GameIO, RefMut<Config>, u8) + 'static,
     ) -> Self {
         Self {
-            name,
+            name: name.to_string(),
             value,
             value_text: Self::generate_value_text(value),
             lower_bound: 0,
@@ -78,7 +78,7 @@ impl UiNode for UiConfigPercentage {
         text_style.bounds.set_position(bounds.position());
 
         // draw name
-        text_style.draw(game_io, sprite_queue, self.name);
+        text_style.draw(game_io, sprite_queue, &self.name);
 
         // draw value
         let metrics = text_style.measure(&self.value_text);
@@ -7,7 +7,7 @@ use std::cell::{RefCell, RefMut};
 use std::rc::Rc;
 
 pub struct UiConfigToggle {
-    name: &'static str,
+    name: String,
     value: bool,
     config: Rc<RefCell<Config>>,
     callback: Box<dyn Fn(&mut GameIO, RefMut<Config>) -> bool>,
@@ -15,13 +15,13 @@ pub struct UiConfigToggle {
 
 impl UiConfigToggle {
     pub fn new(
-        name: &'static str,
+        name: &str,
         value: bool,
         config: Rc<RefCell<Config>>,
         callback: impl Fn(&mut GameIO, RefMut<Config>) -> bool + 'static,
     ) -> Self {
         Self {
-            name,
+            name: name.to_string(),
             config,
             value,
             callback: Box::new(callback),
@@ -41,7 +41,7 @@ impl UiNode for UiConfigToggle {
         text_style.bounds.set_position(bounds.position());
 
         // draw name
-        text_style.draw(game_io, sprite_queue, self.name);
+        text_style.draw(game_io, sprite_queue, &self.name);
 
         // draw value
         let text = if self.value { "true" } else { "false" };
@@ -19,7 +19,15 @@ pub struct AudioManager {
 
 impl AudioManager {
     pub fn new(name: &str) -> Self {
-        let mut audio_manager = Self {
+        let mut audio_manager = Self::new_silent();
+        audio_manager.use_device(name);
+
+        audio_manager
+    }
+
+    /// Creates an AudioManager without an output device, sounds will be ignored
+    pub fn new_silent() -> Self {
+        Self {
             stream: None,
             stream_handle: None,
             sfx_sinks: RefCell::new(Default::default()),
@@ -27,11 +35,7 @@ impl AudioManager {
             music_stack: RefCell::new(vec![(SoundBuffer::new_empty(), false)]),
             music_volume: 1.0,
             sfx_volume: 1.0,
-        };
-
-        audio_manager.use_device(name);
-
-        audio_manager
+        }
     }
 
     pub fn use_device(&mut self, name: &str) {
@@ -111,6 +111,9 @@ impl BootThread {
     }
 
     fn load_packages(&mut self) {
+        // restore packages from an update that didn't finish
+        recover_interrupted_installs();
+
         // load players
         let player_packages = self.load_category(PackageCategory::Player, "Players");
 
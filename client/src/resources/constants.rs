@@ -2,9 +2,12 @@ use crate::render::{CameraMotion, FrameTime};
 use framework::prelude::{Color, UVec2, Vec2};
 
 pub const DEFAULT_PACKAGE_REPO: &str = "https://hubos.dev";
+pub const DEFAULT_LANGUAGE: &str = "en";
 
 // 1 MiB
 pub const BATTLE_VM_MEMORY: usize = 1024 * 1024;
+// instructions each battle vm can run per frame, must match between netplay clients
+pub const BATTLE_VM_INSTRUCTION_BUDGET: u32 = 1_000_000;
 pub const INPUT_BUFFER_LIMIT: usize = 20;
 pub const IDENTITY_LEN: usize = 32;
 
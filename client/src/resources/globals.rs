@@ -1,5 +1,5 @@
 use crate::args::Args;
-use crate::battle::BattleProps;
+use crate::battle::{BattleProps, HeadlessBattleConfig, LuaProfileEntry};
 use crate::lua_api::BattleLuaApi;
 use crate::packages::*;
 use crate::render::ui::{GlyphAtlas, PackageListing};
@@ -39,6 +39,7 @@ pub struct Globals {
 
     // recording
     pub battle_recording: Option<(BattleProps, BattleRecording)>,
+    pub headless_battle_config: Option<HeadlessBattleConfig>,
 
     // sounds
     pub audio: AudioManager,
@@ -48,6 +49,8 @@ pub struct Globals {
     // assets
     pub assets: LocalAssetManager,
     pub glyph_atlas: Arc<GlyphAtlas>,
+    pub localization: Localization,
+    pub languages: Vec<LanguageInfo>,
 
     // shaders
     pub sprite_pipeline_collection: SpritePipelineCollection,
@@ -64,6 +67,8 @@ pub struct Globals {
 
     // debug
     pub debug_visible: bool,
+    /// Updated every frame by the active battle, see `LuaProfiler`
+    pub battle_lua_profile: Vec<LuaProfileEntry>,
 }
 
 impl Globals {
@@ -91,17 +96,30 @@ impl Globals {
         let music_volume = config.music_volume();
         let sfx_volume = config.sfx_volume();
 
-        let audio = AudioManager::new(&config.audio_device)
-            .with_music_volume(music_volume)
-            .with_sfx_volume(sfx_volume);
+        // load translations, resource packages may provide their own locale files
+        let package_locale_folders = (global_save.resource_package_order.iter())
+            .filter(|(_, enabled)| *enabled)
+            .flat_map(|(id, _)| resource_packages.package(PackageNamespace::Local, id))
+            .map(|package| package.package_info.base_path.clone() + ResourcePaths::LOCALES_FOLDER);
 
-        let window = game_io.window_mut();
-        window.set_fullscreen(config.fullscreen);
-        window.set_integer_scaling(config.integer_scaling);
+        let mut locale_folders = vec![ResourcePaths::LOCALES_FOLDER.to_string()];
+        locale_folders.extend(package_locale_folders);
 
-        if config.lock_aspect_ratio {
-            window.lock_resolution(TRUE_RESOLUTION);
-        }
+        let languages =
+            Localization::find_languages(&assets, locale_folders.iter().map(String::as_str));
+        let localization = Localization::load(&assets, &config.language);
+
+        let headless_battle_config = HeadlessBattleConfig::from_args(&args);
+
+        let audio = if headless_battle_config.is_some() {
+            AudioManager::new_silent()
+        } else {
+            AudioManager::new(&config.audio_device)
+        };
+
+        let audio = audio
+            .with_music_volume(music_volume)
+            .with_sfx_volume(sfx_volume);
 
         let snap_resize = config.snap_resize;
         let post_process_adjust_config = PostProcessAdjustConfig::from_config(&config);
@@ -113,9 +131,20 @@ impl Globals {
         let enable_color_blindness =
             config.color_blindness < PostProcessColorBlindness::TOTAL_OPTIONS;
 
-        game_io.set_post_process_enabled::<PostProcessAdjust>(enable_adjustment);
-        game_io.set_post_process_enabled::<PostProcessGhosting>(enable_ghosting);
-        game_io.set_post_process_enabled::<PostProcessColorBlindness>(enable_color_blindness);
+        // headless simulations don't register post processing or display anything
+        if headless_battle_config.is_none() {
+            let window = game_io.window_mut();
+            window.set_fullscreen(config.fullscreen);
+            window.set_integer_scaling(config.integer_scaling);
+
+            if config.lock_aspect_ratio {
+                window.lock_resolution(TRUE_RESOLUTION);
+            }
+
+            game_io.set_post_process_enabled::<PostProcessAdjust>(enable_adjustment);
+            game_io.set_post_process_enabled::<PostProcessGhosting>(enable_ghosting);
+            game_io.set_post_process_enabled::<PostProcessColorBlindness>(enable_color_blindness);
+        }
 
         Self {
             config,
@@ -139,6 +168,7 @@ impl Globals {
 
             // recording
             battle_recording: None,
+            headless_battle_config,
 
             // sounds
             audio,
@@ -148,6 +178,8 @@ impl Globals {
             // assets
             glyph_atlas: Arc::new(GlyphAtlas::new_default(game_io, &assets)),
             assets,
+            localization,
+            languages,
 
             // shaders
             sprite_pipeline_collection: SpritePipelineCollection::new(game_io),
@@ -168,6 +200,7 @@ impl Globals {
 
             // debug
             debug_visible: false,
+            battle_lua_profile: Vec::new(),
         }
     }
 
@@ -652,7 +685,7 @@ impl Globals {
     ) -> Option<PackageListing> {
         let namespace = PackageNamespace::Local;
 
-        match category {
+        let listing = match category {
             PackageCategory::Encounter => self
                 .encounter_packages
                 .package(namespace, id)
@@ -689,7 +722,14 @@ impl Globals {
                 .tile_state_packages
                 .package(namespace, id)
                 .map(|package| package.create_package_listing()),
-        }
+        }?;
+
+        let language = self.localization.language();
+        let translation = self
+            .package_info(category, namespace, id)
+            .and_then(|package_info| package_info.translation(language));
+
+        Some(listing.with_translation(translation))
     }
 
     pub fn namespaces(&self) -> impl Iterator<Item = PackageNamespace> + '_ {
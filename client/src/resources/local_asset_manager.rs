@@ -3,6 +3,7 @@ use crate::render::ui::GlyphAtlas;
 use super::*;
 use framework::prelude::*;
 use packets::structures::{AssetDataType, FileHash, TextureAnimPathPair};
+use packets::zip::{ExtractionError, ExtractionLimits};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::fs;
@@ -118,19 +119,19 @@ impl LocalAssetManager {
         }
     }
 
-    /// Returns path prefix for the virtual zip
+    /// Returns path prefix for the virtual zip, files from a zip that fails to extract are dropped
     pub fn load_virtual_zip(
         &self,
         game_io: &GameIO,
         hash: FileHash,
         bytes: Vec<u8>,
-    ) -> VirtualZipMeta {
+    ) -> Result<VirtualZipMeta, ExtractionError> {
         let mut loaded_zips = self.loaded_zips.borrow_mut();
 
         if let Some(tracking) = loaded_zips.get(&hash) {
             log::debug!("{hash} is already loaded. skipping...");
 
-            return tracking.meta.clone();
+            return Ok(tracking.meta.clone());
         }
 
         loaded_zips.remove(&hash);
@@ -148,7 +149,7 @@ impl LocalAssetManager {
 
         let mut virtual_files = Vec::new();
 
-        packets::zip::extract(&bytes, |path, mut file| {
+        let result = packets::zip::extract(&bytes, &ExtractionLimits::PACKAGE, |path, file| {
             let virtual_path = meta.virtual_prefix.clone() + path.as_str();
             let virtual_path = Arc::<str>::from(virtual_path);
 
@@ -175,7 +176,7 @@ impl LocalAssetManager {
                             virtual_files.push(virtual_path);
                         }
                         Err(err) => {
-                            log::error!("Failed to load {:?} in {}: {}", file.name(), hash, err);
+                            log::error!("Failed to load {:?} in {}: {}", path, hash, err);
                         }
                     }
 
@@ -196,10 +197,20 @@ impl LocalAssetManager {
             };
 
             if let Err(err) = res {
-                log::error!("Failed to load {:?} in {}: {}", file.name(), hash, err);
+                log::error!("Failed to load {:?} in {}: {}", path, hash, err);
             }
         });
 
+        if let Err(err) = result {
+            for file in virtual_files {
+                text_cache.remove(&file);
+                texture_cache.remove(&file);
+                sound_cache.remove(&file);
+            }
+
+            return Err(err);
+        }
+
         let tracking = VirtualZipTracking {
             meta: meta.clone(),
             virtual_files,
@@ -209,7 +220,7 @@ impl LocalAssetManager {
 
         loaded_zips.insert(hash, tracking);
 
-        meta
+        Ok(meta)
     }
 
     pub fn non_midi_audio(&self, path: &str) -> SoundBuffer {
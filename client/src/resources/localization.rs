@@ -0,0 +1,183 @@
+use super::{AssetManager, ResourcePaths, DEFAULT_LANGUAGE};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Clone, PartialEq, Eq)]
+pub struct LanguageInfo {
+    pub code: String,
+    pub name: String,
+}
+
+/// UI strings for the selected language, loaded from `resources/locales/{language}.toml`
+///
+/// Nested tables are flattened into dotted keys: `[config] video = "Video"` is read as `config.video`
+#[derive(Default, Clone)]
+pub struct Localization {
+    language: String,
+    strings: Arc<HashMap<String, String>>,
+    fallback_strings: Arc<HashMap<String, String>>,
+}
+
+impl Localization {
+    pub fn load(assets: &impl AssetManager, language: &str) -> Self {
+        let fallback_strings = Arc::new(parse_strings(
+            &assets.text(&Self::locale_path(DEFAULT_LANGUAGE)),
+        ));
+
+        let strings = if language == DEFAULT_LANGUAGE {
+            fallback_strings.clone()
+        } else {
+            Arc::new(parse_strings(&assets.text(&Self::locale_path(language))))
+        };
+
+        Self {
+            language: language.to_string(),
+            strings,
+            fallback_strings,
+        }
+    }
+
+    pub fn locale_path(language: &str) -> String {
+        format!("{}{language}.toml", ResourcePaths::LOCALES_FOLDER)
+    }
+
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+
+    /// Falls back to English, then to the key itself
+    pub fn get<'a>(&'a self, key: &'a str) -> &'a str {
+        self.strings
+            .get(key)
+            .or_else(|| self.fallback_strings.get(key))
+            .map(|s| s.as_str())
+            .unwrap_or(key)
+    }
+
+    /// Same as `get`, replacing `{name}` with the matching value in `args`
+    pub fn format(&self, key: &str, args: &[(&str, &str)]) -> String {
+        let mut text = self.get(key).to_string();
+
+        for (name, value) in args {
+            text = text.replace(&format!("{{{name}}}"), value);
+        }
+
+        text
+    }
+
+    /// Lists every language with a locale file in the given folders, `folders` should include
+    /// the locales folder of each enabled resource package
+    pub fn find_languages<'a>(
+        assets: &impl AssetManager,
+        folders: impl IntoIterator<Item = &'a str>,
+    ) -> Vec<LanguageInfo> {
+        let mut codes: Vec<String> = folders
+            .into_iter()
+            .flat_map(std::fs::read_dir)
+            .flatten()
+            .flatten()
+            .filter_map(|entry| {
+                let path = entry.path();
+
+                if path.extension()? != "toml" {
+                    return None;
+                }
+
+                Some(path.file_stem()?.to_str()?.to_string())
+            })
+            .collect();
+
+        if !codes.iter().any(|code| code == DEFAULT_LANGUAGE) {
+            codes.push(DEFAULT_LANGUAGE.to_string());
+        }
+
+        codes.sort();
+        codes.dedup();
+
+        codes
+            .into_iter()
+            .map(|code| {
+                let mut strings = parse_strings(&assets.text(&Self::locale_path(&code)));
+                let name = strings.remove("language_name");
+
+                LanguageInfo {
+                    name: name.unwrap_or_else(|| code.clone()),
+                    code,
+                }
+            })
+            .collect()
+    }
+}
+
+fn parse_strings(text: &str) -> HashMap<String, String> {
+    let mut strings = HashMap::new();
+
+    if text.is_empty() {
+        return strings;
+    }
+
+    match text.parse::<toml::Table>() {
+        Ok(table) => flatten_table(&mut strings, String::new(), table),
+        Err(err) => log::error!("Failed to parse locale file:\n{err}"),
+    }
+
+    strings
+}
+
+fn flatten_table(strings: &mut HashMap<String, String>, prefix: String, table: toml::Table) {
+    for (key, value) in table {
+        let key = if prefix.is_empty() {
+            key
+        } else {
+            format!("{prefix}.{key}")
+        };
+
+        match value {
+            toml::Value::String(text) => {
+                strings.insert(key, text);
+            }
+            toml::Value::Table(table) => flatten_table(strings, key, table),
+            _ => log::warn!("Expected a string or table for {key:?} in locale file"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn fallback_strings() {
+        let fallback_strings = parse_strings(
+            r#"
+            language_name = "English"
+
+            [config]
+            video = "Video"
+            audio = "Audio"
+            equip = "Equip {name}?"
+            "#,
+        );
+
+        let strings = parse_strings(
+            r#"
+            [config]
+            video = "Vidéo"
+            "#,
+        );
+
+        let localization = Localization {
+            language: String::from("fr"),
+            strings: Arc::new(strings),
+            fallback_strings: Arc::new(fallback_strings),
+        };
+
+        assert_eq!(localization.get("config.video"), "Vidéo");
+        assert_eq!(localization.get("config.audio"), "Audio");
+        assert_eq!(localization.get("config.missing"), "config.missing");
+        assert_eq!(
+            localization.format("config.equip", &[("name", "Folder")]),
+            "Equip Folder?"
+        );
+    }
+}
@@ -10,6 +10,7 @@ mod global_sfx;
 mod globals;
 mod input_util;
 mod local_asset_manager;
+mod localization;
 mod network;
 mod resource_paths;
 mod restrictions;
@@ -27,6 +28,7 @@ pub use global_sfx::*;
 pub use globals::*;
 pub use input_util::*;
 pub use local_asset_manager::*;
+pub use localization::*;
 pub use network::*;
 pub use packets::structures::Input;
 pub use resource_paths::*;
@@ -1,16 +1,17 @@
 use crate::args::Args;
 use crate::render::FrameTime;
+use crate::resources::ResourcePaths;
 use crate::structures::{DenseSlotMap, GenerationalIndex};
 use framework::math::Instant;
 use framework::prelude::async_sleep;
 use packets::{
-    deserialize, ClientPacket, NetplayPacket, PacketChannels, Reliability, ServerPacket,
-    SERVER_TICK_RATE,
+    deserialize, ClientPacket, ConnectionStats, IdentityPublicKey, NetplayPacket, PacketChannels,
+    Reliability, ServerPacket, SERVER_TICK_RATE,
 };
 use std::collections::HashMap;
 use std::future::Future;
 use std::net::{SocketAddr, UdpSocket};
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 const DISCONNECT_AFTER: Duration = Duration::from_secs(5);
@@ -18,6 +19,7 @@ pub type ClientPacketSender = Arc<dyn Fn(Reliability, ClientPacket) + Send + Syn
 pub type ServerPacketReceiver = flume::Receiver<ServerPacket>;
 pub type NetplayPacketSender = Arc<dyn Fn(NetplayPacket) + Send + Sync>;
 pub type NetplayPacketReceiver = flume::Receiver<NetplayPacket>;
+type SharedConnectionStats = Arc<Mutex<Vec<(SocketAddr, ConnectionStats)>>>;
 
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum ServerStatus {
@@ -41,6 +43,7 @@ enum Event {
 pub struct Network {
     socket: Arc<UdpSocket>,
     sender: flume::Sender<Event>,
+    connection_stats: SharedConnectionStats,
     time: FrameTime,
 }
 
@@ -58,8 +61,15 @@ impl Network {
             move || socket_listener(socket, sender)
         });
 
+        let connection_stats = SharedConnectionStats::default();
+
         std::thread::spawn({
-            let listener = EventListener::new(socket.clone(), receiver, args.resend_budget);
+            let listener = EventListener::new(
+                socket.clone(),
+                receiver,
+                connection_stats.clone(),
+                args.resend_budget,
+            );
 
             move || listener.run()
         });
@@ -67,10 +77,16 @@ impl Network {
         Self {
             socket,
             sender,
+            connection_stats,
             time: 0,
         }
     }
 
+    /// Stats for every open connection, updated every network tick
+    pub fn connection_stats(&self) -> Vec<(SocketAddr, ConnectionStats)> {
+        self.connection_stats.lock().unwrap().clone()
+    }
+
     pub fn subscribe_to_netplay(
         &self,
         address: String,
@@ -185,6 +201,28 @@ fn socket_listener(socket: Arc<UdpSocket>, packet_sender: flume::Sender<Event>)
     }
 }
 
+fn server_identity_path(addr: SocketAddr) -> String {
+    let file_name = addr.to_string().replace(':', "_p");
+
+    ResourcePaths::SERVER_IDENTITY_FOLDER.to_string()
+        + &packets::address_parsing::uri_encode(&file_name)
+}
+
+/// The identity key the server proved in a previous session
+fn load_server_identity(addr: SocketAddr) -> Option<IdentityPublicKey> {
+    let bytes = std::fs::read(server_identity_path(addr)).ok()?;
+
+    bytes.try_into().ok()
+}
+
+fn save_server_identity(addr: SocketAddr, identity_key: IdentityPublicKey) {
+    let _ = std::fs::create_dir_all(ResourcePaths::SERVER_IDENTITY_FOLDER);
+
+    if let Err(e) = std::fs::write(server_identity_path(addr), identity_key) {
+        log::error!("Failed to save identity key for {addr}: {e}");
+    }
+}
+
 struct Connection {
     socket_addr: SocketAddr,
     client_channel: packets::ChannelSender<PacketChannels>,
@@ -194,11 +232,21 @@ struct Connection {
     server_subscribers: Vec<flume::Sender<ServerPacket>>,
     netplay_sender: flume::Sender<NetplayPacket>,
     netplay_recycled_receiver: NetplayPacketReceiver,
+    pinned_identity: Option<IdentityPublicKey>,
+    identity_rejected: bool,
 }
 
 impl Connection {
-    fn new(socket_addr: SocketAddr) -> Self {
+    fn new(socket_addr: SocketAddr, encrypted: bool) -> Self {
         let mut builder = packets::ConnectionBuilder::new(&packets::Config::default());
+        let mut pinned_identity = None;
+
+        if encrypted {
+            // servers must prove the same identity as the first time we connected
+            pinned_identity = load_server_identity(socket_addr);
+            builder.initiate_handshake(pinned_identity);
+        }
+
         builder.receiving_channel(PacketChannels::Server);
         let client_channel = builder.sending_channel(PacketChannels::Client);
         let netplay_channel = builder.bidirectional_channel(PacketChannels::Netplay);
@@ -214,6 +262,27 @@ impl Connection {
             server_subscribers: Vec::new(),
             netplay_sender,
             netplay_recycled_receiver,
+            pinned_identity,
+            identity_rejected: false,
+        }
+    }
+
+    fn update_identity(&mut self) {
+        if self.pinned_identity.is_none() {
+            if let Some(identity_key) = self.packet_receiver.peer_identity_key() {
+                save_server_identity(self.socket_addr, identity_key);
+                self.pinned_identity = Some(identity_key);
+            }
+        }
+
+        if !self.identity_rejected && self.packet_receiver.identity_rejected() {
+            self.identity_rejected = true;
+
+            log::error!(
+                "{} failed to prove its identity and may be an impostor, delete {:?} if the server replaced its identity key",
+                self.socket_addr,
+                server_identity_path(self.socket_addr)
+            );
         }
     }
 }
@@ -223,16 +292,23 @@ struct EventListener {
     connection_map: HashMap<SocketAddr, GenerationalIndex>,
     connections: DenseSlotMap<Connection>,
     receiver: flume::Receiver<Event>,
+    connection_stats: SharedConnectionStats,
     resend_budget: usize,
 }
 
 impl EventListener {
-    fn new(socket: Arc<UdpSocket>, receiver: flume::Receiver<Event>, resend_budget: usize) -> Self {
+    fn new(
+        socket: Arc<UdpSocket>,
+        receiver: flume::Receiver<Event>,
+        connection_stats: SharedConnectionStats,
+        resend_budget: usize,
+    ) -> Self {
         Self {
             socket,
             connection_map: HashMap::new(),
             connections: Default::default(),
             receiver,
+            connection_stats,
             resend_budget,
         }
     }
@@ -275,7 +351,8 @@ impl EventListener {
         if let Some(index) = self.connection_map.get_mut(&addr) {
             self.connections[*index].server_subscribers.push(sender);
         } else {
-            let mut connection = Connection::new(addr);
+            // keeps identities and other server traffic private
+            let mut connection = Connection::new(addr, true);
             connection.server_subscribers.push(sender);
             let index = self.connections.insert(connection);
             self.connection_map.insert(addr, index);
@@ -293,7 +370,7 @@ impl EventListener {
             let _ = sender.send(connection.netplay_recycled_receiver.clone());
         } else {
             // create a connection if it doesnt already exist
-            let connection = Connection::new(addr);
+            let connection = Connection::new(addr, false);
 
             let _ = sender.send(connection.netplay_recycled_receiver.clone());
 
@@ -341,7 +418,10 @@ impl EventListener {
         };
         let connection = &mut self.connections[*index];
 
-        let (channel, messages) = match connection.packet_receiver.receive_packet(time, &bytes) {
+        let result = connection.packet_receiver.receive_packet(time, &bytes);
+        connection.update_identity();
+
+        let (channel, messages) = match result {
             Ok(Some((channel, messages))) => (channel, messages),
             Ok(None) => {
                 return;
@@ -419,6 +499,7 @@ impl EventListener {
 
         self.handle_disconnections(now);
         self.send_packets(now);
+        self.share_stats();
     }
 
     fn handle_disconnections(&mut self, now: Instant) {
@@ -460,4 +541,14 @@ impl EventListener {
             });
         }
     }
+
+    fn share_stats(&self) {
+        let stats = self
+            .connections
+            .iter()
+            .map(|(_, connection)| (connection.socket_addr, connection.packet_sender.stats()))
+            .collect();
+
+        *self.connection_stats.lock().unwrap() = stats;
+    }
 }
@@ -9,9 +9,16 @@ impl ResourcePaths {
     pub const SERVER_CACHE_FOLDER: &'static str = "cache/servers/";
     pub const MOD_CACHE_FOLDER: &'static str = "cache/mods/";
     pub const IDENTITY_FOLDER: &'static str = "identity/";
+    pub const SERVER_IDENTITY_FOLDER: &'static str = "known_servers/";
+    pub const DESYNC_FOLDER: &'static str = "desyncs/";
+    pub const PROFILE_FOLDER: &'static str = "profiles/";
+    pub const PACKAGE_STAGING_FOLDER: &'static str = "cache/staging/";
     pub const VIRTUAL_PREFIX: &'static str = "/virtual/";
     pub const SEPARATOR: &'static str = "/";
 
+    // Localization
+    pub const LOCALES_FOLDER: &'static str = "resources/locales/";
+
     // Music
     pub const SOUND_FONT: &'static str = "resources/music/soundfont.sf2";
     pub const MAIN_MENU_MUSIC: &'static str = "resources/music/main_menu.ogg";
@@ -205,6 +212,12 @@ impl ResourcePaths {
     pub const KEY_ITEMS_UI_ANIMATION: &'static str = "resources/scenes/key_items/ui.animation";
     pub const KEY_ITEMS_MUG: &'static str = "resources/scenes/key_items/mug.png";
 
+    // MailScene
+    pub const MAIL_UI_ANIMATION: &'static str = "resources/scenes/mail/ui.animation";
+    pub const MAIL_ICONS: &'static str = "resources/scenes/mail/icons.png";
+    pub const MAIL_ICONS_ANIMATION: &'static str = "resources/scenes/mail/icons.animation";
+    pub const MAIL_NO_MUG: &'static str = "resources/scenes/mail/nomug.png";
+
     // BattleSelectScene
     pub const BATTLE_SELECT_UI: &'static str = "resources/scenes/battle_select/ui.png";
     pub const BATTLE_SELECT_UI_ANIMATION: &'static str =
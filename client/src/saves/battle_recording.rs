@@ -1,5 +1,6 @@
 use crate::battle::{BattleProps, PlayerSetup};
 use crate::packages::PackageNamespace;
+use crate::render::FrameTime;
 use crate::resources::{AssetManager, Globals, ResourcePaths};
 use crate::{SupportingServiceComm, SupportingServiceEvent};
 use framework::prelude::*;
@@ -8,6 +9,21 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::time::{SystemTime, UNIX_EPOCH};
 
+const RECORDING_MAGIC: &[u8; 8] = b"HUBOSREC";
+
+// increment when BattleRecording or the container changes shape, and add a migration to decode
+const RECORDING_FORMAT_VERSION: u16 = 1;
+
+/// Uncompressed info stored at the start of a recording file, readable without decoding the battle
+#[derive(Clone, Serialize, Deserialize)]
+pub struct BattleRecordingHeader {
+    pub game_version: String,
+    pub version_iteration: u64,
+    pub player_names: Vec<String>,
+    pub encounter_id: Option<PackageId>,
+    pub duration: FrameTime,
+}
+
 #[derive(Clone, Serialize, Deserialize)]
 pub struct BattleRecording {
     pub encounter_package_pair: Option<(PackageNamespace, PackageId)>,
@@ -46,9 +62,10 @@ impl BattleRecording {
         let service_comm = game_io.resource::<SupportingServiceComm>().unwrap().clone();
         let globals = game_io.resource::<Globals>().unwrap();
         let nickname = globals.global_save.nickname.clone();
+        let embed_packages = globals.config.embed_recording_packages;
 
         // collect package zips
-        if self.package_map.is_empty() {
+        if self.required_packages.is_empty() {
             for (info, namespace) in globals.battle_dependencies(game_io, props) {
                 let category = info.category;
                 let namespace = namespace.prefix_recording();
@@ -58,12 +75,21 @@ impl BattleRecording {
                     continue;
                 }
 
-                self.package_map.entry((category, hash)).or_insert_with(|| {
-                    globals.assets.virtual_zip_bytes(&hash).unwrap_or_else(|| {
-                        let path = format!("{}{}.zip", ResourcePaths::MOD_CACHE_FOLDER, hash);
-                        globals.assets.binary(&path)
-                    })
-                });
+                let cache_path = format!("{}{}.zip", ResourcePaths::MOD_CACHE_FOLDER, hash);
+
+                // packages from the mod cache can be referenced by hash instead of embedded
+                let can_reference = !embed_packages
+                    && !globals.assets.contains_virtual_zip(&hash)
+                    && std::path::Path::new(&cache_path).exists();
+
+                if !can_reference {
+                    self.package_map.entry((category, hash)).or_insert_with(|| {
+                        globals
+                            .assets
+                            .virtual_zip_bytes(&hash)
+                            .unwrap_or_else(|| globals.assets.binary(&cache_path))
+                    });
+                }
 
                 self.required_packages.push((category, namespace, hash));
 
@@ -81,13 +107,12 @@ impl BattleRecording {
             })
             .map(|package| package.preview_texture_path.clone());
 
+        let header = self.create_header(game_io);
         let recording = self.clone();
 
         log::info!("Starting background thread to save recording");
 
         std::thread::spawn(move || {
-            use std::fs::File;
-
             // resolve package path
             let elapsed_time = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
             let unique_id = format!(
@@ -135,9 +160,16 @@ impl BattleRecording {
 
             // create recording file
             let dat_path = folder_path.clone() + "recording.dat";
-            let mut file = File::create(&dat_path).unwrap();
 
-            if let Err(e) = rmp_serde::encode::write_named(&mut file, &recording) {
+            let bytes = match recording.encode(&header) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    log::error!("Failed to encode recording: {e}");
+                    return;
+                }
+            };
+
+            if let Err(e) = std::fs::write(&dat_path, bytes) {
                 log::error!("Failed to save data to {:?}: {}", dat_path, e);
                 return;
             }
@@ -152,9 +184,137 @@ impl BattleRecording {
         });
     }
 
+    fn create_header(&self, game_io: &GameIO) -> BattleRecordingHeader {
+        let globals = game_io.resource::<Globals>().unwrap();
+
+        // we only know our own name, other players are named after their navi
+        let player_names = self
+            .player_setups
+            .iter()
+            .map(|setup| {
+                if setup.local {
+                    return globals.global_save.nickname.clone();
+                }
+
+                setup
+                    .player_package(game_io)
+                    .map(|package| package.package_info.name.to_string())
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        let duration = self
+            .player_setups
+            .iter()
+            .map(|setup| setup.buffer.len() as FrameTime)
+            .max()
+            .unwrap_or_default();
+
+        BattleRecordingHeader {
+            game_version: env!("CARGO_PKG_VERSION").to_string(),
+            version_iteration: packets::VERSION_ITERATION,
+            player_names,
+            encounter_id: (self.encounter_package_pair.as_ref()).map(|(_, id)| id.clone()),
+            duration,
+        }
+    }
+
+    /// Layout: magic, format version, header length, header, compressed recording
+    fn encode(&self, header: &BattleRecordingHeader) -> Result<Vec<u8>, String> {
+        use flate2::write::ZlibEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let header_bytes = rmp_serde::to_vec_named(header).map_err(|e| e.to_string())?;
+        let body_bytes = rmp_serde::to_vec_named(self).map_err(|e| e.to_string())?;
+
+        let mut bytes = Vec::new();
+        bytes.extend(RECORDING_MAGIC);
+        bytes.extend(RECORDING_FORMAT_VERSION.to_le_bytes());
+        bytes.extend((header_bytes.len() as u32).to_le_bytes());
+        bytes.extend(header_bytes);
+
+        let mut encoder = ZlibEncoder::new(bytes, Compression::default());
+        encoder.write_all(&body_bytes).map_err(|e| e.to_string())?;
+        encoder.finish().map_err(|e| e.to_string())
+    }
+
+    /// Splits a recording file into the format version, header, and compressed body
+    fn split_container(bytes: &[u8]) -> Result<(u16, BattleRecordingHeader, &[u8]), String> {
+        const CORRUPTED_MESSAGE: &str = "Recording is corrupted";
+
+        let bytes = bytes
+            .strip_prefix(RECORDING_MAGIC)
+            .ok_or(CORRUPTED_MESSAGE)?;
+
+        let (version_bytes, bytes) = bytes.split_at_checked(2).ok_or(CORRUPTED_MESSAGE)?;
+        let format_version = u16::from_le_bytes(version_bytes.try_into().unwrap());
+
+        let (length_bytes, bytes) = bytes.split_at_checked(4).ok_or(CORRUPTED_MESSAGE)?;
+        let header_length = u32::from_le_bytes(length_bytes.try_into().unwrap()) as usize;
+
+        let (header_bytes, body_bytes) = bytes
+            .split_at_checked(header_length)
+            .ok_or(CORRUPTED_MESSAGE)?;
+
+        let header = rmp_serde::from_slice(header_bytes).map_err(|_| CORRUPTED_MESSAGE)?;
+
+        Ok((format_version, header, body_bytes))
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, String> {
+        use flate2::read::ZlibDecoder;
+        use std::io::Read;
+
+        if !bytes.starts_with(RECORDING_MAGIC) {
+            // recordings from before the versioned container were stored as plain BattleRecording
+            return rmp_serde::from_slice(bytes).map_err(|e| {
+                format!("Recording was created by an incompatible version of the game: {e}")
+            });
+        }
+
+        let (format_version, header, body_bytes) = Self::split_container(bytes)?;
+
+        if format_version > RECORDING_FORMAT_VERSION {
+            return Err(format!(
+                "Recording was created by a newer version of the game ({}), update to watch it",
+                header.game_version
+            ));
+        }
+
+        if header.version_iteration != packets::VERSION_ITERATION {
+            log::warn!(
+                "Recording was created with game version {} and may not play back correctly",
+                header.game_version
+            );
+        }
+
+        let mut body = Vec::new();
+
+        ZlibDecoder::new(body_bytes)
+            .read_to_end(&mut body)
+            .map_err(|e| format!("Failed to decompress recording: {e}"))?;
+
+        // format version 1 is the current shape of BattleRecording
+        // older format versions should be migrated here as the shape changes
+        rmp_serde::from_slice(&body).map_err(|e| {
+            format!(
+                "Recording was created by an incompatible version of the game ({}): {e}",
+                header.game_version
+            )
+        })
+    }
+
     pub fn load(assets: &impl AssetManager, path: &str) -> Option<Self> {
         let bytes = assets.binary(path);
-        rmp_serde::from_slice(&bytes).ok()
+
+        match Self::decode(&bytes) {
+            Ok(recording) => Some(recording),
+            Err(e) => {
+                log::error!("Failed to load {path:?}: {e}");
+                None
+            }
+        }
     }
 
     pub fn load_packages(&self, game_io: &mut GameIO, ignored_package_ids: Vec<PackageId>) {
@@ -177,8 +337,26 @@ impl BattleRecording {
             let assets = &globals.assets;
 
             if !assets.contains_virtual_zip(&hash) {
-                if let Some(bytes) = self.package_map.get(&(category, hash)) {
-                    assets.load_virtual_zip(game_io, hash, bytes.clone());
+                let bytes = if let Some(bytes) = self.package_map.get(&(category, hash)) {
+                    bytes.clone()
+                } else {
+                    // referenced by hash, should be in the mod cache
+                    let zip_path = format!("{}{}.zip", ResourcePaths::MOD_CACHE_FOLDER, hash);
+
+                    match std::fs::read(&zip_path) {
+                        Ok(bytes) if FileHash::hash(&bytes) == hash => bytes,
+                        _ => {
+                            log::error!(
+                                "Recording requires a missing {category:?} package: {hash}"
+                            );
+                            continue;
+                        }
+                    }
+                };
+
+                if let Err(err) = assets.load_virtual_zip(game_io, hash, bytes) {
+                    log::error!("Failed to extract {category:?} package {hash}: {err}");
+                    continue;
                 }
             }
 
@@ -209,7 +387,11 @@ impl BattleRecording {
 
                 let globals = game_io.resource::<Globals>().unwrap();
                 let bytes = globals.assets.binary(&zip_path);
-                globals.assets.load_virtual_zip(game_io, hash, bytes);
+
+                if let Err(err) = globals.assets.load_virtual_zip(game_io, hash, bytes) {
+                    log::error!("Failed to extract {category:?} package {hash}: {err}");
+                    continue;
+                }
 
                 let globals = game_io.resource_mut::<Globals>().unwrap();
                 globals.load_virtual_package(category, namespace, hash);
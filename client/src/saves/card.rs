@@ -22,9 +22,10 @@ impl Card {
     ) {
         let globals = game_io.resource::<Globals>().unwrap();
         let package_manager = &globals.card_packages;
+        let language = globals.localization.language();
         let name = package_manager
             .package_or_fallback(PackageNamespace::Local, &self.package_id)
-            .map(|package| package.card_properties.short_name.as_ref())
+            .map(|package| package.localized_name(language))
             .unwrap_or("?????");
 
         let mut text_style = TextStyle::new_monospace(game_io, FontName::Thick);
@@ -141,7 +142,7 @@ impl Card {
             package_manager.package_or_fallback(PackageNamespace::Local, &self.package_id)
         {
             icon_texture_path = package.icon_texture_path.as_str();
-            short_name = package.card_properties.short_name.as_ref();
+            short_name = package.localized_name(globals.localization.language());
             element = package.card_properties.element;
             limit = package.limit;
         } else {
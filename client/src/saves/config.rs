@@ -1,5 +1,5 @@
 use crate::render::PostProcessColorBlindness;
-use crate::resources::{AssetManager, Input, DEFAULT_PACKAGE_REPO, MAX_VOLUME};
+use crate::resources::{AssetManager, Input, DEFAULT_LANGUAGE, DEFAULT_PACKAGE_REPO, MAX_VOLUME};
 use framework::cfg_macros::{cfg_android, cfg_desktop_and_web};
 use framework::input::{Button, Key};
 use itertools::Itertools;
@@ -15,6 +15,7 @@ pub enum KeyStyle {
 
 #[derive(Clone, PartialEq, Eq)]
 pub struct Config {
+    pub language: String,
     pub fullscreen: bool,
     pub vsync: bool,
     pub lock_aspect_ratio: bool,
@@ -34,6 +35,7 @@ pub struct Config {
     pub controller_bindings: HashMap<Input, Vec<Button>>,
     pub controller_index: usize,
     pub package_repo: String,
+    pub embed_recording_packages: bool,
 }
 
 impl Config {
@@ -69,6 +71,7 @@ impl Config {
                 (Input::Shoot, vec![Key::LShift, Key::K, Key::X]),
                 (Input::Sprint, vec![Key::LShift, Key::K, Key::X]),
                 (Input::Map, vec![Key::M]),
+                (Input::Chat, vec![Key::T]),
                 (Input::Option, vec![Key::Return]),
                 (Input::Option2, vec![Key::R, Key::Backspace]),
                 (Input::Special, vec![Key::F]),
@@ -95,6 +98,7 @@ impl Config {
                 (Input::Shoot, vec![Key::LShift, Key::K]),
                 (Input::Sprint, vec![Key::LShift, Key::K]),
                 (Input::Map, vec![Key::M]),
+                (Input::Chat, vec![Key::T]),
                 (Input::Option, vec![Key::Return]),
                 (Input::Option2, vec![Key::R]),
                 (Input::Special, vec![Key::F]),
@@ -121,6 +125,7 @@ impl Config {
                 (Input::Shoot, vec![Key::X]),
                 (Input::Sprint, vec![Key::X]),
                 (Input::Map, vec![Key::M]),
+                (Input::Chat, vec![Key::T]),
                 (Input::Option, vec![Key::Return]),
                 (Input::Option2, vec![Key::Backspace]),
                 (Input::Special, vec![Key::C]),
@@ -236,6 +241,7 @@ impl Config {
 impl Default for Config {
     fn default() -> Config {
         Config {
+            language: String::from(DEFAULT_LANGUAGE),
             fullscreen: {
                 cfg_android! {true}
                 cfg_desktop_and_web! {false}
@@ -258,6 +264,7 @@ impl Default for Config {
             controller_bindings: Self::default_controller_bindings(),
             controller_index: 0,
             package_repo: String::from(DEFAULT_PACKAGE_REPO),
+            embed_recording_packages: true,
         }
     }
 }
@@ -269,6 +276,7 @@ impl From<&str> for Config {
         use strum::IntoEnumIterator;
 
         let mut config = Config {
+            language: String::from(DEFAULT_LANGUAGE),
             fullscreen: false,
             vsync: true,
             lock_aspect_ratio: true,
@@ -288,6 +296,7 @@ impl From<&str> for Config {
             controller_bindings: HashMap::new(),
             controller_index: 0,
             package_repo: String::from(DEFAULT_PACKAGE_REPO),
+            embed_recording_packages: true,
         };
 
         use ini::Ini;
@@ -300,6 +309,17 @@ impl From<&str> for Config {
             }
         };
 
+        if let Some(properties) = ini.section(Some("General")) {
+            config.language = properties
+                .get("Language")
+                .unwrap_or(DEFAULT_LANGUAGE)
+                .to_string();
+
+            if config.language.is_empty() {
+                config.language = String::from(DEFAULT_LANGUAGE);
+            }
+        }
+
         if let Some(properties) = ini.section(Some("Video")) {
             config.fullscreen = parse_or_default(properties.get("Fullscreen"));
             config.vsync = parse_or(properties.get("VSync"), true);
@@ -336,13 +356,20 @@ impl From<&str> for Config {
                 _ => KeyStyle::default(),
             };
 
+            let default_bindings = Config::default_key_bindings(config.key_style);
+
             for input in Input::iter() {
                 let input_string = format!("{input:?}");
 
-                let keys = properties
-                    .get(&input_string)
-                    .into_iter()
-                    .flat_map(|key_str| key_str.split(','))
+                let Some(keys_str) = properties.get(&input_string) else {
+                    // inputs added after this config was saved
+                    let keys = default_bindings.get(&input).cloned().unwrap_or_default();
+                    config.key_bindings.insert(input, keys);
+                    continue;
+                };
+
+                let keys = keys_str
+                    .split(',')
                     .flat_map(|value| Key::from_str(value).ok())
                     .collect();
 
@@ -378,6 +405,10 @@ impl From<&str> for Config {
             }
         }
 
+        if let Some(properties) = ini.section(Some("Recording")) {
+            config.embed_recording_packages = parse_or(properties.get("EmbedPackages"), true);
+        }
+
         config
     }
 }
@@ -386,6 +417,9 @@ impl std::fmt::Display for Config {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         use strum::IntoEnumIterator;
 
+        writeln!(f, "[General]")?;
+        writeln!(f, "Language = {}", self.language)?;
+
         writeln!(f, "[Video]")?;
         writeln!(f, "Fullscreen = {}", self.fullscreen)?;
         writeln!(f, "VSync = {}", self.vsync)?;
@@ -453,6 +487,9 @@ impl std::fmt::Display for Config {
             writeln!(f, "PackageRepo = ",)?;
         }
 
+        writeln!(f, "[Recording]")?;
+        writeln!(f, "EmbedPackages = {}", self.embed_recording_packages)?;
+
         Ok(())
     }
 }
@@ -25,16 +25,29 @@ impl PlayerInputBuffer {
     }
 
     pub fn push_last(&mut self, input: NetplayBufferItem) {
-        self.len += 1;
+        self.push_repeated(input, 1);
+    }
+
+    pub fn push_repeated(&mut self, input: NetplayBufferItem, repeat: usize) {
+        if repeat == 0 {
+            return;
+        }
+
+        self.len += repeat;
 
         if let Some((item, count)) = self.buffer.back_mut() {
             if *item == input {
-                *count += 1;
+                *count += repeat;
                 return;
             }
         }
 
-        self.buffer.push_back((input, 1));
+        self.buffer.push_back((input, repeat));
+    }
+
+    /// Iterates over (item, repeat count)
+    pub fn runs(&self) -> impl Iterator<Item = &(NetplayBufferItem, usize)> {
+        self.buffer.iter()
     }
 
     pub fn delete_last(&mut self) {
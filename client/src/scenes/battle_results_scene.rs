@@ -0,0 +1,201 @@
+use crate::battle::format_time;
+use crate::bindable::SpriteColorMode;
+use crate::render::ui::{
+    FontName, SceneTitle, ScrollTracker, ScrollableFrame, SubSceneFrame, TextStyle, UiInputTracker,
+};
+use crate::render::{Background, Camera, SpriteColorQueue};
+use crate::resources::{Globals, Input, RESOLUTION_F, TEXT_DARK_SHADOW_COLOR};
+use framework::prelude::*;
+use packets::structures::{BattleReward, BattleStatistics};
+
+const LINE_HEIGHT: f32 = 16.0;
+
+struct ResultLine {
+    label: String,
+    value: String,
+}
+
+impl ResultLine {
+    fn new(label: impl Into<String>, value: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            value: value.into(),
+        }
+    }
+
+    fn from_reward(reward: BattleReward) -> Self {
+        match reward {
+            BattleReward::Text(text) => Self::new(text, ""),
+            BattleReward::Item { name, count } => Self::new(name, format!("x{count}")),
+        }
+    }
+}
+
+pub struct BattleResultsScene {
+    camera: Camera,
+    background: Background,
+    frame: SubSceneFrame,
+    lines_frame: ScrollableFrame,
+    lines: Vec<ResultLine>,
+    reward_receiver: Option<flume::Receiver<BattleReward>>,
+    rewards_listed: bool,
+    ui_input_tracker: UiInputTracker,
+    scroll_tracker: ScrollTracker,
+    next_scene: NextScene,
+}
+
+impl BattleResultsScene {
+    pub fn new(
+        game_io: &GameIO,
+        statistics: BattleStatistics,
+        reward_receiver: Option<flume::Receiver<BattleReward>>,
+    ) -> Self {
+        let mut lines = vec![
+            ResultLine::new("TIME", format_time(statistics.time)),
+            ResultLine::new("HITS TAKEN", statistics.hits_taken.to_string()),
+            ResultLine::new("MOVEMENTS", statistics.movements.to_string()),
+            ResultLine::new("BUSTING LEVEL", statistics.score.to_string()),
+        ];
+
+        // survivors
+        let survivors = statistics
+            .ally_survivors
+            .into_iter()
+            .chain(statistics.neutral_survivors);
+
+        for survivor in survivors {
+            let name = if survivor.name.is_empty() {
+                String::from("???")
+            } else {
+                survivor.name
+            };
+
+            lines.push(ResultLine::new(name, format!("{}HP", survivor.health)));
+        }
+
+        // rewards from the encounter
+        let rewards_listed = !statistics.rewards.is_empty();
+
+        if rewards_listed {
+            lines.push(ResultLine::new("REWARDS", ""));
+            lines.extend(statistics.rewards.into_iter().map(ResultLine::from_reward));
+        }
+
+        // scrollable frame
+        let frame_bounds = Rect::new(8.0, 24.0, RESOLUTION_F.x - 16.0, RESOLUTION_F.y - 32.0);
+        let lines_frame = ScrollableFrame::new(game_io, frame_bounds);
+        let body_bounds = lines_frame.body_bounds();
+
+        let view_size = (body_bounds.height / LINE_HEIGHT) as usize;
+        let mut scroll_tracker = ScrollTracker::new(game_io, view_size);
+        scroll_tracker.set_total_items(lines.len());
+        scroll_tracker.define_scrollbar(lines_frame.scroll_start(), lines_frame.scroll_end());
+
+        Self {
+            camera: Camera::new_ui(game_io),
+            background: Background::new_sub_scene(game_io),
+            frame: SubSceneFrame::new(game_io).with_everything(true),
+            lines_frame,
+            lines,
+            reward_receiver,
+            rewards_listed,
+            ui_input_tracker: UiInputTracker::new(),
+            scroll_tracker,
+            next_scene: NextScene::None,
+        }
+    }
+
+    fn receive_rewards(&mut self) {
+        let Some(receiver) = &self.reward_receiver else {
+            return;
+        };
+
+        // rewards from the server can arrive after the battle ends
+        let rewards: Vec<_> = receiver.try_iter().collect();
+
+        if rewards.is_empty() {
+            return;
+        }
+
+        if !self.rewards_listed {
+            self.rewards_listed = true;
+            self.lines.push(ResultLine::new("REWARDS", ""));
+        }
+
+        let lines = rewards.into_iter().map(ResultLine::from_reward);
+        self.lines.extend(lines);
+        self.scroll_tracker.set_total_items(self.lines.len());
+    }
+}
+
+impl Scene for BattleResultsScene {
+    fn next_scene(&mut self) -> &mut NextScene {
+        &mut self.next_scene
+    }
+
+    fn update(&mut self, game_io: &mut GameIO) {
+        self.background.update();
+        self.receive_rewards();
+
+        if game_io.is_in_transition() {
+            return;
+        }
+
+        self.ui_input_tracker.update(game_io);
+
+        if self.ui_input_tracker.pulsed(Input::Confirm)
+            || self.ui_input_tracker.pulsed(Input::Cancel)
+        {
+            let globals = game_io.resource::<Globals>().unwrap();
+            globals.audio.play_sound(&globals.sfx.menu_close);
+
+            let transition = crate::transitions::new_battle_pop(game_io);
+            self.next_scene = NextScene::new_pop().with_transition(transition);
+            return;
+        }
+
+        if self.ui_input_tracker.pulsed(Input::Up) {
+            self.scroll_tracker.move_view_up();
+        }
+
+        if self.ui_input_tracker.pulsed(Input::Down) {
+            self.scroll_tracker.move_view_down();
+        }
+    }
+
+    fn draw(&mut self, game_io: &mut GameIO, render_pass: &mut RenderPass) {
+        self.background.draw(game_io, render_pass);
+
+        let mut sprite_queue =
+            SpriteColorQueue::new(game_io, &self.camera, SpriteColorMode::Multiply);
+
+        // draw lines
+        self.lines_frame.draw(game_io, &mut sprite_queue);
+
+        let mut text_style = TextStyle::new(game_io, FontName::Thin);
+        text_style.shadow_color = TEXT_DARK_SHADOW_COLOR;
+
+        let body_bounds = self.lines_frame.body_bounds();
+        let mut position = body_bounds.top_left() + Vec2::new(4.0, 3.0);
+        let right = body_bounds.right() - 4.0;
+
+        for line in &self.lines[self.scroll_tracker.view_range()] {
+            text_style.bounds.set_position(position);
+            text_style.draw(game_io, &mut sprite_queue, &line.label);
+
+            let value_width = text_style.measure(&line.value).size.x;
+            text_style.bounds.x = right - value_width;
+            text_style.draw(game_io, &mut sprite_queue, &line.value);
+
+            position.y += LINE_HEIGHT;
+        }
+
+        self.scroll_tracker.draw_scrollbar(&mut sprite_queue);
+
+        // draw frame
+        self.frame.draw(&mut sprite_queue);
+        SceneTitle::new("RESULTS").draw(game_io, &mut sprite_queue);
+
+        render_pass.consume_queue(sprite_queue);
+    }
+}
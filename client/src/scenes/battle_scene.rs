@@ -1,13 +1,14 @@
+use super::BattleResultsScene;
 use crate::battle::*;
 use crate::bindable::SpriteColorMode;
 use crate::lua_api::encounter_init;
 use crate::packages::{Package, PackageNamespace};
-use crate::render::ui::{Textbox, TextboxMessage, TextboxQuestion};
+use crate::render::ui::{FontName, Text, Textbox, TextboxMessage, TextboxQuestion};
 use crate::render::*;
 use crate::resources::*;
 use crate::saves::{BattleRecording, PlayerInputBuffer};
 use framework::prelude::*;
-use packets::structures::PackageId;
+use packets::structures::{BattleStatistics, FileHash, PackageCategory, PackageId};
 use packets::{NetplayBufferItem, NetplayPacket, NetplaySignal};
 use std::collections::VecDeque;
 use std::sync::Arc;
@@ -15,6 +16,10 @@ use std::sync::Arc;
 const SLOW_COOLDOWN: FrameTime = INPUT_BUFFER_LIMIT as FrameTime;
 const BUFFER_TOLERANCE: f32 = 2.0;
 const BUFFER_AVERAGE_PERIOD: f32 = SLOW_COOLDOWN as _;
+const PLAYBACK_SEEK_STEP: FrameTime = 60;
+const PLAYBACK_FAST_FORWARD_SPEED: usize = 4;
+const SPECTATOR_DELAY: usize = 180;
+const SPECTATOR_CATCH_UP_SPEED: usize = 10;
 
 fn simple_rolling_average(average: &mut f32, new_data: f32) {
     *average = (*average * (BUFFER_AVERAGE_PERIOD - 1.0) + new_data) / BUFFER_AVERAGE_PERIOD;
@@ -56,13 +61,21 @@ pub struct BattleScene {
     backups: VecDeque<Backup>,
     player_controllers: Vec<PlayerController>,
     local_index: Option<usize>,
+    desync_detector: DesyncDetector,
     slow_cooldown: FrameTime,
     frame_by_frame_debug: bool,
     resimulating: bool,
     draw_player_indices: bool,
     already_snapped: bool,
     is_playing_back_recording: bool,
+    playback_timeline: Option<PlaybackTimeline>,
+    playback_paused: bool,
+    playback_fast_forwarding: bool,
+    spectating: bool,
+    spectator_history: Option<PlayerInputBuffer>,
     exiting: bool,
+    /// Shown on the results screen after exiting
+    results: Option<BattleStatistics>,
     next_scene: NextScene,
 }
 
@@ -87,6 +100,26 @@ impl BattleScene {
             globals.assets.remove_unused_virtual_zips();
         }
 
+        Self::new_from_props(game_io, props, is_playing_back_recording)
+    }
+
+    /// Plays back input stored in each PlayerSetup's buffer
+    pub fn new_playback(game_io: &mut GameIO, props: BattleProps) -> Self {
+        Self::new_from_props(game_io, props, true)
+    }
+
+    /// Follows a netplay battle using input shared by every player, stays behind to avoid stalling
+    pub fn new_spectating(game_io: &mut GameIO, props: BattleProps) -> Self {
+        let mut scene = Self::new_from_props(game_io, props, false);
+        scene.spectating = true;
+        scene
+    }
+
+    fn new_from_props(
+        game_io: &mut GameIO,
+        mut props: BattleProps,
+        is_playing_back_recording: bool,
+    ) -> Self {
         // sort player setups for consistent execution order on every client
         props.player_setups.sort_by_key(|setup| setup.index);
 
@@ -166,6 +199,24 @@ impl BattleScene {
 
         Player::initialize_uninitialized(&mut simulation);
 
+        // track every input we've shared, for spectators joining late
+        let spectator_history = props.spectator_sender.as_ref().and_then(|_| {
+            let local_setup = player_setups.iter().find(|setup| setup.local)?;
+            Some(local_setup.buffer.clone())
+        });
+
+        let playback_timeline = if is_playing_back_recording {
+            let total_frames = player_controllers
+                .iter()
+                .map(|controller| controller.buffer.len() as FrameTime)
+                .max()
+                .unwrap_or_default();
+
+            Some(PlaybackTimeline::new(game_io, total_frames))
+        } else {
+            None
+        };
+
         Self {
             props,
             recording,
@@ -181,13 +232,20 @@ impl BattleScene {
             backups: VecDeque::new(),
             player_controllers,
             local_index,
+            desync_detector: DesyncDetector::new(),
             slow_cooldown: 0,
             frame_by_frame_debug: false,
             resimulating: false,
             draw_player_indices: false,
             already_snapped: false,
             is_playing_back_recording,
+            playback_timeline,
+            playback_paused: false,
+            playback_fast_forwarding: false,
+            spectating: false,
+            spectator_history,
             exiting: false,
+            results: None,
             next_scene: NextScene::None,
         }
     }
@@ -212,11 +270,8 @@ impl BattleScene {
                         continue;
                     };
 
-                    let description = if package.long_description.is_empty() {
-                        package.description.clone()
-                    } else {
-                        package.long_description.clone()
-                    };
+                    let language = globals.localization.language();
+                    let description = package.localized_long_description(language).to_string();
 
                     self.textbox.use_blank_avatar(game_io);
                     let interface = TextboxMessage::new(description);
@@ -283,10 +338,10 @@ impl BattleScene {
             }
         }
 
-        if self.is_playing_back_recording {
+        if self.local_index.is_none() {
             // prevent the textbox from opening and signals from being created
-            // the textbox currently can't make use of recorded inputs
-            // and we should be using signals from the recording
+            // the textbox currently can't make use of recorded or spectated inputs
+            // and we should be using signals from the other players
 
             self.pending_signals.clear();
 
@@ -397,6 +452,17 @@ impl BattleScene {
                 let mut resimulation_time = self.simulation.time;
 
                 if let Some(controller) = self.player_controllers.get_mut(index) {
+                    if !controller.connected {
+                        // the server also shares disconnects, avoid duplicating input
+                        return;
+                    }
+
+                    if self.spectating {
+                        // spectators only use this to learn a player left early
+                        controller.connected = false;
+                        return;
+                    }
+
                     // check disconnect
                     if data.signals.contains(&NetplaySignal::Disconnect) {
                         controller.connected = false;
@@ -426,11 +492,51 @@ impl BattleScene {
                 }
             }
             NetplayPacket::Heartbeat { .. } => {}
+            NetplayPacket::SpectatorJoin { .. } => {
+                self.sync_spectators(game_io);
+            }
+            NetplayPacket::MissingPackages {
+                recipient_index,
+                list,
+                ..
+            } => {
+                // requested by spectators, shared with every player
+                if Some(recipient_index) == self.local_index {
+                    self.share_spectator_packages(game_io, list);
+                }
+            }
+            NetplayPacket::SpectatorBuffer { index, time, data } => {
+                if !self.spectating {
+                    return;
+                }
+
+                if let Some(controller) = self.player_controllers.get_mut(index) {
+                    // skip input we already received in a SpectatorSync
+                    if time != self.synced_time + controller.buffer.len() as FrameTime {
+                        return;
+                    }
+
+                    if data.signals.contains(&NetplaySignal::Disconnect) {
+                        controller.connected = false;
+                    }
+
+                    controller.buffer.push_last(data);
+                }
+            }
+            NetplayPacket::Checksum {
+                index,
+                time,
+                checksum,
+            } => {
+                self.desync_detector
+                    .track_remote_checksum(index, time, checksum);
+                self.desync_detector.save_report();
+            }
             packet => {
                 let name: &'static str = (&packet).into();
                 let index = packet.index();
 
-                log::error!("Expecting Input, Heartbeat, or Disconnect during battle, received: {name} from {index}");
+                log::error!("Expecting Input, Heartbeat, Checksum, or Disconnect during battle, received: {name} from {index}");
             }
         }
     }
@@ -468,6 +574,133 @@ impl BattleScene {
         }
     }
 
+    /// The packages spectators may need from us to simulate our player
+    fn spectator_package_list(
+        &self,
+        game_io: &GameIO,
+    ) -> Vec<(PackageCategory, PackageId, FileHash)> {
+        let Some(local_setup) = self.props.player_setups.iter().find(|setup| setup.local) else {
+            return Vec::new();
+        };
+
+        let namespace = local_setup.namespace();
+        let globals = game_io.resource::<Globals>().unwrap();
+
+        globals
+            .battle_dependencies(game_io, &self.props)
+            .into_iter()
+            .filter(|(_, ns)| *ns == namespace)
+            .map(|(package_info, _)| {
+                (
+                    package_info.category,
+                    package_info.id.clone(),
+                    package_info.hash,
+                )
+            })
+            .collect()
+    }
+
+    fn sync_spectators(&self, game_io: &GameIO) {
+        let Some(send) = &self.props.spectator_sender else {
+            return;
+        };
+
+        let (Some(local_index), Some(history)) = (self.local_index, &self.spectator_history) else {
+            return;
+        };
+
+        let Some(local_setup) = self.props.player_setups.iter().find(|setup| setup.local) else {
+            return;
+        };
+
+        let cards = (local_setup.deck.cards.iter())
+            .map(|card| (card.package_id.clone(), card.code.clone()))
+            .collect();
+
+        send(NetplayPacket::SpectatorSync {
+            index: local_index,
+            seed: self.props.seed,
+            player_package: local_setup.package_id.clone(),
+            script_enabled: local_setup.script_enabled,
+            cards,
+            regular_card: local_setup.deck.regular_index,
+            recipes: local_setup.recipes.clone(),
+            blocks: local_setup.blocks.clone(),
+            drives: local_setup.drives.clone(),
+            packages: self.spectator_package_list(game_io),
+            buffer: history.runs().cloned().collect(),
+        });
+    }
+
+    fn share_spectator_packages(&self, game_io: &GameIO, list: Vec<FileHash>) {
+        let (Some(send), Some(local_index)) = (&self.props.spectator_sender, self.local_index)
+        else {
+            return;
+        };
+
+        let package_list = self.spectator_package_list(game_io);
+        let assets = &game_io.resource::<Globals>().unwrap().assets;
+
+        for hash in list {
+            // only share packages used in this battle
+            if !package_list.iter().any(|(_, _, h)| *h == hash) {
+                continue;
+            }
+
+            let data = assets.virtual_zip_bytes(&hash).unwrap_or_else(|| {
+                let path = format!("{}{}.zip", ResourcePaths::MOD_CACHE_FOLDER, hash);
+
+                assets.binary(&path)
+            });
+
+            send(NetplayPacket::SpectatorPackageZip {
+                index: local_index,
+                data,
+            });
+        }
+    }
+
+    fn share_checksums(&mut self) {
+        let Some(local_index) = self.local_index else {
+            return;
+        };
+
+        if self.is_solo() {
+            return;
+        }
+
+        // every frame before synced_time used input from every player
+        // making the state at synced_time and older final
+        while self.desync_detector.next_checksum_time() <= self.synced_time {
+            let time = self.desync_detector.next_checksum_time();
+
+            let simulation = if self.simulation.time == time {
+                Some(&mut self.simulation)
+            } else {
+                self.backups
+                    .iter_mut()
+                    .map(|backup| &mut backup.simulation)
+                    .find(|simulation| simulation.time == time)
+            };
+
+            let Some(simulation) = simulation else {
+                // backup was dropped before we could read it
+                self.desync_detector.skip_checksum();
+                continue;
+            };
+
+            let state_description = simulation.describe_synced_state();
+            let checksum = self.desync_detector.track_local_state(state_description);
+            self.desync_detector.save_report();
+
+            self.broadcast(NetplayPacket::Checksum {
+                index: local_index,
+                time,
+                checksum,
+            });
+        }
+    }
+
     fn input_synced(&self) -> bool {
         self.player_controllers
             .iter()
@@ -505,6 +738,19 @@ impl BattleScene {
         // update local buffer
         local_controller.buffer.push_last(data.clone());
 
+        // share with spectators
+        if let (Some(send), Some(history)) =
+            (&self.props.spectator_sender, &mut self.spectator_history)
+        {
+            send(NetplayPacket::SpectatorBuffer {
+                index: local_index,
+                time: history.len() as FrameTime,
+                data: data.clone(),
+            });
+
+            history.push_last(data.clone());
+        }
+
         let sync_dist = (self.simulation.time - self.synced_time) as i16;
 
         // gather buffer sizes for remotes to know if they should slow down
@@ -644,6 +890,8 @@ impl BattleScene {
 
         self.already_snapped = false;
 
+        self.resources.vm_manager.reset_instruction_budgets();
+
         self.load_input();
 
         // update simulation
@@ -662,12 +910,23 @@ impl BattleScene {
         simulation.pre_update(game_io, resources, state);
         state.update(game_io, resources, simulation);
         simulation.post_update(game_io, resources);
+        simulation.handle_exceeded_instruction_budgets(game_io, resources);
 
         if self.backups.len() > INPUT_BUFFER_LIMIT {
             self.backups.pop_front();
         }
     }
 
+    fn publish_lua_profile(&self, game_io: &mut GameIO) {
+        let vms = self.resources.vm_manager.vms();
+        let profile = self.resources.lua_profiler.end_frame(vms);
+
+        let globals = game_io.resource_mut::<Globals>().unwrap();
+
+        // clear the profile on exit to avoid displaying it outside of battle
+        globals.battle_lua_profile = if self.exiting { Vec::new() } else { profile };
+    }
+
     fn detect_debug_hotkeys(&mut self, game_io: &mut GameIO) {
         if !game_io.input().is_key_down(Key::F3) {
             return;
@@ -678,6 +937,12 @@ impl BattleScene {
             self.resources.vm_manager.print_memory_usage();
         }
 
+        // save time spent in each vm
+        if game_io.input().was_key_just_pressed(Key::P) {
+            let vms = self.resources.vm_manager.vms();
+            self.resources.lua_profiler.save_report(vms);
+        }
+
         if game_io.input().was_key_just_pressed(Key::I) {
             self.draw_player_indices = !self.draw_player_indices;
         }
@@ -686,15 +951,22 @@ impl BattleScene {
     fn exit(&mut self, game_io: &GameIO, fleeing: bool) {
         self.exiting = true;
 
-        if !self.is_playing_back_recording {
+        if self.local_index.is_some() {
             self.pending_signals.push(NetplaySignal::Disconnect);
         }
 
-        if let Some(statistics_callback) = self.props.statistics_callback.take() {
+        if !self.spectating && !self.is_playing_back_recording {
             self.simulation.wrap_up_statistics();
             let mut statistics = self.simulation.statistics.clone();
             statistics.ran = fleeing;
-            statistics_callback(Some(statistics));
+
+            if let Some(statistics_callback) = self.props.statistics_callback.take() {
+                statistics_callback(Some(statistics.clone()));
+            }
+
+            if statistics.won && !fleeing {
+                self.results = Some(statistics);
+            }
         }
 
         // clean up music stack
@@ -702,7 +974,199 @@ impl BattleScene {
         globals.audio.pop_music_stack();
     }
 
-    fn core_update(&mut self, game_io: &GameIO) {
+    fn playback_total_frames(&self) -> FrameTime {
+        self.player_controllers
+            .iter()
+            .map(|controller| controller.buffer.len() as FrameTime)
+            .max()
+            .unwrap_or_default()
+    }
+
+    fn playback_has_input(&self) -> bool {
+        self.simulation.time < self.playback_total_frames()
+    }
+
+    fn track_playback(&mut self) {
+        if let Some(timeline) = &mut self.playback_timeline {
+            timeline.track(&mut self.simulation);
+        }
+    }
+
+    fn update_playback(&mut self, game_io: &mut GameIO) {
+        let input_util = InputUtil::new(game_io);
+        let time = self.simulation.time;
+
+        if input_util.was_just_pressed(Input::Pause) {
+            self.playback_paused = !self.playback_paused;
+        }
+
+        let timeline = self.playback_timeline.as_ref();
+
+        let seek_target = if input_util.was_just_pressed(Input::RewindFrame) {
+            self.playback_paused = true;
+            Some(time - 1)
+        } else if input_util.was_just_pressed(Input::AdvanceFrame) {
+            self.playback_paused = true;
+            Some(time + 1)
+        } else if input_util.was_just_pressed(Input::Left) {
+            Some(time - PLAYBACK_SEEK_STEP)
+        } else if input_util.was_just_pressed(Input::Right) {
+            Some(time + PLAYBACK_SEEK_STEP)
+        } else if input_util.was_just_pressed(Input::ShoulderL) {
+            timeline.and_then(|timeline| timeline.previous_event_time(time))
+        } else if input_util.was_just_pressed(Input::ShoulderR) {
+            timeline.and_then(|timeline| timeline.next_event_time(time))
+        } else {
+            None
+        };
+
+        if let Some(time) = seek_target {
+            self.seek(game_io, time);
+            return;
+        }
+
+        self.playback_fast_forwarding = !self.playback_paused && input_util.is_down(Input::Up);
+
+        if self.playback_paused {
+            return;
+        }
+
+        let steps = if self.playback_fast_forwarding {
+            PLAYBACK_FAST_FORWARD_SPEED
+        } else {
+            1
+        };
+
+        for i in 0..steps {
+            if !self.playback_has_input() {
+                break;
+            }
+
+            // only the first step is audible
+            self.simulation.is_resimulation = i > 0;
+            self.simulate(game_io);
+            self.track_playback();
+        }
+
+        self.simulation.is_resimulation = false;
+    }
+
+    /// Moves playback to the specified frame,
+    /// restarts playback if the frame is older than our backups
+    fn seek(&mut self, game_io: &mut GameIO, time: FrameTime) {
+        let time = time.clamp(0, self.playback_total_frames());
+        let current_time = self.simulation.time;
+
+        if time < current_time {
+            let steps = (current_time - time) as usize;
+
+            if steps < self.backups.len() {
+                self.rewind(game_io, steps);
+            } else {
+                self.restart_playback(game_io);
+            }
+        }
+
+        self.simulation.is_resimulation = true;
+
+        while self.simulation.time < time {
+            self.simulate(game_io);
+            self.track_playback();
+        }
+
+        self.simulation.is_resimulation = false;
+    }
+
+    fn restart_playback(&mut self, game_io: &mut GameIO) {
+        let props = BattleProps {
+            encounter_package_pair: self.props.encounter_package_pair.clone(),
+            data: self.props.data.clone(),
+            seed: self.props.seed,
+            background: self.props.background.clone(),
+            player_setups: self.props.player_setups.clone(),
+            senders: Default::default(),
+            receivers: Default::default(),
+            spectator_sender: None,
+            statistics_callback: None,
+            reward_receiver: None,
+            recording_enabled: false,
+        };
+
+        // the new simulation resolves its music stack depth from the current stack
+        let globals = game_io.resource::<Globals>().unwrap();
+        globals.audio.pop_music_stack();
+
+        let mut scene = Self::new_from_props(game_io, props, true);
+
+        let globals = game_io.resource::<Globals>().unwrap();
+        globals.audio.push_music_stack();
+
+        // retain playback state
+        scene.playback_timeline = self.playback_timeline.take();
+        scene.playback_paused = self.playback_paused;
+
+        *self = scene;
+    }
+
+    /// Simulates playback as fast as possible without rendering,
+    /// until the battle ends, the input runs out, or the frame limit is reached
+    pub fn simulate_headless(&mut self, game_io: &mut GameIO, frame_limit: FrameTime) {
+        while !self.simulation.exit
+            && self.simulation.time < frame_limit
+            && self.playback_has_input()
+        {
+            self.update_textbox(game_io);
+            self.simulate(game_io);
+        }
+
+        self.simulation.wrap_up_statistics();
+    }
+
+    pub fn simulation(&self) -> &BattleSimulation {
+        &self.simulation
+    }
+
+    fn spectator_can_simulate(&self) -> bool {
+        let has_input = self
+            .player_controllers
+            .iter()
+            .any(|controller| !controller.buffer.is_empty());
+
+        // stay behind connected players, in case their input arrives late
+        let has_delayed_input = self
+            .player_controllers
+            .iter()
+            .all(|controller| !controller.connected || controller.buffer.len() > SPECTATOR_DELAY);
+
+        has_input && has_delayed_input && self.input_synced()
+    }
+
+    fn update_spectating(&mut self, game_io: &GameIO) {
+        // simulate extra frames to catch up after joining late
+        for i in 0..SPECTATOR_CATCH_UP_SPEED {
+            if !self.spectator_can_simulate() {
+                break;
+            }
+
+            // only the first step is audible
+            self.simulation.is_resimulation = i > 0;
+            self.simulate(game_io);
+        }
+
+        self.simulation.is_resimulation = false;
+    }
+
+    fn core_update(&mut self, game_io: &mut GameIO) {
+        if self.is_playing_back_recording {
+            self.update_playback(game_io);
+            return;
+        }
+
+        if self.spectating {
+            self.update_spectating(game_io);
+            return;
+        }
+
         let input_util = InputUtil::new(game_io);
 
         if self.frame_by_frame_debug {
@@ -722,21 +1186,10 @@ impl BattleScene {
             // exit from frame_by_frame_debug with pause
             self.frame_by_frame_debug = !input_util.was_just_pressed(Input::Pause);
         } else {
-            // normal update
-            let can_simulate = if self.is_playing_back_recording {
-                let controller_iter = self.player_controllers.iter();
-                let total_frames = controller_iter
-                    .map(|controller| controller.buffer.len() as FrameTime)
-                    .max()
-                    .unwrap_or_default();
-
-                // simulate as long as we have input
-                self.simulation.time < total_frames
-            } else {
-                // simulate as long as we can roll back to the synced time
-                self.simulation.time < self.synced_time + INPUT_BUFFER_LIMIT as FrameTime
-                    || self.input_synced()
-            };
+            // normal update, simulate as long as we can roll back to the synced time
+            let can_simulate = self.simulation.time
+                < self.synced_time + INPUT_BUFFER_LIMIT as FrameTime
+                || self.input_synced();
             let should_slow_down = self.slow_cooldown == SLOW_COOLDOWN;
 
             if !should_slow_down && can_simulate {
@@ -744,7 +1197,7 @@ impl BattleScene {
                 self.simulate(game_io);
             }
 
-            self.frame_by_frame_debug = (self.is_playing_back_recording || self.is_solo())
+            self.frame_by_frame_debug = self.is_solo()
                 && (input_util.was_just_pressed(Input::RewindFrame)
                     || input_util.was_just_pressed(Input::AdvanceFrame));
         }
@@ -753,13 +1206,22 @@ impl BattleScene {
     fn handle_exit_requests(&mut self, game_io: &GameIO) {
         let requested_exit = if self.is_playing_back_recording {
             // pressing confirm or cancel, without pressing pause
-            // as pause is used to exit frame_by_frame_debug
+            // as pause is used to pause playback
             // and the same input may also be binded to Confirm or Cancel
             let input_util = InputUtil::new(game_io);
 
             !input_util.was_just_pressed(Input::Pause)
                 && (input_util.was_just_pressed(Input::Cancel)
                     || input_util.was_just_pressed(Input::Confirm))
+        } else if self.spectating {
+            // spectators never roll back, and stop when there's no more input to follow
+            let input_util = InputUtil::new(game_io);
+            let out_of_input = self
+                .player_controllers
+                .iter()
+                .all(|controller| !controller.connected && controller.buffer.is_empty());
+
+            self.simulation.exit || out_of_input || input_util.was_just_pressed(Input::Cancel)
         } else {
             // use the oldest backup, as we can still rewind and end up not exitting otherwise
             let oldest_backup = self.backups.front();
@@ -777,7 +1239,14 @@ impl BattleScene {
             // need to exit, not currently exiting, no pending signals that must be shared
             // safe to exit
             let transition = crate::transitions::new_battle_pop(game_io);
-            self.next_scene = NextScene::new_pop().with_transition(transition);
+
+            self.next_scene = if let Some(statistics) = self.results.take() {
+                let reward_receiver = self.props.reward_receiver.take();
+                let scene = BattleResultsScene::new(game_io, statistics, reward_receiver);
+                NextScene::new_swap(scene).with_transition(transition)
+            } else {
+                NextScene::new_pop().with_transition(transition)
+            };
         }
     }
 }
@@ -803,7 +1272,9 @@ impl Scene for BattleScene {
                 player_setups: std::mem::take(&mut self.props.player_setups),
                 senders: Default::default(),
                 receivers: Default::default(),
+                spectator_sender: None,
                 statistics_callback: None,
+                reward_receiver: None,
                 recording_enabled: true,
             };
 
@@ -815,8 +1286,10 @@ impl Scene for BattleScene {
         self.update_textbox(game_io);
         self.handle_packets(game_io);
         self.core_update(game_io);
+        self.share_checksums();
         self.detect_debug_hotkeys(game_io);
         self.handle_exit_requests(game_io);
+        self.publish_lua_profile(game_io);
     }
 
     fn draw(&mut self, game_io: &mut GameIO, render_pass: &mut RenderPass) {
@@ -847,9 +1320,33 @@ impl Scene for BattleScene {
         self.resources
             .draw_fade_sprite(&mut sprite_queue, fade_color);
 
+        // draw playback controls
+        if let Some(timeline) = &mut self.playback_timeline {
+            timeline.draw(
+                game_io,
+                &mut sprite_queue,
+                self.simulation.time,
+                self.playback_paused,
+                self.playback_fast_forwarding,
+            );
+        }
+
         // draw textbox over everything
         self.textbox.draw(game_io, &mut sprite_queue);
 
+        // non blocking desync notification
+        if self.desync_detector.desync_time().is_some() {
+            let mut text = Text::new(game_io, FontName::Thin);
+            text.style.color = Color::ORANGE;
+            text.style.shadow_color = Color::BLACK;
+            text.text = String::from("Desync detected");
+
+            let text_size = text.measure().size;
+            let position = Vec2::new(2.0, RESOLUTION_F.y - text_size.y - 2.0);
+            text.style.bounds.set_position(position);
+            text.draw(game_io, &mut sprite_queue);
+        }
+
         render_pass.consume_queue(sprite_queue);
     }
 }
@@ -159,11 +159,27 @@ impl BootScene {
             }
         }
 
+        if self.done {
+            self.run_headless_battle(game_io);
+        }
+
         let metrics = self.status_label.measure();
         let status_position = self.status_position - metrics.size * 0.5;
         self.status_label.style.bounds.set_position(status_position);
     }
 
+    fn run_headless_battle(&mut self, game_io: &mut GameIO) {
+        let globals = game_io.resource_mut::<Globals>().unwrap();
+
+        let Some(config) = globals.headless_battle_config.take() else {
+            return;
+        };
+
+        let report = config.run(game_io, &self.log_receiver);
+        config.output_report(&report);
+        config.exit(&report);
+    }
+
     fn update_progress_bar(&mut self, multiplier: f32) {
         let mut bounds = self.progress_bar_bounds;
 
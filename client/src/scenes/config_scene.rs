@@ -10,7 +10,7 @@ use packets::structures::{FileHash, PackageCategory, PackageId};
 use std::cell::RefCell;
 use std::collections::HashMap;
 use std::rc::Rc;
-use strum::{EnumIter, IntoEnumIterator, IntoStaticStr};
+use strum::{EnumIter, IntoEnumIterator};
 
 #[derive(Clone)]
 enum Event {
@@ -29,7 +29,7 @@ enum Event {
     Leave { save: bool },
 }
 
-#[derive(EnumIter, IntoStaticStr, Clone, Copy)]
+#[derive(EnumIter, Clone, Copy)]
 enum ConfigCategory {
     Video,
     Audio,
@@ -39,6 +39,19 @@ enum ConfigCategory {
     Profile,
 }
 
+impl ConfigCategory {
+    fn localization_key(self) -> &'static str {
+        match self {
+            ConfigCategory::Video => "config.categories.video",
+            ConfigCategory::Audio => "config.categories.audio",
+            ConfigCategory::Keyboard => "config.categories.keyboard",
+            ConfigCategory::Gamepad => "config.categories.gamepad",
+            ConfigCategory::Mods => "config.categories.mods",
+            ConfigCategory::Profile => "config.categories.profile",
+        }
+    }
+}
+
 pub struct ConfigScene {
     camera: Camera,
     background: Background,
@@ -63,6 +76,7 @@ impl ConfigScene {
     pub fn new(game_io: &GameIO) -> Box<Self> {
         let globals = game_io.resource::<Globals>().unwrap();
         let assets = &globals.assets;
+        let localization = &globals.localization;
 
         // cursor
         let cursor_sprite = assets.new_sprite(game_io, ResourcePaths::SELECT_CURSOR);
@@ -105,18 +119,29 @@ impl ConfigScene {
                 primary_layout_start,
             ),
             secondary_layout: ScrollableList::new(game_io, secondary_bounds, 16.0)
-                .with_label_str("VIDEO")
-                .with_children(Self::generate_video_menu(&config))
+                .with_label_str(&localization.get("config.categories.video").to_uppercase())
+                .with_children(Self::generate_video_menu(game_io, &config))
                 .with_focus(false),
             cursor_sprite,
             cursor_animator,
             event_sender,
             event_receiver,
-            context_menu: ContextMenu::new(game_io, "BINDING", context_position).with_options(
+            context_menu: ContextMenu::new(
+                game_io,
+                localization.get("config.binding.title"),
+                context_position,
+            )
+            .with_options(
                 game_io,
                 [
-                    ("Append", BindingContextOption::Append),
-                    ("Clear", BindingContextOption::Clear),
+                    (
+                        localization.get("config.binding.append"),
+                        BindingContextOption::Append,
+                    ),
+                    (
+                        localization.get("config.binding.clear"),
+                        BindingContextOption::Clear,
+                    ),
                 ],
             ),
             context_sender: None,
@@ -133,7 +158,9 @@ impl ConfigScene {
         event_sender: flume::Sender<Event>,
         start: Vec2,
     ) -> UiLayout {
-        let assets = &game_io.resource::<Globals>().unwrap().assets;
+        let globals = game_io.resource::<Globals>().unwrap();
+        let assets = &globals.assets;
+        let localization = &globals.localization;
         let ui_texture = assets.texture(game_io, ResourcePaths::UI_NINE_PATCHES);
         let ui_animator = Animator::load_new(assets, ResourcePaths::UI_NINE_PATCHES_ANIMATION);
         let button_9patch = build_9patch!(game_io, ui_texture, &ui_animator, "BUTTON");
@@ -148,7 +175,9 @@ impl ConfigScene {
             Rect::new(start.x, start.y, f32::INFINITY, f32::INFINITY),
             ConfigCategory::iter()
                 .map(|option| {
-                    UiButton::new_text(game_io, FontName::Thick, option.into())
+                    let label = localization.get(option.localization_key());
+
+                    UiButton::new_text(game_io, FontName::Thick, label)
                         .on_activate({
                             let event_sender = event_sender.clone();
 
@@ -181,21 +210,23 @@ impl ConfigScene {
         event_sender: &flume::Sender<Event>,
     ) -> Vec<Box<dyn UiNode>> {
         match category {
-            ConfigCategory::Video => Self::generate_video_menu(config),
+            ConfigCategory::Video => Self::generate_video_menu(game_io, config),
             ConfigCategory::Audio => Self::generate_audio_menu(game_io, config),
             ConfigCategory::Keyboard => Self::generate_keyboard_menu(game_io, config, event_sender),
             ConfigCategory::Gamepad => {
                 Self::generate_controller_menu(game_io, config, event_sender)
             }
-            ConfigCategory::Mods => Self::generate_mods_menu(game_io, event_sender),
-            ConfigCategory::Profile => Self::generate_profile_menu(game_io, event_sender),
+            ConfigCategory::Mods => Self::generate_mods_menu(game_io, config, event_sender),
+            ConfigCategory::Profile => Self::generate_profile_menu(game_io, config, event_sender),
         }
     }
 
-    fn generate_video_menu(config: &Rc<RefCell<Config>>) -> Vec<Box<dyn UiNode>> {
+    fn generate_video_menu(game_io: &GameIO, config: &Rc<RefCell<Config>>) -> Vec<Box<dyn UiNode>> {
+        let localization = &game_io.resource::<Globals>().unwrap().localization;
+
         vec![
             Box::new(UiConfigToggle::new(
-                "Fullscreen",
+                localization.get("config.video.fullscreen"),
                 config.borrow().fullscreen,
                 config.clone(),
                 |game_io, mut config| {
@@ -207,7 +238,7 @@ impl ConfigScene {
                 },
             )),
             Box::new(UiConfigToggle::new(
-                "VSync",
+                localization.get("config.video.vsync"),
                 config.borrow().vsync,
                 config.clone(),
                 |game_io, mut config| {
@@ -219,7 +250,7 @@ impl ConfigScene {
                 },
             )),
             Box::new(UiConfigToggle::new(
-                "Lock Aspect",
+                localization.get("config.video.lock_aspect"),
                 config.borrow().lock_aspect_ratio,
                 config.clone(),
                 |game_io, mut config| {
@@ -237,7 +268,7 @@ impl ConfigScene {
                 },
             )),
             Box::new(UiConfigToggle::new(
-                "Integer Scaling",
+                localization.get("config.video.integer_scaling"),
                 config.borrow().integer_scaling,
                 config.clone(),
                 |game_io, mut config| {
@@ -250,7 +281,7 @@ impl ConfigScene {
                 },
             )),
             Box::new(UiConfigToggle::new(
-                "Snap Resize",
+                localization.get("config.video.snap_resize"),
                 config.borrow().snap_resize,
                 config.clone(),
                 |game_io, mut config| {
@@ -264,7 +295,7 @@ impl ConfigScene {
             )),
             Box::new(
                 UiConfigPercentage::new(
-                    "Brightness",
+                    localization.get("config.video.brightness"),
                     config.borrow().brightness,
                     config.clone(),
                     |game_io, mut config, value| {
@@ -281,7 +312,7 @@ impl ConfigScene {
                 .with_lower_bound(10),
             ),
             Box::new(UiConfigPercentage::new(
-                "Saturation",
+                localization.get("config.video.saturation"),
                 config.borrow().saturation,
                 config.clone(),
                 |game_io, mut config, value| {
@@ -297,7 +328,7 @@ impl ConfigScene {
             )),
             Box::new(
                 UiConfigPercentage::new(
-                    "Ghosting",
+                    localization.get("config.video.ghosting"),
                     config.borrow().ghosting,
                     config.clone(),
                     |game_io, mut config, value| {
@@ -313,14 +344,17 @@ impl ConfigScene {
                 .with_upper_bound(98),
             ),
             Box::new(UiConfigCycle::new(
-                "Color Sim",
+                localization.get("config.video.color_sim"),
                 config.borrow().color_blindness,
                 config.clone(),
                 &[
-                    ("Prot", 0),
-                    ("Deut", 1),
-                    ("Trit", 2),
-                    ("Off", PostProcessColorBlindness::TOTAL_OPTIONS),
+                    (localization.get("config.video.color_sim_prot"), 0),
+                    (localization.get("config.video.color_sim_deut"), 1),
+                    (localization.get("config.video.color_sim_trit"), 2),
+                    (
+                        localization.get("config.video.color_sim_off"),
+                        PostProcessColorBlindness::TOTAL_OPTIONS,
+                    ),
                 ],
                 |game_io, mut config, value, _| {
                     let globals = game_io.resource_mut::<Globals>().unwrap();
@@ -339,10 +373,13 @@ impl ConfigScene {
         game_io: &mut GameIO,
         config: &Rc<RefCell<Config>>,
     ) -> Vec<Box<dyn UiNode>> {
+        let localization = game_io.resource::<Globals>().unwrap().localization.clone();
+        let auto_text = localization.get("config.audio.device_auto").to_string();
+
         vec![
             Box::new(
                 UiConfigPercentage::new(
-                    "Music",
+                    localization.get("config.audio.music"),
                     config.borrow().music,
                     config.clone(),
                     |game_io, mut config, value| {
@@ -358,7 +395,7 @@ impl ConfigScene {
                 .with_auditory_feedback(false),
             ),
             Box::new(UiConfigPercentage::new(
-                "SFX",
+                localization.get("config.audio.sfx"),
                 config.borrow().sfx,
                 config.clone(),
                 |game_io, mut config, value| {
@@ -373,7 +410,7 @@ impl ConfigScene {
                 },
             )),
             Box::new(UiConfigToggle::new(
-                "Mute Music",
+                localization.get("config.audio.mute_music"),
                 config.borrow().mute_music,
                 config.clone(),
                 |game_io, mut config| {
@@ -386,7 +423,7 @@ impl ConfigScene {
                 },
             )),
             Box::new(UiConfigToggle::new(
-                "Mute SFX",
+                localization.get("config.audio.mute_sfx"),
                 config.borrow().mute_sfx,
                 config.clone(),
                 |game_io, mut config| {
@@ -400,12 +437,12 @@ impl ConfigScene {
             )),
             Box::new(UiConfigDynamicCycle::new(
                 game_io,
-                "Device",
+                localization.get("config.audio.device"),
                 config.borrow().audio_device.clone(),
                 config.clone(),
-                |_, value| {
+                move |_, value| {
                     if value.is_empty() {
-                        String::from("Auto")
+                        auto_text.clone()
                     } else {
                         value.clone()
                     }
@@ -444,15 +481,23 @@ impl ConfigScene {
         config: &Rc<RefCell<Config>>,
         event_sender: &flume::Sender<Event>,
     ) -> Vec<Box<dyn UiNode>> {
+        let localization = &game_io.resource::<Globals>().unwrap().localization;
+
         let mut children: Vec<Box<dyn UiNode>> = vec![
             Box::new(UiConfigCycle::new(
-                "Style",
+                localization.get("config.keyboard.style"),
                 config.borrow().key_style,
                 config.clone(),
                 &[
-                    ("Mix", KeyStyle::Mix),
-                    ("WASD", KeyStyle::Wasd),
-                    ("Emulator", KeyStyle::Emulator),
+                    (localization.get("config.keyboard.style_mix"), KeyStyle::Mix),
+                    (
+                        localization.get("config.keyboard.style_wasd"),
+                        KeyStyle::Wasd,
+                    ),
+                    (
+                        localization.get("config.keyboard.style_emulator"),
+                        KeyStyle::Emulator,
+                    ),
                 ],
                 |game_io, mut config, value, confirmed| {
                     config.key_style = value;
@@ -465,7 +510,12 @@ impl ConfigScene {
                 },
             )),
             Box::new(
-                UiButton::new_text(game_io, FontName::Thick, "Reset Binds").on_activate({
+                UiButton::new_text(
+                    game_io,
+                    FontName::Thick,
+                    localization.get("config.keyboard.reset_binds"),
+                )
+                .on_activate({
                     let config = config.clone();
                     let event_sender = event_sender.clone();
 
@@ -502,10 +552,12 @@ impl ConfigScene {
         config: &Rc<RefCell<Config>>,
         event_sender: &flume::Sender<Event>,
     ) -> Vec<Box<dyn UiNode>> {
+        let localization = game_io.resource::<Globals>().unwrap().localization.clone();
+
         let mut children: Vec<Box<dyn UiNode>> = vec![
             Box::new(UiConfigDynamicCycle::new(
                 game_io,
-                "Active Gamepad",
+                localization.get("config.gamepad.active_gamepad"),
                 config.borrow().controller_index,
                 config.clone(),
                 |_, value| value.to_string(),
@@ -526,7 +578,12 @@ impl ConfigScene {
                 },
             )),
             Box::new(
-                UiButton::new_text(game_io, FontName::Thick, "Reset Binds").on_activate({
+                UiButton::new_text(
+                    game_io,
+                    FontName::Thick,
+                    localization.get("config.gamepad.reset_binds"),
+                )
+                .on_activate({
                     let config = config.clone();
 
                     move || {
@@ -557,6 +614,7 @@ impl ConfigScene {
 
     fn generate_mods_menu(
         game_io: &GameIO,
+        config: &Rc<RefCell<Config>>,
         event_sender: &flume::Sender<Event>,
     ) -> Vec<Box<dyn UiNode>> {
         let create_button = |name: &str, event: Event| -> Box<dyn UiNode> {
@@ -569,18 +627,75 @@ impl ConfigScene {
             )
         };
 
+        let localization = &game_io.resource::<Globals>().unwrap().localization;
+
         vec![
-            create_button("Manage Mods", Event::ViewPackages),
-            create_button("Update Mods", Event::UpdatePackages),
-            create_button("Resource Mods", Event::ReorderResources),
-            create_button("Clear Cache", Event::ClearCache),
+            create_button(localization.get("config.mods.manage"), Event::ViewPackages),
+            create_button(
+                localization.get("config.mods.update"),
+                Event::UpdatePackages,
+            ),
+            create_button(
+                localization.get("config.mods.resources"),
+                Event::ReorderResources,
+            ),
+            create_button(
+                localization.get("config.mods.clear_cache"),
+                Event::ClearCache,
+            ),
+            // when disabled, recordings reference cached mods by hash instead of embedding them
+            Box::new(UiConfigToggle::new(
+                localization.get("config.mods.record"),
+                config.borrow().embed_recording_packages,
+                config.clone(),
+                |_, mut config| {
+                    config.embed_recording_packages = !config.embed_recording_packages;
+                    config.embed_recording_packages
+                },
+            )),
         ]
     }
 
     fn generate_profile_menu(
-        game_io: &GameIO,
+        game_io: &mut GameIO,
+        config: &Rc<RefCell<Config>>,
         event_sender: &flume::Sender<Event>,
     ) -> Vec<Box<dyn UiNode>> {
+        let localization = game_io.resource::<Globals>().unwrap().localization.clone();
+
+        let language_cycle = UiConfigDynamicCycle::new(
+            game_io,
+            localization.get("config.profile.language"),
+            config.borrow().language.clone(),
+            config.clone(),
+            |game_io, value| {
+                let globals = game_io.resource::<Globals>().unwrap();
+
+                globals
+                    .languages
+                    .iter()
+                    .find(|language| language.code == *value)
+                    .map(|language| language.name.clone())
+                    .unwrap_or_else(|| value.clone())
+            },
+            |game_io, previous_value, cycle_right| {
+                let globals = game_io.resource::<Globals>().unwrap();
+
+                UiConfigDynamicCycle::cycle_slice(&globals.languages, cycle_right, |language| {
+                    language.code == *previous_value
+                })
+                .map(|language| language.code.clone())
+                .unwrap_or_else(|| previous_value.clone())
+            },
+            |game_io, mut config, value| {
+                // menus generated after this point will use the new language
+                let globals = game_io.resource_mut::<Globals>().unwrap();
+                globals.localization = Localization::load(&globals.assets, value);
+
+                config.language.clone_from(value);
+            },
+        );
+
         let create_button = |name: &str, event: Event| -> Box<dyn UiNode> {
             let event_sender = event_sender.clone();
 
@@ -591,10 +706,13 @@ impl ConfigScene {
             )
         };
 
-        vec![create_button(
-            "Change Nickname",
-            Event::RequestNicknameChange,
-        )]
+        vec![
+            Box::new(language_cycle),
+            create_button(
+                localization.get("config.profile.change_nickname"),
+                Event::RequestNicknameChange,
+            ),
+        ]
     }
 }
 
@@ -621,7 +739,8 @@ impl Scene for ConfigScene {
             SpriteColorQueue::new(game_io, &self.camera, SpriteColorMode::Multiply);
 
         self.frame.draw(&mut sprite_queue);
-        SceneTitle::new("CONFIG").draw(game_io, &mut sprite_queue);
+        let localization = &game_io.resource::<Globals>().unwrap().localization;
+        SceneTitle::new(localization.get("config.title")).draw(game_io, &mut sprite_queue);
 
         self.primary_layout.draw(game_io, &mut sprite_queue);
         self.secondary_layout.draw(game_io, &mut sprite_queue);
@@ -646,9 +765,10 @@ impl ConfigScene {
                     let children =
                         Self::generate_submenu(game_io, &self.config, category, &self.event_sender);
 
-                    let label: &'static str = category.into();
+                    let localization = &game_io.resource::<Globals>().unwrap().localization;
+                    let label = localization.get(category.localization_key());
 
-                    self.secondary_layout.set_label(label.to_ascii_uppercase());
+                    self.secondary_layout.set_label(label.to_uppercase());
                     self.secondary_layout.set_children(children);
                 }
                 Event::ApplyKeyBinds => {
@@ -697,8 +817,8 @@ impl ConfigScene {
                     let (doorstop, doorstop_remover) = TextboxDoorstop::new();
                     self.doorstop_remover = Some(doorstop_remover);
 
-                    self.textbox
-                        .push_interface(doorstop.with_str("Checking for updates..."));
+                    let message = globals.localization.get("config.mods.checking_for_updates");
+                    self.textbox.push_interface(doorstop.with_str(message));
                     self.textbox.open();
 
                     game_io
@@ -725,14 +845,17 @@ impl ConfigScene {
                         })
                         .collect();
 
+                    let localization = &globals.localization;
+
                     if requires_update.is_empty() {
-                        let interface =
-                            TextboxMessage::new(String::from("All packages are up to date."));
+                        let message = localization.get("config.mods.up_to_date");
+                        let interface = TextboxMessage::new(message.to_string());
                         self.textbox.push_interface(interface);
                     } else {
                         let event_sender = self.event_sender.clone();
-                        let interface = TextboxMessage::new(String::from("Updates found."))
-                            .with_callback(move || {
+                        let message = localization.get("config.mods.updates_found");
+                        let interface =
+                            TextboxMessage::new(message.to_string()).with_callback(move || {
                                 event_sender
                                     .send(Event::ViewUpdates(requires_update))
                                     .unwrap()
@@ -756,23 +879,24 @@ impl ConfigScene {
                 Event::ClearCache => {
                     let globals = &mut game_io.resource::<Globals>().unwrap();
 
-                    let message = if !globals.connected_to_server {
+                    let message_key = if !globals.connected_to_server {
                         match std::fs::remove_dir_all(ResourcePaths::SERVER_CACHE_FOLDER) {
-                            Ok(()) => String::from("Successfully cleared cache."),
+                            Ok(()) => "config.mods.cache_cleared",
                             Err(e) => {
                                 log::error!("{e}");
 
                                 if matches!(e.kind(), std::io::ErrorKind::NotFound) {
-                                    String::from("Cache is already empty.")
+                                    "config.mods.cache_empty"
                                 } else {
-                                    String::from("Failed to clear cache.")
+                                    "config.mods.cache_clear_failed"
                                 }
                             }
                         }
                     } else {
-                        String::from("You should jack out before clearing cache.")
+                        "config.mods.cache_jack_out"
                     };
 
+                    let message = globals.localization.get(message_key).to_string();
                     let interface = TextboxMessage::new(message);
 
                     self.textbox.push_interface(interface);
@@ -792,6 +916,12 @@ impl ConfigScene {
                         // input
                         config.key_bindings = self.key_bindings_backup.clone();
 
+                        // language
+                        if globals.localization.language() != config.language {
+                            globals.localization =
+                                Localization::load(&globals.assets, &config.language);
+                        }
+
                         // audio
                         let audio = &mut globals.audio;
 
@@ -933,19 +1063,22 @@ impl ConfigScene {
         let event_sender = self.event_sender.clone();
 
         // get permission to save
+        let localization = &globals.localization;
+
         let interface = if config.validate() {
-            TextboxQuestion::new(String::from("Save changes?"), move |save| {
+            let message = localization.get("config.save_question").to_string();
+
+            TextboxQuestion::new(message, move |save| {
                 let _ = event_sender.send(Event::Leave { save });
             })
         } else {
-            TextboxQuestion::new(
-                String::from("Config is invalid, use old config?"),
-                move |leave| {
-                    if leave {
-                        let _ = event_sender.send(Event::Leave { save: false });
-                    }
-                },
-            )
+            let message = localization.get("config.invalid_question").to_string();
+
+            TextboxQuestion::new(message, move |leave| {
+                if leave {
+                    let _ = event_sender.send(Event::Leave { save: false });
+                }
+            })
         };
 
         self.textbox.push_interface(interface);
@@ -58,6 +58,7 @@ pub struct DeckEditorScene {
 impl DeckEditorScene {
     pub fn new(game_io: &GameIO, deck_index: usize) -> Self {
         let globals = game_io.resource::<Globals>().unwrap();
+        let localization = &globals.localization;
 
         // limits
         let global_save = &globals.global_save;
@@ -121,16 +122,27 @@ impl DeckEditorScene {
             scene_time: 0,
             page_tracker: PageTracker::new(game_io, 2)
                 .with_page_arrow_offset(0, pack_dock.page_arrow_offset),
-            context_menu: ContextMenu::new(game_io, "SORT", Vec2::ZERO).with_options(
+            context_menu: ContextMenu::new(
+                game_io,
+                localization.get("deck_editor.sort"),
+                Vec2::ZERO,
+            )
+            .with_options(
                 game_io,
                 [
-                    ("ID", Sorting::Id),
-                    ("ABCDE", Sorting::Alphabetical),
-                    ("Code", Sorting::Code),
-                    ("Attack", Sorting::Damage),
-                    ("Element", Sorting::Element),
-                    ("No.", Sorting::Number),
-                    ("Class", Sorting::Class),
+                    (localization.get("deck_editor.sort_id"), Sorting::Id),
+                    (
+                        localization.get("deck_editor.sort_alphabetical"),
+                        Sorting::Alphabetical,
+                    ),
+                    (localization.get("deck_editor.sort_code"), Sorting::Code),
+                    (localization.get("deck_editor.sort_attack"), Sorting::Damage),
+                    (
+                        localization.get("deck_editor.sort_element"),
+                        Sorting::Element,
+                    ),
+                    (localization.get("deck_editor.sort_number"), Sorting::Number),
+                    (localization.get("deck_editor.sort_class"), Sorting::Class),
                 ],
             ),
             last_sort: None,
@@ -233,7 +245,8 @@ impl Scene for DeckEditorScene {
 
         // draw title
         self.frame.draw(&mut sprite_queue);
-        SceneTitle::new("FOLDER EDIT").draw(game_io, &mut sprite_queue);
+        let localization = &game_io.resource::<Globals>().unwrap().localization;
+        SceneTitle::new(localization.get("deck_editor.title")).draw(game_io, &mut sprite_queue);
 
         // draw docks
         for (page, offset) in self.page_tracker.visible_pages() {
@@ -319,9 +332,9 @@ fn handle_events(scene: &mut DeckEditorScene, game_io: &mut GameIO) {
             match mode {
                 EditorMode::Default => {}
                 EditorMode::Regular => {
-                    let interface = TextboxMessage::new(String::from(
-                        "Choose a card to\nuse as a Regular\nCard!",
-                    ));
+                    let localization = &game_io.resource::<Globals>().unwrap().localization;
+                    let message = localization.get("deck_editor.choose_regular");
+                    let interface = TextboxMessage::new(message.to_string());
                     scene.textbox.push_interface(interface);
                 }
             }
@@ -406,8 +419,10 @@ fn handle_input(scene: &mut DeckEditorScene, game_io: &mut GameIO) {
                     event_sender.send(Event::Leave(response)).unwrap();
                 };
 
-                let textbox_interface =
-                    TextboxQuestion::new(format!("Equip {}?", old_deck.name), callback);
+                let message = globals
+                    .localization
+                    .format("deck_editor.equip_question", &[("name", &old_deck.name)]);
+                let textbox_interface = TextboxQuestion::new(message, callback);
 
                 scene.textbox.push_interface(textbox_interface);
                 scene.textbox.open();
@@ -441,8 +456,10 @@ fn handle_input(scene: &mut DeckEditorScene, game_io: &mut GameIO) {
     // handle selecting regular card
     if scene.page_tracker.active_page() == 0 && input_util.was_released(Input::Option2) {
         let event_sender = scene.event_sender.clone();
+        let localization = &game_io.resource::<Globals>().unwrap().localization;
+        let message = localization.get("deck_editor.choose_regular_question");
 
-        let interface = TextboxQuestion::new(String::from("Choose Regular Card?"), move |yes| {
+        let interface = TextboxQuestion::new(message.to_string(), move |yes| {
             if yes {
                 event_sender
                     .send(Event::SwitchMode(EditorMode::Regular))
@@ -472,7 +489,9 @@ fn handle_context_menu_input(scene: &mut DeckEditorScene, game_io: &mut GameIO)
         return;
     };
 
-    let card_manager = &game_io.resource::<Globals>().unwrap().card_packages;
+    let globals = game_io.resource::<Globals>().unwrap();
+    let card_manager = &globals.card_packages;
+    let language = globals.localization.language();
     let dock = match scene.page_tracker.active_page() {
         0 => &mut scene.deck_dock,
         1 => &mut scene.pack_dock,
@@ -490,7 +509,7 @@ fn handle_context_menu_input(scene: &mut DeckEditorScene, game_io: &mut GameIO)
                 .package(NAMESPACE, &item.card.package_id)
                 .unwrap();
 
-            package.card_properties.short_name.clone()
+            package.localized_name(language).to_string()
         }),
         Sorting::Code => sort_card_items(card_slots, |item: &CardListItem| item.card.code.clone()),
         Sorting::Damage => sort_card_items(card_slots, |item: &CardListItem| {
@@ -619,13 +638,15 @@ fn select_regular_card(scene: &mut DeckEditorScene, game_io: &GameIO) {
             }
         }
 
-        let interface = TextboxMessage::new(String::from("Finished setting up\nthe Regular Card"));
+        let message = globals.localization.get("deck_editor.regular_set");
+        let interface = TextboxMessage::new(message.to_string());
         scene.textbox.push_interface(interface);
         scene.textbox.open();
 
         globals.audio.play_sound(&globals.sfx.card_select_confirm);
     } else {
-        let interface = TextboxMessage::new(String::from("Regular Card\nsettings released."));
+        let message = globals.localization.get("deck_editor.regular_released");
+        let interface = TextboxMessage::new(message.to_string());
         scene.textbox.push_interface(interface);
         scene.textbox.open();
 
@@ -0,0 +1,410 @@
+use crate::bindable::SpriteColorMode;
+use crate::overworld::OverworldEvent;
+use crate::packages::PackageNamespace;
+use crate::render::ui::{
+    FontName, SceneTitle, ScrollTracker, ScrollableFrame, SubSceneFrame, TextStyle, Textbox,
+    TextboxDoorstop, TextboxMessage, TextboxQuestion, UiInputTracker,
+};
+use crate::render::{Animator, AnimatorLoopMode, Background, Camera, SpriteColorQueue};
+use crate::resources::{AssetManager, Globals, Input, ResourcePaths, TEXT_DARK_SHADOW_COLOR};
+use framework::prelude::*;
+use packets::structures::{Mail, MailAttachment, MailCategory};
+
+const LINE_HEIGHT: f32 = 16.0;
+
+enum Event {
+    Claim(usize),
+}
+
+struct MailEntry {
+    mail: Mail,
+    mug_animator: Animator,
+    mug_sprite: Sprite,
+}
+
+pub struct MailScene {
+    camera: Camera,
+    background: Background,
+    frame: SubSceneFrame,
+    entries: Vec<MailEntry>,
+    icon_animator: Animator,
+    icon_sprite: Sprite,
+    new_animator: Animator,
+    new_sprite: Sprite,
+    cursor_start: Vec2,
+    icon_offset: Vec2,
+    text_offset: Vec2,
+    new_offset: Vec2,
+    ui_input_tracker: UiInputTracker,
+    scroll_tracker: ScrollTracker,
+    mail_frame: ScrollableFrame,
+    textbox: Textbox,
+    event_sender: flume::Sender<Event>,
+    event_receiver: flume::Receiver<Event>,
+    overworld_event_sender: flume::Sender<OverworldEvent>,
+    next_scene: NextScene,
+}
+
+impl MailScene {
+    pub fn new(
+        game_io: &GameIO,
+        server_assets: &impl AssetManager,
+        mail_list: &[Mail],
+        overworld_event_sender: flume::Sender<OverworldEvent>,
+    ) -> Self {
+        let globals = game_io.resource::<Globals>().unwrap();
+        let assets = &globals.assets;
+
+        // mail, newest first
+        // mugs are loaded now as the server's assets aren't available later
+        let entries: Vec<_> = mail_list
+            .iter()
+            .rev()
+            .map(|mail| {
+                let (mug_animator, mug_sprite) = match &mail.mug {
+                    Some(mug) => (
+                        Animator::load_new(server_assets, &mug.animation),
+                        server_assets.new_sprite(game_io, &mug.texture),
+                    ),
+                    None => (
+                        Animator::load_new(assets, ResourcePaths::BLANK),
+                        assets.new_sprite(game_io, ResourcePaths::MAIL_NO_MUG),
+                    ),
+                };
+
+                MailEntry {
+                    mail: mail.clone(),
+                    mug_animator,
+                    mug_sprite,
+                }
+            })
+            .collect();
+
+        // layout
+        let mut ui_animator = Animator::load_new(assets, ResourcePaths::MAIL_UI_ANIMATION);
+        ui_animator.set_state("DEFAULT");
+
+        let cursor_start = ui_animator.point_or_zero("CURSOR_START");
+        let icon_offset = ui_animator.point_or_zero("ICON_OFFSET");
+        let text_offset = ui_animator.point_or_zero("TEXT_OFFSET");
+        let new_offset = ui_animator.point_or_zero("NEW_OFFSET");
+
+        // icons
+        let icon_animator = Animator::load_new(assets, ResourcePaths::MAIL_ICONS_ANIMATION);
+        let icon_sprite = assets.new_sprite(game_io, ResourcePaths::MAIL_ICONS);
+
+        let new_animator = icon_animator
+            .clone()
+            .with_state("new")
+            .with_loop_mode(AnimatorLoopMode::Loop);
+        let new_sprite = icon_sprite.clone();
+
+        // scrollable frame
+        let frame_bounds = Rect::from_corners(
+            ui_animator.point_or_zero("FRAME_START"),
+            ui_animator.point_or_zero("FRAME_END"),
+        );
+
+        let mail_frame = ScrollableFrame::new(game_io, frame_bounds);
+
+        // scroll tracker
+        let mut scroll_tracker = ScrollTracker::new(game_io, 4);
+        scroll_tracker.set_total_items(entries.len());
+        scroll_tracker.define_cursor(cursor_start, LINE_HEIGHT);
+        scroll_tracker.define_scrollbar(mail_frame.scroll_start(), mail_frame.scroll_end());
+
+        // textbox
+        let textbox = Textbox::new_navigation(game_io)
+            .begin_open()
+            .with_text_animation_enabled(false);
+
+        // events
+        let (event_sender, event_receiver) = flume::unbounded();
+
+        let mut scene = Self {
+            camera: Camera::new_ui(game_io),
+            background: Background::new_sub_scene(game_io),
+            frame: SubSceneFrame::new(game_io).with_everything(true),
+            entries,
+            icon_animator,
+            icon_sprite,
+            new_animator,
+            new_sprite,
+            cursor_start,
+            icon_offset,
+            text_offset,
+            new_offset,
+            ui_input_tracker: UiInputTracker::new(),
+            scroll_tracker,
+            mail_frame,
+            textbox,
+            event_sender,
+            event_receiver,
+            overworld_event_sender,
+            next_scene: NextScene::None,
+        };
+
+        scene.push_preview(game_io);
+        scene
+    }
+
+    fn selected_entry(&self) -> Option<&MailEntry> {
+        self.entries.get(self.scroll_tracker.selected_index())
+    }
+
+    /// Replaces the current doorstop with a summary of the selected mail
+    fn push_preview(&mut self, game_io: &GameIO) {
+        let preview = self.selected_entry().map(|entry| {
+            let mail = &entry.mail;
+            let mut text = format!("From: {}", mail.sender);
+
+            if mail.has_unclaimed_attachments() {
+                text.push_str("\nAttached: ");
+                text.push_str(&describe_attachments(game_io, &mail.attachments));
+            }
+
+            text
+        });
+
+        let text = match preview {
+            Some(text) => {
+                self.use_selected_avatar();
+                text
+            }
+            None => {
+                self.textbox.use_navigation_avatar(game_io);
+                String::from("You have no mail.")
+            }
+        };
+
+        let (interface, _) = TextboxDoorstop::new();
+        self.textbox.push_interface(interface.with_string(text));
+        self.textbox.advance_interface(game_io);
+    }
+
+    fn use_selected_avatar(&mut self) {
+        let Some(entry) = self.entries.get(self.scroll_tracker.selected_index()) else {
+            return;
+        };
+
+        self.textbox
+            .set_next_avatar_sprite(entry.mug_animator.clone(), entry.mug_sprite.clone());
+    }
+
+    fn open_selected(&mut self, game_io: &GameIO) {
+        let index = self.scroll_tracker.selected_index();
+
+        let Some(entry) = self.entries.get_mut(index) else {
+            return;
+        };
+
+        let mail = &mut entry.mail;
+
+        if !mail.read {
+            mail.read = true;
+
+            let event = OverworldEvent::MailRead(mail.id.clone());
+            self.overworld_event_sender.send(event).unwrap();
+        }
+
+        let body = mail.body.clone();
+        let claimable = mail.has_unclaimed_attachments();
+
+        self.use_selected_avatar();
+
+        if !body.is_empty() {
+            self.textbox.push_interface(TextboxMessage::new(body));
+        }
+
+        if claimable {
+            let event_sender = self.event_sender.clone();
+
+            let question =
+                TextboxQuestion::new(String::from("Accept the attachments?"), move |yes| {
+                    if yes {
+                        event_sender.send(Event::Claim(index)).unwrap();
+                    }
+                });
+
+            self.textbox.push_interface(question);
+        }
+
+        self.push_preview(game_io);
+    }
+
+    fn handle_events(&mut self, game_io: &GameIO) {
+        while let Ok(event) = self.event_receiver.try_recv() {
+            match event {
+                Event::Claim(index) => {
+                    let Some(entry) = self.entries.get_mut(index) else {
+                        continue;
+                    };
+
+                    let mail = &mut entry.mail;
+                    mail.claimed = true;
+
+                    let event = OverworldEvent::MailClaim(mail.id.clone());
+                    self.overworld_event_sender.send(event).unwrap();
+
+                    let message = format!(
+                        "Received {}!",
+                        describe_attachments(game_io, &mail.attachments)
+                    );
+
+                    self.use_selected_avatar();
+                    self.textbox.push_interface(TextboxMessage::new(message));
+                    self.push_preview(game_io);
+                }
+            }
+        }
+    }
+}
+
+impl Scene for MailScene {
+    fn next_scene(&mut self) -> &mut NextScene {
+        &mut self.next_scene
+    }
+
+    fn update(&mut self, game_io: &mut GameIO) {
+        self.background.update();
+        self.textbox.update(game_io);
+        self.new_animator.update();
+
+        self.handle_events(game_io);
+
+        if game_io.is_in_transition() {
+            return;
+        }
+
+        if self.textbox.remaining_interfaces() > 1 {
+            // reading mail, the textbox is handling input
+            return;
+        }
+
+        self.ui_input_tracker.update(game_io);
+
+        if self.ui_input_tracker.pulsed(Input::Cancel) {
+            let globals = game_io.resource::<Globals>().unwrap();
+            globals.audio.play_sound(&globals.sfx.cursor_cancel);
+
+            let transition = crate::transitions::new_scene_pop(game_io);
+            self.next_scene = NextScene::new_pop().with_transition(transition);
+            return;
+        }
+
+        let prev_index = self.scroll_tracker.selected_index();
+
+        self.scroll_tracker
+            .handle_vertical_input(&self.ui_input_tracker);
+
+        if prev_index != self.scroll_tracker.selected_index() {
+            let globals = game_io.resource::<Globals>().unwrap();
+            globals.audio.play_sound(&globals.sfx.cursor_move);
+
+            self.push_preview(game_io);
+        }
+
+        if self.ui_input_tracker.pulsed(Input::Confirm) && !self.entries.is_empty() {
+            let globals = game_io.resource::<Globals>().unwrap();
+            globals.audio.play_sound(&globals.sfx.cursor_select);
+
+            self.open_selected(game_io);
+        }
+    }
+
+    fn draw(&mut self, game_io: &mut GameIO, render_pass: &mut RenderPass) {
+        self.background.draw(game_io, render_pass);
+
+        let mut sprite_queue =
+            SpriteColorQueue::new(game_io, &self.camera, SpriteColorMode::Multiply);
+
+        // draw mail frame
+        self.mail_frame.draw(game_io, &mut sprite_queue);
+
+        // draw mail
+        let mut text_style = TextStyle::new(game_io, FontName::Thin);
+        text_style.shadow_color = TEXT_DARK_SHADOW_COLOR;
+
+        if !self.entries.is_empty() {
+            let range = self.scroll_tracker.view_range();
+            let mut position = self.cursor_start;
+
+            for entry in &self.entries[range] {
+                let mail = &entry.mail;
+
+                // category icon
+                let icon_state = match mail.category {
+                    _ if mail.has_unclaimed_attachments() => "dm_w_attachment",
+                    MailCategory::Message => "dm",
+                    MailCategory::Announcement => "announcement",
+                    MailCategory::Important => "important",
+                    MailCategory::Mission => "mission",
+                };
+
+                self.icon_animator.set_state(icon_state);
+                self.icon_animator.apply(&mut self.icon_sprite);
+                self.icon_sprite.set_position(position + self.icon_offset);
+                sprite_queue.draw_sprite(&self.icon_sprite);
+
+                // subject
+                text_style.bounds.set_position(position + self.text_offset);
+                text_style.draw(game_io, &mut sprite_queue, &mail.subject);
+
+                // unread marker
+                if !mail.read {
+                    self.new_animator.apply(&mut self.new_sprite);
+                    self.new_sprite.set_position(position + self.new_offset);
+                    sprite_queue.draw_sprite(&self.new_sprite);
+                }
+
+                position.y += LINE_HEIGHT;
+            }
+        } else {
+            // default for no mail
+            let position = self.cursor_start + self.text_offset;
+
+            text_style.bounds.set_position(position);
+            text_style.draw(game_io, &mut sprite_queue, "None");
+        }
+
+        // draw scrollbar and cursor
+        self.scroll_tracker.draw_cursor(&mut sprite_queue);
+        self.scroll_tracker.draw_scrollbar(&mut sprite_queue);
+
+        // draw frame
+        self.frame.draw(&mut sprite_queue);
+        SceneTitle::new("MAIL").draw(game_io, &mut sprite_queue);
+
+        // draw textbox
+        self.textbox.draw(game_io, &mut sprite_queue);
+
+        render_pass.consume_queue(sprite_queue);
+    }
+}
+
+fn describe_attachments(game_io: &GameIO, attachments: &[MailAttachment]) -> String {
+    let globals = game_io.resource::<Globals>().unwrap();
+
+    let descriptions: Vec<_> = attachments
+        .iter()
+        .map(|attachment| match attachment {
+            MailAttachment::Item { name, count, .. } => format!("{name} x{count}"),
+            MailAttachment::Card {
+                package_id,
+                code,
+                count,
+            } => {
+                let name = globals
+                    .card_packages
+                    .package_or_fallback(PackageNamespace::Server, package_id)
+                    .map(|package| package.card_properties.short_name.to_string())
+                    .unwrap_or_else(|| package_id.to_string());
+
+                format!("{name} {code} x{count}")
+            }
+            MailAttachment::Money { amount } => format!("{amount}$"),
+        })
+        .collect();
+
+    descriptions.join(", ")
+}
@@ -81,9 +81,10 @@ impl CharacterData {
         shadow_sprite.set_color(Color::new(0.0, 0.0, 0.0, 0.3));
 
         // text
+        let localization = &globals.localization;
         let part_text = player_package
-            .map(|package| package.name.as_str())
-            .unwrap_or("Missing Navi")
+            .map(|package| package.localized_name(localization.language()))
+            .unwrap_or(localization.get("main_menu.missing_navi"))
             .to_uppercase()
             + "   ";
 
@@ -137,10 +138,11 @@ impl MainMenuScene {
         let mut textbox = Textbox::new_navigation(game_io);
 
         if !character_data.loaded {
-            const MESSAGE: &str = "Missing player mod.\n\n\nInstall from Manage Mods in Config.";
+            let globals = game_io.resource::<Globals>().unwrap();
+            let message = globals.localization.get("main_menu.missing_player_mod");
 
             textbox.use_navigation_avatar(game_io);
-            textbox.push_interface(TextboxMessage::new(MESSAGE.to_string()));
+            textbox.push_interface(TextboxMessage::new(message.to_string()));
             textbox.open();
         }
 
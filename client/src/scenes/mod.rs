@@ -1,4 +1,5 @@
 mod battle_init_scene;
+mod battle_results_scene;
 mod battle_scene;
 mod battle_select_scene;
 mod blocks_scene;
@@ -11,6 +12,7 @@ mod deck_list_scene;
 mod initial_connect_scene;
 mod key_item_scene;
 mod library_scene;
+mod mail_scene;
 mod main_menu_scene;
 mod manage_switch_drive_scene;
 mod netplay_init_scene;
@@ -23,6 +25,7 @@ mod server_edit_scene;
 mod server_list_scene;
 
 pub use battle_init_scene::*;
+pub use battle_results_scene::*;
 pub use battle_scene::*;
 pub use battle_select_scene::*;
 pub use blocks_scene::*;
@@ -35,6 +38,7 @@ pub use deck_list_scene::*;
 pub use initial_connect_scene::*;
 pub use key_item_scene::*;
 pub use library_scene::*;
+pub use mail_scene::*;
 pub use main_menu_scene::*;
 pub use manage_switch_drive_scene::*;
 pub use netplay_init_scene::*;
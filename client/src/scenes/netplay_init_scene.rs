@@ -9,15 +9,21 @@ use crate::transitions::HoldColorScene;
 use framework::prelude::*;
 use futures::Future;
 use packets::structures::{
-    Emotion, FileHash, InstalledBlock, InstalledSwitchDrive, PackageCategory, RemotePlayerInfo,
+    BattleReward, Emotion, FileHash, InstalledBlock, InstalledSwitchDrive, PackageCategory,
+    RemotePlayerInfo,
+};
+use packets::zip::ExtractionLimits;
+use packets::{
+    NetplayBufferItem, NetplayPacket, NetplaySeedSecret, NetplaySignal, SERVER_TICK_RATE,
 };
-use packets::{NetplayBufferItem, NetplayPacket, NetplaySignal, SERVER_TICK_RATE};
 use rand::rngs::OsRng;
 use rand::RngCore;
 use std::collections::{HashMap, HashSet};
 use std::pin::Pin;
 
 const MAX_FALLBACK_SILENCE: Duration = Duration::from_secs(3);
+const SPECTATOR_JOIN_INTERVAL: Duration = Duration::from_secs(1);
+const SEED_REVEAL_TIMEOUT: Duration = Duration::from_secs(10);
 
 pub struct NetplayProps {
     pub background: Option<Background>,
@@ -29,12 +35,16 @@ pub struct NetplayProps {
     pub remote_players: Vec<RemotePlayerInfo>,
     pub fallback_address: String,
     pub statistics_callback: Option<BattleStatisticsCallback>,
+    pub reward_receiver: Option<flume::Receiver<BattleReward>>,
+    /// Follow the battle through the server instead of participating
+    pub spectating: bool,
 }
 
 enum Event {
     AddressesFailed,
     ResolvedAddresses {
         players: Vec<(NetplayPacketSender, NetplayPacketReceiver)>,
+        spectator_relay: (NetplayPacketSender, NetplayPacketReceiver),
     },
     Fallback {
         fallback: (NetplayPacketSender, NetplayPacketReceiver),
@@ -57,6 +67,8 @@ struct RemotePlayerConnection {
     ready_for_packages: bool,
     received_package_list: bool,
     ready: bool,
+    seed_commitment: Option<FileHash>,
+    seed_secret: Option<NetplaySeedSecret>,
     send: Option<NetplayPacketSender>,
     receiver: Option<NetplayPacketReceiver>,
     buffer: PlayerInputBuffer,
@@ -71,13 +83,19 @@ pub struct NetplayInitScene {
     data: Option<String>,
     background: Option<Background>,
     statistics_callback: Option<BattleStatisticsCallback>,
+    reward_receiver: Option<flume::Receiver<BattleReward>>,
     last_heartbeat: Instant,
     failed: bool,
     seed: u64,
+    seed_secret: Option<NetplaySeedSecret>,
+    seed_reveal_instant: Option<Instant>,
     missing_packages: HashSet<FileHash>,
     player_connections: Vec<RemotePlayerConnection>,
     last_fallback_instant: Instant,
     fallback_sender_receiver: Option<(NetplayPacketSender, NetplayPacketReceiver)>,
+    spectator_relay: Option<(NetplayPacketSender, NetplayPacketReceiver)>,
+    spectating: bool,
+    last_spectator_join: Instant,
     event_receiver: flume::Receiver<Event>,
     ui_camera: Camera,
     sprite: Sprite,
@@ -98,6 +116,8 @@ impl NetplayInitScene {
             remote_players,
             fallback_address,
             statistics_callback,
+            reward_receiver,
+            spectating,
         } = props;
 
         let local_index = Self::resolve_local_index(&remote_players);
@@ -110,8 +130,10 @@ impl NetplayInitScene {
         let remote_index_map: Vec<_> = remote_players.iter().map(|info| info.index).collect();
         let total_remote = remote_players.len();
 
+        // spectators only communicate through the server
         let remote_futures: Vec<_> = remote_players
             .iter()
+            .filter(|_| !spectating)
             .map(|remote_player| network.subscribe_to_netplay(remote_player.address.to_string()))
             .chain(std::iter::once(
                 network.subscribe_to_netplay(fallback_address),
@@ -122,6 +144,17 @@ impl NetplayInitScene {
 
         let communication_future = async move {
             let results = futures::future::join_all(remote_futures).await;
+
+            if spectating {
+                let event = match results.into_iter().next().flatten() {
+                    Some(fallback) => Event::Fallback { fallback },
+                    None => Event::AddressesFailed,
+                };
+
+                let _ = event_sender.send(event);
+                return;
+            }
+
             let mut senders_and_receivers: Vec<_> = results.into_iter().flatten().collect();
             let fallback_sender_receiver = senders_and_receivers.pop().unwrap();
 
@@ -146,6 +179,7 @@ impl NetplayInitScene {
                 log::debug!("Hole punching successful");
                 Event::ResolvedAddresses {
                     players: senders_and_receivers,
+                    spectator_relay: fallback_sender_receiver,
                 }
             } else {
                 log::debug!("Hole punching failed");
@@ -175,9 +209,16 @@ impl NetplayInitScene {
                 ready_for_packages: false,
                 received_package_list: false,
                 ready: false,
+                seed_commitment: None,
+                seed_secret: None,
                 send: None,
                 receiver: None,
-                buffer: PlayerInputBuffer::new_with_delay(INPUT_DELAY),
+                buffer: if spectating {
+                    // received from the player, starting from the first frame
+                    PlayerInputBuffer::default()
+                } else {
+                    PlayerInputBuffer::new_with_delay(INPUT_DELAY)
+                },
             })
             .collect();
 
@@ -190,13 +231,19 @@ impl NetplayInitScene {
             data,
             background,
             statistics_callback,
+            reward_receiver,
             last_heartbeat: game_io.frame_start_instant(),
             failed: false,
             seed: 0,
+            seed_secret: None,
+            seed_reveal_instant: None,
             missing_packages: HashSet::new(),
             player_connections: remote_player_connections,
             last_fallback_instant: game_io.frame_start_instant(),
             fallback_sender_receiver: None,
+            spectator_relay: None,
+            spectating,
+            last_spectator_join: game_io.frame_start_instant(),
             event_receiver,
             ui_camera: Camera::new_ui(game_io),
             sprite: (globals.assets).new_sprite(game_io, ResourcePaths::WHITE_PIXEL),
@@ -231,6 +278,39 @@ impl NetplayInitScene {
                 index: self.local_index,
             });
         }
+
+        // players only respond once they've started the battle, keep asking until everyone has
+        let waiting_for_sync = self.spectating && !self.all_ready();
+
+        if waiting_for_sync && now - self.last_spectator_join >= SPECTATOR_JOIN_INTERVAL {
+            self.request_spectator_sync();
+        }
+
+        // a player could hold back their secret after seeing everyone else's
+        let revealing_too_long = self
+            .seed_reveal_instant
+            .is_some_and(|instant| now - instant >= SEED_REVEAL_TIMEOUT);
+
+        if revealing_too_long && !self.all_ready() {
+            for connection in &self.player_connections {
+                if connection.seed_secret.is_none() {
+                    log::error!(
+                        "Player {} never revealed their seed, disconnecting",
+                        connection.index
+                    );
+                }
+            }
+
+            self.failed = true;
+        }
+    }
+
+    fn request_spectator_sync(&mut self) {
+        self.last_spectator_join = Instant::now();
+
+        self.broadcast(NetplayPacket::SpectatorJoin {
+            index: self.local_index,
+        });
     }
 
     fn handle_packets(&mut self, game_io: &mut GameIO) {
@@ -250,7 +330,10 @@ impl NetplayInitScene {
                 packets.push(packet);
             }
 
-            if game_io.frame_start_instant() - self.last_fallback_instant > MAX_FALLBACK_SILENCE {
+            let silence = game_io.frame_start_instant() - self.last_fallback_instant;
+
+            // players don't communicate with spectators until the battle starts
+            if !self.spectating && silence > MAX_FALLBACK_SILENCE {
                 // remote must've disconnected in some edge case, such as leaving before connection even starts
                 self.failed = true;
             }
@@ -389,15 +472,27 @@ impl NetplayInitScene {
                     self.share_packages(game_io);
                 }
             }
-            NetplayPacket::PackageZip { data, .. } => {
+            NetplayPacket::PackageZip { data, .. }
+            | NetplayPacket::SpectatorPackageZip { data, .. } => {
                 let hash = FileHash::hash(&data);
 
                 log::debug!("Received zip for {hash}");
 
+                if let Err(err) = packets::zip::check_limits(&data, &ExtractionLimits::PACKAGE) {
+                    log::error!("Rejected zip for {hash}: {err}");
+                    self.failed = true;
+                    return;
+                }
+
                 if self.missing_packages.remove(&hash) {
                     let globals = game_io.resource::<Globals>().unwrap();
                     let assets = &globals.assets;
-                    assets.load_virtual_zip(game_io, hash, data);
+
+                    if let Err(err) = assets.load_virtual_zip(game_io, hash, data) {
+                        log::error!("Failed to extract zip for {hash}: {err}");
+                        self.failed = true;
+                        return;
+                    }
 
                     let globals = game_io.resource_mut::<Globals>().unwrap();
 
@@ -421,17 +516,134 @@ impl NetplayInitScene {
                     self.failed = true;
                 }
             }
-            NetplayPacket::Ready { seed, .. } => {
-                // todo: prevent seed manipulation
-                self.seed = self.seed.max(seed);
+            NetplayPacket::Ready {
+                seed_commitment, ..
+            } => {
+                if connection
+                    .seed_commitment
+                    .is_some_and(|commitment| commitment != seed_commitment)
+                {
+                    log::error!("Player {index} changed their seed commitment, disconnecting");
+                    self.failed = true;
+                    return;
+                }
+
+                connection.seed_commitment = Some(seed_commitment);
                 connection.ready = true;
+
+                self.reveal_seed();
+            }
+            NetplayPacket::SeedReveal { seed_secret, .. } => {
+                let Some(commitment) = connection.seed_commitment else {
+                    log::error!(
+                        "Player {index} revealed their seed before committing to it, disconnecting"
+                    );
+                    self.failed = true;
+                    return;
+                };
+
+                if packets::netplay_seed_commitment(index, &seed_secret) != commitment {
+                    log::error!(
+                        "Player {index} revealed a seed that doesn't match their commitment, disconnecting"
+                    );
+                    self.failed = true;
+                    return;
+                }
+
+                connection.seed_secret = Some(seed_secret);
             }
             NetplayPacket::Buffer { data, .. } => {
                 if data.signals.contains(&NetplaySignal::Disconnect) {
                     self.failed = true;
                 }
 
-                connection.buffer.push_last(data);
+                if !self.spectating {
+                    // spectators receive input through SpectatorSync and SpectatorBuffer
+                    connection.buffer.push_last(data);
+                }
+            }
+            NetplayPacket::Checksum { .. } | NetplayPacket::SpectatorJoin { .. } => {
+                // only shared during battle
+            }
+            NetplayPacket::SpectatorSync {
+                index,
+                seed,
+                player_package,
+                script_enabled,
+                cards,
+                regular_card,
+                recipes,
+                blocks,
+                drives,
+                packages,
+                buffer,
+            } => {
+                if !self.spectating || connection.ready {
+                    // already synced, likely a response to a repeated join request
+                    return;
+                }
+
+                connection.player_package = player_package;
+                connection.script_enabled = script_enabled;
+                connection.deck.cards = cards
+                    .into_iter()
+                    .map(|(package_id, code)| Card { package_id, code })
+                    .collect();
+                connection.deck.regular_index = regular_card;
+                connection.recipes = recipes;
+                connection.blocks = blocks;
+                connection.drives = drives;
+
+                for (item, repeat) in buffer {
+                    connection.buffer.push_repeated(item, repeat);
+                }
+
+                // every player shares the same final seed
+                self.seed = seed;
+                connection.ready = true;
+                connection.received_package_list = true;
+
+                let globals = game_io.resource::<Globals>().unwrap();
+
+                let load_list: Vec<_> = packages
+                    .into_iter()
+                    .filter(|(category, id, hash)| {
+                        globals
+                            .package_or_fallback_info(*category, PackageNamespace::Local, id)
+                            .map(|package_info| package_info.hash != *hash)
+                            .unwrap_or(true)
+                    })
+                    .collect();
+
+                for (category, _, hash) in &load_list {
+                    connection.load_map.insert(*hash, *category);
+                }
+
+                let missing_packages: Vec<_> = load_list
+                    .into_iter()
+                    .map(|(_, _, hash)| hash)
+                    .filter(|hash| self.missing_packages.insert(*hash))
+                    .collect();
+
+                if !missing_packages.is_empty() {
+                    self.send(
+                        index,
+                        NetplayPacket::MissingPackages {
+                            index: self.local_index,
+                            recipient_index: index,
+                            list: missing_packages,
+                        },
+                    );
+                }
+            }
+            NetplayPacket::SpectatorBuffer { time, data, .. } => {
+                // ignore input from before we synced, it's included in the sync
+                if self.spectating
+                    && connection.ready
+                    && time == connection.buffer.len() as FrameTime
+                {
+                    connection.buffer.push_last(data);
+                }
             }
         }
     }
@@ -442,6 +654,21 @@ impl NetplayInitScene {
                 .player_connections
                 .iter()
                 .all(|connection| connection.ready)
+            && (self.spectating || self.received_every_seed_secret())
+    }
+
+    fn received_every_seed_commitment(&self) -> bool {
+        self.player_connections
+            .iter()
+            .all(|connection| connection.seed_commitment.is_some())
+    }
+
+    fn received_every_seed_secret(&self) -> bool {
+        self.seed_secret.is_some()
+            && self
+                .player_connections
+                .iter()
+                .all(|connection| connection.seed_secret.is_some())
     }
 
     fn received_every_missing_list(&self) -> bool {
@@ -591,29 +818,69 @@ impl NetplayInitScene {
     }
 
     fn broadcast_ready(&mut self) {
-        let seed = OsRng.next_u64();
+        if self.spectating {
+            // spectators use the seed shared in SpectatorSync
+            return;
+        }
 
+        if self.seed_secret.is_some() {
+            // already committed
+            return;
+        }
+
+        let mut seed_secret = NetplaySeedSecret::default();
+        OsRng.fill_bytes(&mut seed_secret);
+        self.seed_secret = Some(seed_secret);
+
+        // commit first, so no one can pick their secret after seeing ours
         self.broadcast(NetplayPacket::Ready {
             index: self.local_index,
-            seed,
+            seed_commitment: packets::netplay_seed_commitment(self.local_index, &seed_secret),
+        });
+
+        self.reveal_seed();
+    }
+
+    fn reveal_seed(&mut self) {
+        let Some(seed_secret) = self.seed_secret else {
+            return;
+        };
+
+        if self.seed_reveal_instant.is_some() || !self.received_every_seed_commitment() {
+            return;
+        }
+
+        self.seed_reveal_instant = Some(Instant::now());
+
+        self.broadcast(NetplayPacket::SeedReveal {
+            index: self.local_index,
+            seed_secret,
         });
+    }
+
+    fn resolve_seed(&self) -> Option<u64> {
+        let mut secrets = vec![(self.local_index, self.seed_secret?)];
 
-        if self.seed < seed {
-            self.seed = seed;
+        for connection in &self.player_connections {
+            secrets.push((connection.index, connection.seed_secret?));
         }
+
+        Some(packets::combine_netplay_seed_secrets(&secrets))
     }
 
     fn handle_transition(&mut self, game_io: &mut GameIO) {
         if self.failed {
             // let other player's know we're giving up on them
-            self.broadcast(NetplayPacket::Buffer {
-                index: self.local_index,
-                data: NetplayBufferItem {
-                    pressed: Vec::new(),
-                    signals: vec![NetplaySignal::Disconnect],
-                },
-                lead: Vec::new(),
-            });
+            if !self.spectating {
+                self.broadcast(NetplayPacket::Buffer {
+                    index: self.local_index,
+                    data: NetplayBufferItem {
+                        pressed: Vec::new(),
+                        signals: vec![NetplaySignal::Disconnect],
+                    },
+                    lead: Vec::new(),
+                });
+            }
 
             // make sure the statistics callback gets called
             if let Some(callback) = self.statistics_callback.take() {
@@ -636,6 +903,11 @@ impl NetplayInitScene {
             let encounter_package = self.encounter_package.take();
             let mut props = BattleProps::new_with_defaults(game_io, encounter_package);
 
+            if !self.spectating {
+                // every player's secret is revealed at this point
+                self.seed = self.resolve_seed().unwrap_or_default();
+            }
+
             props.data = self.data.take();
             props.seed = self.seed;
 
@@ -644,12 +916,17 @@ impl NetplayInitScene {
                 props.background = background;
             }
 
-            // correct index and health
-            let local_setup = &mut props.player_setups[0];
-            local_setup.index = self.local_index;
-            local_setup.health = self.local_health;
-            local_setup.base_health = self.local_base_health;
-            local_setup.emotion = self.local_emotion.clone();
+            if self.spectating {
+                // spectators aren't in the battle
+                props.player_setups.clear();
+            } else {
+                // correct index and health
+                let local_setup = &mut props.player_setups[0];
+                local_setup.index = self.local_index;
+                local_setup.health = self.local_health;
+                local_setup.base_health = self.local_base_health;
+                local_setup.emotion = self.local_emotion.clone();
+            }
 
             // setup other players
             for mut connection in std::mem::take(&mut self.player_connections) {
@@ -693,14 +970,25 @@ impl NetplayInitScene {
             }
 
             if let Some((send, receiver)) = self.fallback_sender_receiver.take() {
+                props.spectator_sender = Some(send.clone());
                 props.senders.push(send);
                 props.receivers.push((None, receiver));
             }
 
+            if let Some((send, receiver)) = self.spectator_relay.take() {
+                props.spectator_sender = Some(send);
+                props.receivers.push((None, receiver));
+            }
+
             props.statistics_callback = self.statistics_callback.take();
+            props.reward_receiver = self.reward_receiver.take();
 
             // create scene
-            let battle_scene = BattleScene::new(game_io, props);
+            let battle_scene = if self.spectating {
+                BattleScene::new_spectating(game_io, props)
+            } else {
+                BattleScene::new(game_io, props)
+            };
             let hold_duration = crate::transitions::BATTLE_HOLD_DURATION
                 .checked_sub(self.start_instant.elapsed())
                 .unwrap_or_default();
@@ -747,19 +1035,28 @@ impl Scene for NetplayInitScene {
                 Event::AddressesFailed => {
                     self.failed = true;
                 }
-                Event::ResolvedAddresses { players } => {
+                Event::ResolvedAddresses {
+                    players,
+                    spectator_relay,
+                } => {
                     for (i, (send, receiver)) in players.into_iter().enumerate() {
                         let connection = &mut self.player_connections[i];
                         connection.send = Some(send);
                         connection.receiver = Some(receiver);
                     }
 
+                    self.spectator_relay = Some(spectator_relay);
                     self.broadcast_package_list(game_io);
                 }
                 Event::Fallback { fallback } => {
                     self.last_fallback_instant = game_io.frame_start_instant();
                     self.fallback_sender_receiver = Some(fallback);
-                    self.broadcast_package_list(game_io);
+
+                    if self.spectating {
+                        self.request_spectator_sync();
+                    } else {
+                        self.broadcast_package_list(game_io);
+                    }
                 }
             }
         }
@@ -1,8 +1,8 @@
 use super::{
-    InitialConnectScene, NetplayInitScene, NetplayProps, PackageScene, ServerEditProp,
+    InitialConnectScene, MailScene, NetplayInitScene, NetplayProps, PackageScene, ServerEditProp,
     ServerEditScene,
 };
-use crate::battle::BattleProps;
+use crate::battle::{BattleProps, BattleStatisticsCallback};
 use crate::bindable::SpriteColorMode;
 use crate::overworld::components::*;
 use crate::overworld::*;
@@ -19,7 +19,8 @@ use bimap::BiMap;
 use framework::prelude::*;
 use packets::address_parsing::uri_encode;
 use packets::structures::{
-    ActorId, ActorProperty, BattleStatistics, FileHash, SpriteId, SpriteParent, TextboxOptions,
+    ActorId, ActorProperty, BattleReward, BattleStatistics, ChatChannel, FileHash,
+    RemotePlayerInfo, SpriteId, SpriteParent, TextboxOptions,
 };
 use packets::{
     address_parsing, ClientAssetType, ClientPacket, Reliability, ServerPacket, SERVER_TICK_RATE,
@@ -50,6 +51,8 @@ pub struct OverworldOnlineScene {
     doorstop_remover: Option<TextboxDoorstopRemover>,
     encounter_packages: HashMap<String, PackageId>, // server_path -> package_id
     loaded_zips: HashMap<String, FileHash>,         // server_path -> hash
+    battle_reward_sender: Option<flume::Sender<BattleReward>>,
+    last_whisperer: Option<ActorId>,
 }
 
 impl OverworldOnlineScene {
@@ -83,6 +86,11 @@ impl OverworldOnlineScene {
         let emote_menu_index = menu_manager.register_menu(Box::new(emote_menu));
         menu_manager.bind_menu(Input::Option2, emote_menu_index);
 
+        // chat menu
+        let chat_menu = ChatMenu::new(game_io);
+        let chat_menu_index = menu_manager.register_menu(Box::new(chat_menu));
+        menu_manager.bind_menu(Input::Chat, chat_menu_index);
+
         // hud
         let hud = OverworldHud::new(game_io, area.player_data.health);
 
@@ -110,6 +118,8 @@ impl OverworldOnlineScene {
             doorstop_remover: None,
             encounter_packages: HashMap::new(),
             loaded_zips: HashMap::new(),
+            battle_reward_sender: None,
+            last_whisperer: None,
         }
     }
 
@@ -968,7 +978,11 @@ impl OverworldOnlineScene {
                     let bytes = self.assets.binary(&package_path);
                     let hash = FileHash::hash(&bytes);
 
-                    globals.assets.load_virtual_zip(game_io, hash, bytes);
+                    if let Err(err) = globals.assets.load_virtual_zip(game_io, hash, bytes) {
+                        log::error!("Failed to extract {package_path:?}: {err}");
+                        return;
+                    }
+
                     self.loaded_zips.insert(package_path.clone(), hash);
 
                     let globals = game_io.resource_mut::<Globals>().unwrap();
@@ -1012,6 +1026,11 @@ impl OverworldOnlineScene {
                         let _ = event_sender.send(OverworldEvent::BattleStatistics(statistics));
                     }));
 
+                    // rewards
+                    let (reward_sender, reward_receiver) = flume::unbounded();
+                    self.battle_reward_sender = Some(reward_sender);
+                    props.reward_receiver = Some(reward_receiver);
+
                     // copy background
                     props.background = self
                         .area
@@ -1036,43 +1055,32 @@ impl OverworldOnlineScene {
             } => {
                 (self.send_packet)(Reliability::ReliableOrdered, ClientPacket::EncounterStart);
 
-                // copy background
-                let background = self
-                    .area
-                    .map
-                    .background_properties()
-                    .generate_background(game_io, &self.assets);
-
-                // callback
-                let event_sender = self.area.event_sender.clone();
-                let statistics_callback = Box::new(move |statistics| {
-                    let _ = event_sender.send(OverworldEvent::BattleStatistics(statistics));
-                });
-
-                // get package
-                let encounter_package = package_path
-                    .and_then(|path| self.encounter_packages.get(&path))
-                    .map(|id| (PackageNamespace::Server, id.clone()));
-
-                // create scene
-                let player_data = &self.area.player_data;
-                let props = NetplayProps {
-                    background: Some(background),
-                    encounter_package,
-                    data,
-                    health: player_data.health,
-                    base_health: player_data.base_health,
-                    emotion: player_data.emotion.clone(),
-                    remote_players,
-                    fallback_address: self.server_address.clone(),
-                    statistics_callback: Some(statistics_callback),
-                };
-
-                let scene = NetplayInitScene::new(game_io, props);
+                self.initiate_netplay(game_io, package_path, data, remote_players, false);
+            }
+            ServerPacket::InitiateSpectating {
+                package_path,
+                data,
+                players,
+            } => {
+                self.initiate_netplay(game_io, package_path, data, players, true);
+            }
+            ServerPacket::BattleReward { reward } => {
+                // forwarded to the results screen of the latest battle
+                if let Some(sender) = &self.battle_reward_sender {
+                    let _ = sender.send(reward);
+                }
+            }
+            ServerPacket::Mail { mail } => {
+                let inbox = &mut self.area.player_data.mail;
 
-                let transition = crate::transitions::new_battle(game_io);
-                let next_scene = NextScene::new_push(scene).with_transition(transition);
-                self.next_scene_queue.push_back(next_scene);
+                if let Some(existing_mail) = inbox.iter_mut().find(|m| m.id == mail.id) {
+                    *existing_mail = mail;
+                } else {
+                    inbox.push(mail);
+                }
+            }
+            ServerPacket::RemoveMail { id } => {
+                self.area.player_data.mail.retain(|mail| mail.id != id);
             }
             ServerPacket::ActorConnected {
                 actor_id,
@@ -1260,6 +1268,47 @@ impl OverworldOnlineScene {
                     Emote::animate_actor(&mut self.area.entities, entity, &emote_id, false);
                 }
             }
+            ServerPacket::ChatMessage {
+                sender_id,
+                sender_name,
+                channel,
+                message,
+            } => {
+                let local_id = self
+                    .actor_id_map
+                    .get_by_right(&self.area.player_data.entity)
+                    .cloned();
+
+                let sent_by_us = sender_id.is_some() && sender_id == local_id;
+
+                let (kind, text) = match channel {
+                    ChatChannel::Area if sender_name.is_empty() => (ChatLineKind::Area, message),
+                    ChatChannel::Area => (ChatLineKind::Area, format!("{sender_name}: {message}")),
+                    ChatChannel::Whisper(target_id)
+                        if sent_by_us && Some(target_id) != local_id =>
+                    {
+                        // our own whisper echoed back
+                        let target_name = self.actor_name(target_id).unwrap_or_default();
+                        let text = format!("To {target_name}: {message}");
+                        (ChatLineKind::Whisper, text)
+                    }
+                    ChatChannel::Whisper(_) if sender_name.is_empty() => {
+                        (ChatLineKind::Whisper, message)
+                    }
+                    ChatChannel::Whisper(_) => {
+                        if sender_id.is_some() {
+                            self.last_whisperer = sender_id;
+                        }
+
+                        let text = format!("From {sender_name}: {message}");
+                        (ChatLineKind::Whisper, text)
+                    }
+                };
+
+                self.area
+                    .chat_log
+                    .push_line(self.area.world_time, kind, text);
+            }
             ServerPacket::ActorAnimate {
                 actor_id,
                 state,
@@ -1417,6 +1466,69 @@ impl OverworldOnlineScene {
         }
     }
 
+    fn initiate_netplay(
+        &mut self,
+        game_io: &GameIO,
+        package_path: Option<String>,
+        data: Option<String>,
+        remote_players: Vec<RemotePlayerInfo>,
+        spectating: bool,
+    ) {
+        // copy background
+        let background = self
+            .area
+            .map
+            .background_properties()
+            .generate_background(game_io, &self.assets);
+
+        // callback, spectators have no results to share
+        let statistics_callback: Option<BattleStatisticsCallback> = if spectating {
+            None
+        } else {
+            let event_sender = self.area.event_sender.clone();
+
+            Some(Box::new(move |statistics| {
+                let _ = event_sender.send(OverworldEvent::BattleStatistics(statistics));
+            }))
+        };
+
+        // rewards, spectators have no results screen
+        let reward_receiver = if spectating {
+            None
+        } else {
+            let (reward_sender, reward_receiver) = flume::unbounded();
+            self.battle_reward_sender = Some(reward_sender);
+            Some(reward_receiver)
+        };
+
+        // get package
+        let encounter_package = package_path
+            .and_then(|path| self.encounter_packages.get(&path))
+            .map(|id| (PackageNamespace::Server, id.clone()));
+
+        // create scene
+        let player_data = &self.area.player_data;
+        let props = NetplayProps {
+            background: Some(background),
+            encounter_package,
+            data,
+            health: player_data.health,
+            base_health: player_data.base_health,
+            emotion: player_data.emotion.clone(),
+            remote_players,
+            fallback_address: self.server_address.clone(),
+            statistics_callback,
+            reward_receiver,
+            spectating,
+        };
+
+        let scene = NetplayInitScene::new(game_io, props);
+
+        let transition = crate::transitions::new_battle(game_io);
+        let next_scene = NextScene::new_push(scene).with_transition(transition);
+        self.next_scene_queue.push_back(next_scene);
+    }
+
     fn push_textbox_interface_with_options(
         &mut self,
         game_io: &GameIO,
@@ -1467,12 +1579,52 @@ impl OverworldOnlineScene {
                         ClientPacket::Emote { emote_id },
                     );
                 }
+                OverworldEvent::ChatSubmitted(message) => {
+                    self.submit_chat(message);
+                }
                 OverworldEvent::ItemUse(item_id) => {
                     (self.send_packet)(
                         Reliability::ReliableOrdered,
                         ClientPacket::ItemUse { item_id },
                     );
                 }
+                OverworldEvent::OpenMail => {
+                    let scene = MailScene::new(
+                        game_io,
+                        &self.assets,
+                        &self.area.player_data.mail,
+                        self.area.event_sender.clone(),
+                    );
+
+                    let transition = crate::transitions::new_navigation(game_io);
+                    let next_scene = NextScene::new_push(scene).with_transition(transition);
+
+                    self.next_scene_queue.push_back(next_scene);
+                }
+                OverworldEvent::MailRead(mail_id) => {
+                    let inbox = &mut self.area.player_data.mail;
+
+                    if let Some(mail) = inbox.iter_mut().find(|m| m.id == mail_id) {
+                        mail.read = true;
+                    }
+
+                    (self.send_packet)(
+                        Reliability::ReliableOrdered,
+                        ClientPacket::MailRead { mail_id },
+                    );
+                }
+                OverworldEvent::MailClaim(mail_id) => {
+                    let inbox = &mut self.area.player_data.mail;
+
+                    if let Some(mail) = inbox.iter_mut().find(|m| m.id == mail_id) {
+                        mail.claimed = true;
+                    }
+
+                    (self.send_packet)(
+                        Reliability::ReliableOrdered,
+                        ClientPacket::MailClaim { mail_id },
+                    );
+                }
                 OverworldEvent::TextboxResponse(response) => {
                     (self.send_packet)(
                         Reliability::ReliableOrdered,
@@ -1608,6 +1760,65 @@ impl OverworldOnlineScene {
         }
     }
 
+    fn actor_name(&self, actor_id: ActorId) -> Option<String> {
+        let entity = *self.actor_id_map.get_by_left(&actor_id)?;
+        let mut query = self.area.entities.query_one::<&NameLabel>(entity).ok()?;
+        let label = query.get()?;
+
+        Some(label.0.clone())
+    }
+
+    fn find_actor_by_name(&self, name: &str) -> Option<ActorId> {
+        let mut query = self.area.entities.query::<&NameLabel>();
+
+        let (entity, _) = query
+            .iter()
+            .find(|(_, label)| label.0.eq_ignore_ascii_case(name))?;
+
+        self.actor_id_map.get_by_right(&entity).cloned()
+    }
+
+    /// Supports `/w name message` for whispers and `/r message` to reply to the last whisper
+    fn submit_chat(&mut self, message: String) {
+        let time = self.area.world_time;
+
+        let (channel, message) = if let Some(whisper) = message.strip_prefix("/w ") {
+            let whisper = whisper.trim_start();
+            let (name, message) = whisper.split_once(' ').unwrap_or((whisper, ""));
+
+            let Some(target_id) = self.find_actor_by_name(name) else {
+                let text = format!("No one named {name} is here");
+                self.area
+                    .chat_log
+                    .push_line(time, ChatLineKind::Notice, text);
+                return;
+            };
+
+            (ChatChannel::Whisper(target_id), message.to_string())
+        } else if let Some(message) = message.strip_prefix("/r ") {
+            let Some(target_id) = self.last_whisperer else {
+                let text = String::from("No whisper to reply to");
+                self.area
+                    .chat_log
+                    .push_line(time, ChatLineKind::Notice, text);
+                return;
+            };
+
+            (ChatChannel::Whisper(target_id), message.to_string())
+        } else {
+            (ChatChannel::Area, message)
+        };
+
+        if message.trim().is_empty() {
+            return;
+        }
+
+        (self.send_packet)(
+            Reliability::ReliableOrdered,
+            ClientPacket::Chat { channel, message },
+        );
+    }
+
     fn handle_interaction(&mut self, button: u8) {
         let player_data = &self.area.player_data;
         let send_packet = &self.send_packet;
@@ -1844,8 +2055,12 @@ impl Scene for OverworldOnlineScene {
             let texbox_is_open = self.menu_manager.is_textbox_open();
             self.hud.set_map_name_visible(!texbox_is_open);
 
+            // the chat menu displays the log while open
+            let menu_is_open = self.menu_manager.is_open();
+            self.hud.set_chat_visible(!menu_is_open);
+
             // draw the hud
-            self.hud.draw(game_io, &mut sprite_queue, &self.area.map);
+            self.hud.draw(game_io, &mut sprite_queue, &self.area);
 
             // draw hud attachments
             self.area
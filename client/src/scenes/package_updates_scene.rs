@@ -201,7 +201,7 @@ impl PackageUpdatesScene {
         }
     }
 
-    fn handle_events(&mut self, game_io: &GameIO) {
+    fn handle_events(&mut self, game_io: &mut GameIO) {
         if self.next_scene.is_some() || game_io.is_in_transition() {
             return;
         }
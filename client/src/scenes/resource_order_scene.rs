@@ -88,10 +88,16 @@ impl ResourceOrderScene {
             .filter(|id| !saved_order.iter().any(|(saved_id, _)| *saved_id == **id))
             .map(|id| (id.clone(), true));
 
+        let language = globals.localization.language();
+
         let package_order_iter = tracked_iter.chain(missing_iter).flat_map(|(id, enabled)| {
             let package = packages.package(PackageNamespace::Local, &id)?;
+            let translation = package.package_info.translation(language);
+            let listing = package
+                .create_package_listing()
+                .with_translation(translation);
 
-            Some((package.create_package_listing(), enabled))
+            Some((listing, enabled))
         });
 
         let mut package_order = vec![(ResourcePackage::default_package_listing(), true)];
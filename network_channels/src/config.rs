@@ -3,18 +3,23 @@ use instant::Duration;
 #[derive(Clone)]
 pub struct Config {
     pub mtu: u16,
+    /// Hard cap on bytes sent per tick, reliable packets are further limited by the congestion window
     pub bytes_per_tick: usize,
+    /// Multiplier for the retransmission timeout, which is based on the smoothed RTT and its variance
     pub rtt_resend_factor: f32,
     pub initial_rtt: Duration,
+    /// Bytes of reliable packets allowed in transit before the first acks arrive
+    pub initial_window: usize,
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             mtu: 1400,
-            rtt_resend_factor: 0.5,
+            rtt_resend_factor: 1.0,
             bytes_per_tick: 65536,
             initial_rtt: Duration::from_millis(500),
+            initial_window: 16384,
         }
     }
 }
@@ -0,0 +1,264 @@
+use crate::config::Config;
+use instant::{Duration, Instant};
+
+const MIN_RETRY_DELAY: Duration = Duration::from_millis(20);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(2);
+const MAX_BACKOFF: u32 = 4;
+const RATE_SAMPLE_PERIOD: Duration = Duration::from_secs(1);
+/// How much each reliable packet affects the loss estimate
+const LOSS_SMOOTHING: f32 = 1.0 / 32.0;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct ConnectionStats {
+    /// Smoothed round trip time, based on acknowledged reliable packets
+    pub rtt: Duration,
+    pub rtt_variance: Duration,
+    /// Estimated fraction of reliable packets lost in transit, from 0.0 to 1.0
+    pub packet_loss: f32,
+    /// Bytes sent per second, including unreliable packets and acks
+    pub send_rate: f32,
+    /// Bytes per second of reliable packets acknowledged by the peer
+    pub delivery_rate: f32,
+    /// Max bytes of unacknowledged reliable packets allowed in transit
+    pub congestion_window: usize,
+    pub bytes_in_flight: usize,
+}
+
+/// Loss and RTT aware send window, based on TCP's slow start and congestion avoidance
+pub(crate) struct CongestionControl {
+    max_packet_size: usize,
+    rtt_resend_factor: f32,
+    initial_rtt: Duration,
+    smoothed_rtt: Option<Duration>,
+    rtt_variance: Duration,
+    backoff: u32,
+    window: usize,
+    min_window: usize,
+    slow_start_threshold: usize,
+    /// Losses of packets sent before this point belong to the last reduction
+    recovery_start: Instant,
+    bytes_in_flight: usize,
+    packet_loss: f32,
+    sample_start: Instant,
+    sampled_sent: usize,
+    sampled_delivered: usize,
+    send_rate: f32,
+    delivery_rate: f32,
+}
+
+impl CongestionControl {
+    pub(crate) fn new(config: &Config, now: Instant) -> Self {
+        let max_packet_size = config.mtu as usize;
+        let min_window = max_packet_size * 2;
+
+        Self {
+            max_packet_size,
+            rtt_resend_factor: config.rtt_resend_factor,
+            initial_rtt: config.initial_rtt,
+            smoothed_rtt: None,
+            rtt_variance: Duration::ZERO,
+            backoff: 0,
+            window: config.initial_window.max(min_window),
+            min_window,
+            slow_start_threshold: usize::MAX,
+            recovery_start: now,
+            bytes_in_flight: 0,
+            packet_loss: 0.0,
+            sample_start: now,
+            sampled_sent: 0,
+            sampled_delivered: 0,
+            send_rate: 0.0,
+            delivery_rate: 0.0,
+        }
+    }
+
+    /// How long to wait for an ack before a reliable packet is considered lost
+    pub(crate) fn retry_delay(&self) -> Duration {
+        let timeout = match self.smoothed_rtt {
+            Some(rtt) => rtt + self.rtt_variance * 4,
+            None => self.initial_rtt,
+        };
+
+        let delay = timeout.mul_f32(self.rtt_resend_factor) * (1 << self.backoff);
+
+        delay.clamp(MIN_RETRY_DELAY, MAX_RETRY_DELAY)
+    }
+
+    /// At least one packet is always allowed through to keep probing the connection
+    pub(crate) fn can_send(&self, bytes: usize) -> bool {
+        self.bytes_in_flight == 0 || self.bytes_in_flight + bytes <= self.window
+    }
+
+    pub(crate) fn on_packet_sent(&mut self, bytes: usize) {
+        self.bytes_in_flight += bytes;
+    }
+
+    /// `rtt_sample` should only be provided for packets that were sent once
+    pub(crate) fn on_packet_acked(
+        &mut self,
+        bytes: usize,
+        in_flight: bool,
+        rtt_sample: Option<Duration>,
+    ) {
+        if let Some(rtt) = rtt_sample {
+            self.update_rtt(rtt);
+        }
+
+        self.sampled_delivered += bytes;
+        self.packet_loss -= self.packet_loss * LOSS_SMOOTHING;
+
+        if !in_flight {
+            // already counted as lost, the ack was late
+            return;
+        }
+
+        // avoid growing the window while the application isn't filling it
+        let window_utilized = self.bytes_in_flight * 2 >= self.window;
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(bytes);
+
+        if !window_utilized {
+            return;
+        }
+
+        if self.window < self.slow_start_threshold {
+            self.window += bytes;
+        } else {
+            self.window += (self.max_packet_size * bytes / self.window).max(1);
+        }
+    }
+
+    pub(crate) fn on_packet_lost(&mut self, now: Instant, bytes: usize, last_send: Instant) {
+        self.bytes_in_flight = self.bytes_in_flight.saturating_sub(bytes);
+        self.packet_loss += (1.0 - self.packet_loss) * LOSS_SMOOTHING;
+
+        if last_send < self.recovery_start {
+            // the window was already reduced for this round of losses
+            return;
+        }
+
+        self.slow_start_threshold = (self.window / 2).max(self.min_window);
+        self.window = self.slow_start_threshold;
+        self.recovery_start = now;
+        self.backoff = (self.backoff + 1).min(MAX_BACKOFF);
+    }
+
+    pub(crate) fn update_rates(&mut self, now: Instant, sent_bytes: usize) {
+        self.sampled_sent += sent_bytes;
+
+        let elapsed = now - self.sample_start;
+
+        if elapsed < RATE_SAMPLE_PERIOD {
+            return;
+        }
+
+        let elapsed_secs = elapsed.as_secs_f32();
+        self.send_rate = self.sampled_sent as f32 / elapsed_secs;
+        self.delivery_rate = self.sampled_delivered as f32 / elapsed_secs;
+
+        self.sample_start = now;
+        self.sampled_sent = 0;
+        self.sampled_delivered = 0;
+    }
+
+    pub(crate) fn stats(&self) -> ConnectionStats {
+        ConnectionStats {
+            rtt: self.smoothed_rtt.unwrap_or(self.initial_rtt),
+            rtt_variance: self.rtt_variance,
+            packet_loss: self.packet_loss,
+            send_rate: self.send_rate,
+            delivery_rate: self.delivery_rate,
+            congestion_window: self.window,
+            bytes_in_flight: self.bytes_in_flight,
+        }
+    }
+
+    // RFC 6298
+    fn update_rtt(&mut self, rtt: Duration) {
+        self.backoff = 0;
+
+        let Some(smoothed_rtt) = self.smoothed_rtt else {
+            self.smoothed_rtt = Some(rtt);
+            self.rtt_variance = rtt / 2;
+            return;
+        };
+
+        let deviation = smoothed_rtt.abs_diff(rtt);
+
+        self.rtt_variance = self.rtt_variance.mul_f32(0.75) + deviation.mul_f32(0.25);
+        self.smoothed_rtt = Some(smoothed_rtt.mul_f32(0.875) + rtt.mul_f32(0.125));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_grows_and_shrinks() {
+        let config = Config::default();
+        let start = Instant::now();
+        let mut congestion = CongestionControl::new(&config, start);
+        let initial_window = congestion.stats().congestion_window;
+
+        // fill the window and ack everything
+        let packet_size = config.mtu as usize;
+        let packet_count = initial_window / packet_size;
+
+        for _ in 0..packet_count {
+            assert!(congestion.can_send(packet_size));
+            congestion.on_packet_sent(packet_size);
+        }
+
+        assert!(!congestion.can_send(packet_size));
+
+        for _ in 0..packet_count {
+            let rtt = Some(Duration::from_millis(50));
+            congestion.on_packet_acked(packet_size, true, rtt);
+        }
+
+        let grown_window = congestion.stats().congestion_window;
+        assert!(grown_window > initial_window);
+        assert_eq!(congestion.stats().bytes_in_flight, 0);
+
+        // losing a round of packets only halves the window once
+        let send_time = start + Duration::from_millis(100);
+        let loss_time = send_time + congestion.retry_delay();
+
+        for _ in 0..3 {
+            congestion.on_packet_sent(packet_size);
+        }
+
+        for _ in 0..3 {
+            congestion.on_packet_lost(loss_time, packet_size, send_time);
+        }
+
+        assert_eq!(congestion.stats().congestion_window, grown_window / 2);
+        assert!(congestion.stats().packet_loss > 0.0);
+    }
+
+    #[test]
+    fn retry_delay_follows_rtt() {
+        let config = Config::default();
+        let now = Instant::now();
+        let mut congestion = CongestionControl::new(&config, now);
+
+        assert_eq!(congestion.retry_delay(), config.initial_rtt);
+
+        for _ in 0..32 {
+            congestion.on_packet_sent(100);
+            congestion.on_packet_acked(100, true, Some(Duration::from_millis(100)));
+        }
+
+        let stats = congestion.stats();
+        assert!(stats.rtt.abs_diff(Duration::from_millis(100)) < Duration::from_millis(1));
+
+        let retry_delay = congestion.retry_delay();
+        assert!(retry_delay >= Duration::from_millis(100));
+        assert!(retry_delay < Duration::from_millis(150));
+
+        // timeouts back off until a new rtt sample arrives
+        congestion.on_packet_sent(100);
+        congestion.on_packet_lost(now + Duration::from_secs(1), 100, now);
+        assert!(congestion.retry_delay() >= retry_delay * 2);
+    }
+}
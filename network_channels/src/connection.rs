@@ -1,5 +1,5 @@
 use crate::packet_sender::PacketSender;
-use crate::{DecodeError, Instant, Label, PacketReceiver};
+use crate::{ConnectionStats, DecodeError, IdentityPublicKey, Instant, Label, PacketReceiver};
 
 pub struct Connection<ChannelLabel: Label> {
     pub(crate) packet_sender: PacketSender<ChannelLabel>,
@@ -11,6 +11,21 @@ impl<ChannelLabel: Label> Connection<ChannelLabel> {
         (self.packet_sender, self.packet_receiver)
     }
 
+    /// True once a session key is negotiated with the peer
+    pub fn is_encrypted(&self) -> bool {
+        self.packet_sender.is_encrypted()
+    }
+
+    /// The identity the peer proved during the handshake, only set for initiators
+    pub fn peer_identity_key(&self) -> Option<IdentityPublicKey> {
+        self.packet_receiver.peer_identity_key()
+    }
+
+    /// RTT, loss, and throughput estimates for this connection
+    pub fn stats(&self) -> ConnectionStats {
+        self.packet_sender.stats()
+    }
+
     pub fn tick(&mut self, now: Instant, send: impl Fn(&[u8])) {
         self.packet_sender.tick(now, send);
     }
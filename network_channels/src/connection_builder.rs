@@ -1,4 +1,7 @@
 use crate::config::Config;
+use crate::encryption::{
+    HandshakeRole, IdentityKey, IdentityPublicKey, KeyExchange, ENCRYPTION_OVERHEAD,
+};
 use crate::packet::{Packet, PacketBuilder};
 use crate::packet_sender::PacketSender;
 use crate::{ChannelSender, Connection, Label, PacketReceiver};
@@ -10,6 +13,7 @@ pub struct ConnectionBuilder<ChannelLabel: Label> {
     receiving_channels: Vec<ChannelLabel>,
     packet_sender: mpsc::Sender<PacketBuilder<ChannelLabel>>,
     packet_receiver: mpsc::Receiver<PacketBuilder<ChannelLabel>>,
+    handshake_role: Option<HandshakeRole>,
 }
 
 impl<ChannelLabel: Label> ConnectionBuilder<ChannelLabel> {
@@ -22,9 +26,23 @@ impl<ChannelLabel: Label> ConnectionBuilder<ChannelLabel> {
             sending_channels: Vec::new(),
             packet_sender,
             packet_receiver,
+            handshake_role: None,
         }
     }
 
+    /// Negotiates a session key with the peer before sending anything else.
+    /// Every datagram after the handshake is encrypted and authenticated, unencrypted datagrams are ignored.
+    /// If `pinned_identity` is set, handshakes from peers proving a different identity are ignored
+    pub fn initiate_handshake(&mut self, pinned_identity: Option<IdentityPublicKey>) {
+        self.handshake_role = Some(HandshakeRole::Initiator { pinned_identity });
+    }
+
+    /// Answers handshakes from peers that initiate one, once a session key is negotiated unencrypted datagrams are ignored.
+    /// The `identity` is proven to the peer, if `required` is set unencrypted datagrams are always ignored
+    pub fn accept_handshake(&mut self, required: bool, identity: IdentityKey) {
+        self.handshake_role = Some(HandshakeRole::Acceptor { required, identity });
+    }
+
     pub fn sending_channel(&mut self, label: ChannelLabel) -> ChannelSender<ChannelLabel> {
         if !self.sending_channels.contains(&label) {
             self.sending_channels.push(label);
@@ -32,7 +50,9 @@ impl<ChannelLabel: Label> ConnectionBuilder<ChannelLabel> {
 
         ChannelSender {
             channel: label,
-            mtu: self.config.mtu as usize - std::mem::size_of::<Packet<'_, ChannelLabel>>(),
+            mtu: self.config.mtu as usize
+                - std::mem::size_of::<Packet<'_, ChannelLabel>>()
+                - ENCRYPTION_OVERHEAD,
             sender: self.packet_sender.clone(),
         }
     }
@@ -50,16 +70,26 @@ impl<ChannelLabel: Label> ConnectionBuilder<ChannelLabel> {
 
     pub fn build(self) -> Connection<ChannelLabel> {
         let (ack_sender, ack_receiver) = mpsc::channel();
+        let (handshake_sender, handshake_receiver) = mpsc::channel();
+
+        let key_exchange = self.handshake_role.map(KeyExchange::new);
 
         let packet_sender = PacketSender::new(
             &self.config,
             &self.sending_channels,
             self.packet_receiver,
             ack_receiver,
+            handshake_receiver,
+            key_exchange.as_ref(),
         );
 
-        let packet_receiver =
-            PacketReceiver::new(&self.receiving_channels, self.packet_sender, ack_sender);
+        let packet_receiver = PacketReceiver::new(
+            &self.receiving_channels,
+            self.packet_sender,
+            ack_sender,
+            handshake_sender,
+            key_exchange,
+        );
 
         Connection {
             packet_sender,
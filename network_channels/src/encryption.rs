@@ -0,0 +1,650 @@
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use hkdf::Hkdf;
+use sha2::Sha256;
+use x25519_dalek::{PublicKey, ReusableSecret, SharedSecret, StaticSecret};
+
+pub(crate) type PublicKeyBytes = [u8; 32];
+
+/// Public half of an [IdentityKey], initiators can pin this to detect impersonation
+pub type IdentityPublicKey = [u8; 32];
+
+/// Room reserved in every datagram for the nonce and authentication tag
+pub(crate) const ENCRYPTION_OVERHEAD: usize = 32;
+
+/// Long term key used by acceptors to prove who they are during handshakes
+#[derive(Clone)]
+pub struct IdentityKey {
+    secret: StaticSecret,
+}
+
+impl IdentityKey {
+    pub fn generate() -> Self {
+        Self {
+            secret: StaticSecret::random(),
+        }
+    }
+
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self {
+            secret: StaticSecret::from(bytes),
+        }
+    }
+
+    pub fn to_bytes(&self) -> [u8; 32] {
+        self.secret.to_bytes()
+    }
+
+    pub fn public_key(&self) -> IdentityPublicKey {
+        PublicKey::from(&self.secret).to_bytes()
+    }
+}
+
+impl std::fmt::Debug for IdentityKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdentityKey")
+            .field("public_key", &self.public_key())
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Clone)]
+pub(crate) enum HandshakeRole {
+    Initiator {
+        pinned_identity: Option<IdentityPublicKey>,
+    },
+    Acceptor {
+        required: bool,
+        identity: IdentityKey,
+    },
+}
+
+impl HandshakeRole {
+    pub(crate) fn is_initiator(&self) -> bool {
+        matches!(self, HandshakeRole::Initiator { .. })
+    }
+}
+
+pub(crate) enum HandshakeEvent {
+    /// The peer sent their public key, acceptors should reply with their own
+    PeerHandshake,
+    KeyEstablished(Encryptor),
+    /// A packet from the peer was successfully decrypted, the peer has our public key
+    Confirmed,
+}
+
+pub(crate) enum HandshakeResult {
+    Established(Encryptor, Decryptor),
+    /// The peer's identity is missing or doesn't match the pinned identity
+    IdentityRejected,
+    /// The peer's keys are unusable
+    Failed,
+}
+
+pub(crate) struct KeyExchange {
+    role: HandshakeRole,
+    secret: ReusableSecret,
+    public_key: PublicKeyBytes,
+    peer_public_key: Option<PublicKeyBytes>,
+    peer_identity: Option<IdentityPublicKey>,
+}
+
+impl KeyExchange {
+    pub(crate) fn new(role: HandshakeRole) -> Self {
+        let secret = ReusableSecret::random();
+        let public_key = PublicKey::from(&secret).to_bytes();
+
+        Self {
+            role,
+            secret,
+            public_key,
+            peer_public_key: None,
+            peer_identity: None,
+        }
+    }
+
+    pub(crate) fn role(&self) -> &HandshakeRole {
+        &self.role
+    }
+
+    pub(crate) fn public_key(&self) -> PublicKeyBytes {
+        self.public_key
+    }
+
+    /// The identity sent with our handshake, only acceptors prove their identity
+    pub(crate) fn identity_key(&self) -> Option<IdentityPublicKey> {
+        match &self.role {
+            HandshakeRole::Initiator { .. } => None,
+            HandshakeRole::Acceptor { identity, .. } => Some(identity.public_key()),
+        }
+    }
+
+    /// The ephemeral key of the peer the session key was established with
+    pub(crate) fn peer_public_key(&self) -> Option<PublicKeyBytes> {
+        self.peer_public_key
+    }
+
+    /// The acceptor's identity, only known to initiators after a session key is established
+    pub(crate) fn peer_identity(&self) -> Option<IdentityPublicKey> {
+        self.peer_identity
+    }
+
+    /// Should only be called until a session key is established, the session key can't be replaced
+    pub(crate) fn complete(
+        &mut self,
+        peer_public_key: PublicKeyBytes,
+        peer_identity: Option<IdentityPublicKey>,
+    ) -> HandshakeResult {
+        if self.peer_public_key.is_some() {
+            return HandshakeResult::Failed;
+        }
+
+        let ephemeral_secret = self
+            .secret
+            .diffie_hellman(&PublicKey::from(peer_public_key));
+
+        // the initiator's ephemeral key is mixed with the acceptor's identity,
+        // only the holder of the identity secret can derive the same session keys
+        let (identity_key, identity_secret) = match &self.role {
+            HandshakeRole::Initiator { pinned_identity } => {
+                let Some(peer_identity) = peer_identity else {
+                    return HandshakeResult::IdentityRejected;
+                };
+
+                if pinned_identity.is_some_and(|pinned| pinned != peer_identity) {
+                    return HandshakeResult::IdentityRejected;
+                }
+
+                let identity_secret = self.secret.diffie_hellman(&PublicKey::from(peer_identity));
+
+                (peer_identity, identity_secret)
+            }
+            HandshakeRole::Acceptor { identity, .. } => {
+                let identity_secret = identity
+                    .secret
+                    .diffie_hellman(&PublicKey::from(peer_public_key));
+
+                (identity.public_key(), identity_secret)
+            }
+        };
+
+        if !ephemeral_secret.was_contributory() || !identity_secret.was_contributory() {
+            return HandshakeResult::Failed;
+        }
+
+        let Some((encryptor, decryptor)) = self.derive_session(
+            peer_public_key,
+            identity_key,
+            &ephemeral_secret,
+            &identity_secret,
+        ) else {
+            return HandshakeResult::Failed;
+        };
+
+        self.peer_public_key = Some(peer_public_key);
+
+        if self.role.is_initiator() {
+            self.peer_identity = Some(identity_key);
+        }
+
+        HandshakeResult::Established(encryptor, decryptor)
+    }
+
+    fn derive_session(
+        &self,
+        peer_public_key: PublicKeyBytes,
+        identity_key: IdentityPublicKey,
+        ephemeral_secret: &SharedSecret,
+        identity_secret: &SharedSecret,
+    ) -> Option<(Encryptor, Decryptor)> {
+        // bind every public key to the session keys
+        let (initiator_key, acceptor_key) = if self.role.is_initiator() {
+            (self.public_key, peer_public_key)
+        } else {
+            (peer_public_key, self.public_key)
+        };
+
+        let mut salt = [0; 96];
+        salt[..32].copy_from_slice(&initiator_key);
+        salt[32..64].copy_from_slice(&acceptor_key);
+        salt[64..].copy_from_slice(&identity_key);
+
+        let mut input_key = [0; 64];
+        input_key[..32].copy_from_slice(ephemeral_secret.as_bytes());
+        input_key[32..].copy_from_slice(identity_secret.as_bytes());
+
+        let hkdf = Hkdf::<Sha256>::new(Some(&salt), &input_key);
+
+        let mut initiator_to_acceptor = Key::default();
+        let mut acceptor_to_initiator = Key::default();
+        hkdf.expand(b"initiator to acceptor", &mut initiator_to_acceptor)
+            .ok()?;
+        hkdf.expand(b"acceptor to initiator", &mut acceptor_to_initiator)
+            .ok()?;
+
+        let (sending_key, receiving_key) = if self.role.is_initiator() {
+            (initiator_to_acceptor, acceptor_to_initiator)
+        } else {
+            (acceptor_to_initiator, initiator_to_acceptor)
+        };
+
+        let encryptor = Encryptor {
+            cipher: ChaCha20Poly1305::new(&sending_key),
+            next_nonce: 0,
+        };
+
+        let decryptor = Decryptor {
+            cipher: ChaCha20Poly1305::new(&receiving_key),
+            latest_nonce: None,
+            received_window: 0,
+        };
+
+        Some((encryptor, decryptor))
+    }
+}
+
+fn create_nonce(nonce: u64) -> Nonce {
+    let mut bytes = Nonce::default();
+    bytes[..8].copy_from_slice(&nonce.to_le_bytes());
+    bytes
+}
+
+pub(crate) struct Encryptor {
+    cipher: ChaCha20Poly1305,
+    next_nonce: u64,
+}
+
+impl Encryptor {
+    /// Returns the nonce and ciphertext, every call uses a new nonce
+    pub(crate) fn encrypt(&mut self, data: &[u8]) -> (u64, Vec<u8>) {
+        let nonce = self.next_nonce;
+        self.next_nonce += 1;
+
+        let ciphertext = self
+            .cipher
+            .encrypt(&create_nonce(nonce), data)
+            .expect("ChaCha20Poly1305 encryption should not fail for datagram sized data");
+
+        (nonce, ciphertext)
+    }
+}
+
+pub(crate) struct Decryptor {
+    cipher: ChaCha20Poly1305,
+    latest_nonce: Option<u64>,
+    /// Bit n is set if `latest_nonce - n` was received
+    received_window: u64,
+}
+
+impl Decryptor {
+    /// Returns None for tampered, forged, or replayed datagrams
+    pub(crate) fn decrypt(&mut self, nonce: u64, data: &[u8]) -> Option<Vec<u8>> {
+        if self.is_replayed(nonce) {
+            return None;
+        }
+
+        let plaintext = self.cipher.decrypt(&create_nonce(nonce), data).ok()?;
+
+        self.track_nonce(nonce);
+
+        Some(plaintext)
+    }
+
+    fn is_replayed(&self, nonce: u64) -> bool {
+        let Some(latest_nonce) = self.latest_nonce else {
+            return false;
+        };
+
+        if nonce > latest_nonce {
+            return false;
+        }
+
+        let offset = latest_nonce - nonce;
+
+        // anything older than the window is treated as a replay
+        offset >= u64::BITS as u64 || self.received_window & (1 << offset) != 0
+    }
+
+    fn track_nonce(&mut self, nonce: u64) {
+        let Some(latest_nonce) = self.latest_nonce else {
+            self.latest_nonce = Some(nonce);
+            self.received_window = 1;
+            return;
+        };
+
+        if nonce > latest_nonce {
+            let shift = nonce - latest_nonce;
+
+            self.received_window = self.received_window.checked_shl(shift as u32).unwrap_or(0);
+            self.received_window |= 1;
+            self.latest_nonce = Some(nonce);
+        } else {
+            self.received_window |= 1 << (latest_nonce - nonce);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::packet::Packet;
+    use crate::{
+        deserialize, serialize, ChannelSender, Config, Connection, ConnectionBuilder, Instant,
+        Reliability,
+    };
+    use std::cell::RefCell;
+
+    fn create_pair() -> ((Encryptor, Decryptor), (Encryptor, Decryptor)) {
+        let identity = IdentityKey::generate();
+
+        let mut initiator = KeyExchange::new(HandshakeRole::Initiator {
+            pinned_identity: Some(identity.public_key()),
+        });
+        let mut acceptor = KeyExchange::new(HandshakeRole::Acceptor {
+            required: true,
+            identity,
+        });
+
+        let HandshakeResult::Established(encryptor, decryptor) =
+            initiator.complete(acceptor.public_key(), acceptor.identity_key())
+        else {
+            panic!("initiator failed to establish a session key");
+        };
+
+        let initiator_pair = (encryptor, decryptor);
+
+        let HandshakeResult::Established(encryptor, decryptor) =
+            acceptor.complete(initiator.public_key(), None)
+        else {
+            panic!("acceptor failed to establish a session key");
+        };
+
+        (initiator_pair, (encryptor, decryptor))
+    }
+
+    type ConnectionPair = (ChannelSender<()>, Connection<()>);
+
+    fn create_connections(
+        pinned_identity: Option<IdentityPublicKey>,
+        identity: IdentityKey,
+    ) -> (ConnectionPair, ConnectionPair) {
+        let config = Config::default();
+
+        let mut builder_a = ConnectionBuilder::new(&config);
+        let channel_a = builder_a.bidirectional_channel(());
+        builder_a.initiate_handshake(pinned_identity);
+
+        let mut builder_b = ConnectionBuilder::new(&config);
+        let channel_b = builder_b.bidirectional_channel(());
+        builder_b.accept_handshake(true, identity);
+
+        (
+            (channel_a, builder_a.build()),
+            (channel_b, builder_b.build()),
+        )
+    }
+
+    /// Ticks both connections, returning messages received by each side
+    fn exchange(
+        connection_a: &mut Connection<()>,
+        connection_b: &mut Connection<()>,
+        rounds: usize,
+    ) -> (Vec<Vec<u8>>, Vec<Vec<u8>>) {
+        let a_datagrams = RefCell::new(Vec::new());
+        let b_datagrams = RefCell::new(Vec::new());
+        let mut received_a = Vec::new();
+        let mut received_b = Vec::new();
+
+        for _ in 0..rounds {
+            let now = Instant::now();
+
+            connection_a.tick(now, |bytes| a_datagrams.borrow_mut().push(bytes.to_vec()));
+
+            for bytes in a_datagrams.take() {
+                assert!(!bytes.windows(8).any(|window| window == b"identity"));
+
+                if let Some((_, messages)) = connection_b.receive_packet(now, &bytes).unwrap() {
+                    received_b.extend(messages);
+                }
+            }
+
+            connection_b.tick(now, |bytes| b_datagrams.borrow_mut().push(bytes.to_vec()));
+
+            for bytes in b_datagrams.take() {
+                if let Some((_, messages)) = connection_a.receive_packet(now, &bytes).unwrap() {
+                    received_a.extend(messages);
+                }
+            }
+        }
+
+        (received_a, received_b)
+    }
+
+    fn forged_handshake(role: HandshakeRole) -> Vec<u8> {
+        let key_exchange = KeyExchange::new(role);
+
+        let packet: Packet<()> = Packet::Handshake {
+            public_key: key_exchange.public_key(),
+            identity_key: key_exchange.identity_key(),
+        };
+
+        serialize(&packet)
+    }
+
+    #[test]
+    fn session_keys_match() {
+        let (
+            (mut initiator_encryptor, mut initiator_decryptor),
+            (mut acceptor_encryptor, mut acceptor_decryptor),
+        ) = create_pair();
+
+        let (nonce, ciphertext) = initiator_encryptor.encrypt(b"identity");
+        assert_ne!(ciphertext.as_slice(), b"identity");
+        assert_eq!(
+            acceptor_decryptor.decrypt(nonce, &ciphertext).as_deref(),
+            Some(&b"identity"[..])
+        );
+
+        let (nonce, ciphertext) = acceptor_encryptor.encrypt(b"reply");
+        assert_eq!(
+            initiator_decryptor.decrypt(nonce, &ciphertext).as_deref(),
+            Some(&b"reply"[..])
+        );
+    }
+
+    #[test]
+    fn rejects_tampered_and_replayed_datagrams() {
+        let ((mut encryptor, _), (_, mut decryptor)) = create_pair();
+
+        let (first_nonce, first) = encryptor.encrypt(b"first");
+        let (second_nonce, mut second) = encryptor.encrypt(b"second");
+
+        // out of order is fine
+        assert!(decryptor.decrypt(second_nonce, &second).is_some());
+        assert!(decryptor.decrypt(first_nonce, &first).is_some());
+
+        // replays are not
+        assert!(decryptor.decrypt(first_nonce, &first).is_none());
+        assert!(decryptor.decrypt(second_nonce, &second).is_none());
+
+        let (third_nonce, _) = encryptor.encrypt(b"third");
+        second[0] ^= 1;
+        assert!(decryptor.decrypt(third_nonce, &second).is_none());
+    }
+
+    #[test]
+    fn rejects_unpinned_identities() {
+        let identity = IdentityKey::generate();
+
+        let mut initiator = KeyExchange::new(HandshakeRole::Initiator {
+            pinned_identity: Some(identity.public_key()),
+        });
+
+        let impostor = KeyExchange::new(HandshakeRole::Acceptor {
+            required: true,
+            identity: IdentityKey::generate(),
+        });
+
+        assert!(matches!(
+            initiator.complete(impostor.public_key(), impostor.identity_key()),
+            HandshakeResult::IdentityRejected
+        ));
+
+        assert!(matches!(
+            initiator.complete(impostor.public_key(), None),
+            HandshakeResult::IdentityRejected
+        ));
+
+        assert!(initiator.peer_identity().is_none());
+    }
+
+    #[test]
+    fn impostors_cannot_derive_session_keys() {
+        let identity = IdentityKey::generate();
+
+        let mut initiator = KeyExchange::new(HandshakeRole::Initiator {
+            pinned_identity: Some(identity.public_key()),
+        });
+
+        // claims the pinned identity without holding its secret
+        let mut impostor = KeyExchange::new(HandshakeRole::Acceptor {
+            required: true,
+            identity: IdentityKey::generate(),
+        });
+
+        let HandshakeResult::Established(mut encryptor, _) =
+            initiator.complete(impostor.public_key(), Some(identity.public_key()))
+        else {
+            panic!("initiator failed to establish a session key");
+        };
+
+        let HandshakeResult::Established(_, mut decryptor) =
+            impostor.complete(initiator.public_key(), None)
+        else {
+            panic!("impostor failed to establish a session key");
+        };
+
+        let (nonce, ciphertext) = encryptor.encrypt(b"identity");
+        assert!(decryptor.decrypt(nonce, &ciphertext).is_none());
+    }
+
+    #[test]
+    fn handshake_over_connections() {
+        let identity = IdentityKey::generate();
+        let identity_public_key = identity.public_key();
+
+        let ((channel_a, mut connection_a), (_, mut connection_b)) =
+            create_connections(None, identity);
+
+        channel_a.send_serialized(Reliability::Reliable, "identity");
+
+        let (_, received) = exchange(&mut connection_a, &mut connection_b, 4);
+
+        assert!(connection_a.is_encrypted());
+        assert!(connection_b.is_encrypted());
+        assert_eq!(connection_a.peer_identity_key(), Some(identity_public_key));
+        assert_eq!(received.len(), 1);
+        assert_eq!(deserialize::<&str>(&received[0]).unwrap(), "identity");
+    }
+
+    #[test]
+    fn ignores_unpinned_acceptors() {
+        let pinned_identity = IdentityKey::generate().public_key();
+
+        let ((channel_a, mut connection_a), (_, mut connection_b)) =
+            create_connections(Some(pinned_identity), IdentityKey::generate());
+
+        channel_a.send_serialized(Reliability::Reliable, "identity");
+
+        let (_, received) = exchange(&mut connection_a, &mut connection_b, 4);
+
+        assert!(!connection_a.is_encrypted());
+        assert!(connection_a.packet_receiver.identity_rejected());
+        assert_eq!(connection_a.peer_identity_key(), None);
+        assert!(received.is_empty());
+    }
+
+    #[test]
+    fn ignores_handshakes_after_key_exchange() {
+        let identity = IdentityKey::generate();
+
+        let ((channel_a, mut connection_a), (channel_b, mut connection_b)) =
+            create_connections(Some(identity.public_key()), identity.clone());
+
+        exchange(&mut connection_a, &mut connection_b, 4);
+        assert!(connection_a.is_encrypted());
+        assert!(connection_b.is_encrypted());
+
+        // forged handshakes for both sides, even one using the real identity
+        let now = Instant::now();
+        let forged_initiator = forged_handshake(HandshakeRole::Initiator {
+            pinned_identity: None,
+        });
+        let forged_acceptor = forged_handshake(HandshakeRole::Acceptor {
+            required: true,
+            identity,
+        });
+
+        assert!(connection_b
+            .receive_packet(now, &forged_initiator)
+            .unwrap()
+            .is_none());
+        assert!(connection_a
+            .receive_packet(now, &forged_acceptor)
+            .unwrap()
+            .is_none());
+
+        channel_a.send_serialized(Reliability::Reliable, "identity");
+        channel_b.send_serialized(Reliability::Reliable, "reply");
+
+        let (received_a, received_b) = exchange(&mut connection_a, &mut connection_b, 4);
+
+        assert_eq!(received_a.len(), 1);
+        assert_eq!(deserialize::<&str>(&received_a[0]).unwrap(), "reply");
+        assert_eq!(received_b.len(), 1);
+        assert_eq!(deserialize::<&str>(&received_b[0]).unwrap(), "identity");
+    }
+
+    #[test]
+    fn ignores_upgrades_after_plaintext() {
+        let identity = IdentityKey::generate();
+        let config = Config::default();
+
+        let mut builder_a = ConnectionBuilder::new(&config);
+        let channel_a = builder_a.bidirectional_channel(());
+        let mut connection_a = builder_a.build();
+
+        let mut builder_b = ConnectionBuilder::new(&config);
+        let channel_b = builder_b.bidirectional_channel(());
+        builder_b.accept_handshake(false, identity);
+        let mut connection_b = builder_b.build();
+
+        channel_a.send_serialized(Reliability::Reliable, "plaintext");
+
+        let (_, received_b) = exchange(&mut connection_a, &mut connection_b, 4);
+        assert_eq!(received_b.len(), 1);
+
+        let forged_initiator = forged_handshake(HandshakeRole::Initiator {
+            pinned_identity: None,
+        });
+
+        assert!(connection_b
+            .receive_packet(Instant::now(), &forged_initiator)
+            .unwrap()
+            .is_none());
+        assert!(!connection_b.is_encrypted());
+
+        channel_a.send_serialized(Reliability::Reliable, "still plaintext");
+        channel_b.send_serialized(Reliability::Reliable, "reply");
+
+        let (received_a, received_b) = exchange(&mut connection_a, &mut connection_b, 4);
+
+        assert_eq!(received_a.len(), 1);
+        assert_eq!(deserialize::<&str>(&received_a[0]).unwrap(), "reply");
+        assert_eq!(received_b.len(), 1);
+        assert_eq!(
+            deserialize::<&str>(&received_b[0]).unwrap(),
+            "still plaintext"
+        );
+    }
+}
@@ -2,8 +2,10 @@ mod channel_receiver;
 mod channel_send_tracking;
 mod channel_sender;
 mod config;
+mod congestion;
 mod connection;
 mod connection_builder;
+mod encryption;
 mod label;
 mod packet;
 mod packet_receiver;
@@ -13,8 +15,10 @@ mod serialize;
 
 pub use channel_sender::*;
 pub use config::*;
+pub use congestion::ConnectionStats;
 pub use connection::*;
 pub use connection_builder::*;
+pub use encryption::{IdentityKey, IdentityPublicKey};
 pub use label::*;
 pub use packet_receiver::*;
 pub use packet_sender::*;
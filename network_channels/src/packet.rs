@@ -1,4 +1,5 @@
-use crate::{Instant, Label, Reliability};
+use crate::encryption::{IdentityPublicKey, PublicKeyBytes};
+use crate::{Instant, Reliability};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
 use std::sync::Arc;
@@ -20,15 +21,16 @@ pub(crate) enum Packet<'a, ChannelLabel> {
     Ack {
         header: PacketHeader<ChannelLabel>,
     },
-}
-
-impl<'a, ChannelLabel: Label> Packet<'a, ChannelLabel> {
-    pub(crate) fn channel(&self) -> ChannelLabel {
-        match self {
-            Packet::Message { header, .. } => header.channel,
-            Packet::Ack { header } => header.channel,
-        }
-    }
+    Handshake {
+        public_key: PublicKeyBytes,
+        /// Sent by acceptors
+        identity_key: Option<IdentityPublicKey>,
+    },
+    /// Wraps a serialized Message or Ack
+    Encrypted {
+        nonce: u64,
+        data: &'a [u8],
+    },
 }
 
 pub(crate) enum PacketBuilder<ChannelLabel> {
@@ -1,5 +1,9 @@
 use crate::channel_receiver::ChannelReceiver;
 use crate::deserialize;
+use crate::encryption::{
+    Decryptor, HandshakeEvent, HandshakeResult, HandshakeRole, IdentityPublicKey, KeyExchange,
+    PublicKeyBytes,
+};
 use crate::packet::{Ack, Packet, PacketBuilder};
 use crate::{DecodeError, Instant, Label};
 use std::sync::mpsc;
@@ -10,6 +14,13 @@ pub struct PacketReceiver<ChannelLabel> {
     channel_receivers: Vec<ChannelReceiver<ChannelLabel>>,
     packet_sender: mpsc::Sender<PacketBuilder<ChannelLabel>>,
     ack_sender: mpsc::Sender<Ack<ChannelLabel>>,
+    handshake_sender: mpsc::Sender<HandshakeEvent>,
+    key_exchange: Option<KeyExchange>,
+    decryptor: Option<Decryptor>,
+    encryption_required: bool,
+    encryption_confirmed: bool,
+    received_plaintext: bool,
+    identity_rejected: bool,
     last_receive_time: Instant,
 }
 
@@ -18,16 +29,31 @@ impl<ChannelLabel: Label> PacketReceiver<ChannelLabel> {
         channels: &[ChannelLabel],
         packet_sender: mpsc::Sender<PacketBuilder<ChannelLabel>>,
         ack_sender: mpsc::Sender<Ack<ChannelLabel>>,
+        handshake_sender: mpsc::Sender<HandshakeEvent>,
+        key_exchange: Option<KeyExchange>,
     ) -> Self {
         let channel_receivers: Vec<_> = channels
             .iter()
             .map(|channel| ChannelReceiver::new(*channel))
             .collect();
 
+        let encryption_required = match key_exchange.as_ref().map(KeyExchange::role) {
+            Some(HandshakeRole::Initiator { .. }) => true,
+            Some(HandshakeRole::Acceptor { required, .. }) => *required,
+            None => false,
+        };
+
         Self {
             channel_receivers,
             packet_sender,
             ack_sender,
+            handshake_sender,
+            key_exchange,
+            decryptor: None,
+            encryption_required,
+            encryption_confirmed: false,
+            received_plaintext: false,
+            identity_rejected: false,
             last_receive_time: Instant::now(),
         }
     }
@@ -36,16 +62,126 @@ impl<ChannelLabel: Label> PacketReceiver<ChannelLabel> {
         self.last_receive_time
     }
 
+    /// True once a session key is negotiated, unencrypted packets are ignored from this point
+    pub fn is_encrypted(&self) -> bool {
+        self.decryptor.is_some()
+    }
+
+    /// The identity the peer proved during the handshake, only set for initiators
+    pub fn peer_identity_key(&self) -> Option<IdentityPublicKey> {
+        self.key_exchange.as_ref()?.peer_identity()
+    }
+
+    /// True if the peer sent a handshake without the pinned identity, these handshakes are ignored
+    pub fn identity_rejected(&self) -> bool {
+        self.identity_rejected
+    }
+
     pub fn receive_packet<'a>(
         &mut self,
         now: Instant,
         data: &'a [u8],
     ) -> ReceiveResult<Option<(ChannelLabel, Vec<Vec<u8>>)>> {
-        self.last_receive_time = now;
-
         let packet: Packet<'a, ChannelLabel> = deserialize(data)?;
 
-        let channel = packet.channel();
+        match packet {
+            Packet::Handshake {
+                public_key,
+                identity_key,
+            } => {
+                self.last_receive_time = now;
+                self.receive_handshake(public_key, identity_key);
+                Ok(None)
+            }
+            Packet::Encrypted { nonce, data } => {
+                let Some(decryptor) = &mut self.decryptor else {
+                    return Ok(None);
+                };
+
+                let Some(plaintext) = decryptor.decrypt(nonce, data) else {
+                    // forged or replayed
+                    return Ok(None);
+                };
+
+                if !self.encryption_confirmed {
+                    self.encryption_confirmed = true;
+                    let _ = self.handshake_sender.send(HandshakeEvent::Confirmed);
+                }
+
+                let packet: Packet<'_, ChannelLabel> = deserialize(&plaintext)?;
+
+                if matches!(packet, Packet::Handshake { .. } | Packet::Encrypted { .. }) {
+                    return Ok(None);
+                }
+
+                Ok(self.receive_decrypted_packet(now, packet))
+            }
+            packet => {
+                if self.encryption_required {
+                    return Ok(None);
+                }
+
+                self.received_plaintext = true;
+
+                Ok(self.receive_decrypted_packet(now, packet))
+            }
+        }
+    }
+
+    fn receive_handshake(
+        &mut self,
+        public_key: PublicKeyBytes,
+        identity_key: Option<IdentityPublicKey>,
+    ) {
+        let Some(key_exchange) = &mut self.key_exchange else {
+            return;
+        };
+
+        let is_acceptor = !key_exchange.role().is_initiator();
+
+        if let Some(peer_public_key) = key_exchange.peer_public_key() {
+            // the session key is never replaced, a repeated handshake means our reply was lost
+            if is_acceptor && peer_public_key == public_key {
+                let _ = self.handshake_sender.send(HandshakeEvent::PeerHandshake);
+            }
+
+            return;
+        }
+
+        if self.received_plaintext {
+            // the peer already chose plaintext, an upgrade now is likely spoofed
+            // and would cause us to drop every packet from the real peer
+            return;
+        }
+
+        if is_acceptor {
+            // resent every time in case the reply was lost
+            let _ = self.handshake_sender.send(HandshakeEvent::PeerHandshake);
+        }
+
+        let (encryptor, decryptor) = match key_exchange.complete(public_key, identity_key) {
+            HandshakeResult::Established(encryptor, decryptor) => (encryptor, decryptor),
+            HandshakeResult::IdentityRejected => {
+                self.identity_rejected = true;
+                return;
+            }
+            HandshakeResult::Failed => return,
+        };
+
+        self.decryptor = Some(decryptor);
+        self.encryption_required = true;
+
+        let _ = self
+            .handshake_sender
+            .send(HandshakeEvent::KeyEstablished(encryptor));
+    }
+
+    fn receive_decrypted_packet(
+        &mut self,
+        now: Instant,
+        packet: Packet<'_, ChannelLabel>,
+    ) -> Option<(ChannelLabel, Vec<Vec<u8>>)> {
+        self.last_receive_time = now;
 
         match packet {
             Packet::Message {
@@ -64,10 +200,10 @@ impl<ChannelLabel: Label> PacketReceiver<ChannelLabel> {
                     .iter_mut()
                     .find(|r| r.channel() == header.channel)
                 {
-                    return Ok(Some((
-                        channel,
+                    return Some((
+                        header.channel,
                         receiver.sort_packet(header, fragment_type, data.to_vec()),
-                    )));
+                    ));
                 }
 
                 let _ = self.ack_sender.send(Ack {
@@ -81,8 +217,9 @@ impl<ChannelLabel: Label> PacketReceiver<ChannelLabel> {
                     time: now,
                 });
             }
+            Packet::Handshake { .. } | Packet::Encrypted { .. } => {}
         }
 
-        Ok(None)
+        None
     }
 }
@@ -1,15 +1,28 @@
 use crate::channel_send_tracking::ChannelSendTracking;
 use crate::config::Config;
+use crate::congestion::{CongestionControl, ConnectionStats};
+use crate::encryption::{Encryptor, HandshakeEvent, KeyExchange};
 use crate::packet::{Ack, FragmentType, Packet, PacketBuilder, PacketHeader};
 use crate::{serialize, Label};
-use instant::{Duration, Instant};
+use instant::Instant;
 use std::sync::mpsc;
 
 pub struct StoredPacket<ChannelLabel> {
     header: PacketHeader<ChannelLabel>,
     bytes: Vec<u8>,
-    creation: Instant,
+    send_count: u32,
+    last_send: Instant,
     next_retry: Instant,
+    in_flight: bool,
+}
+
+struct SendingHandshake {
+    is_initiator: bool,
+    handshake_bytes: Vec<u8>,
+    encryptor: Option<Encryptor>,
+    confirmed: bool,
+    reply_pending: bool,
+    next_handshake: Instant,
 }
 
 pub struct PacketSender<ChannelLabel: Label> {
@@ -17,10 +30,11 @@ pub struct PacketSender<ChannelLabel: Label> {
     send_trackers: Vec<ChannelSendTracking<ChannelLabel>>,
     packet_receiver: mpsc::Receiver<PacketBuilder<ChannelLabel>>,
     ack_receiver: mpsc::Receiver<Ack<ChannelLabel>>,
+    handshake_receiver: mpsc::Receiver<HandshakeEvent>,
+    handshake: Option<SendingHandshake>,
     stored_packets: Vec<StoredPacket<ChannelLabel>>,
     last_receive_time: Instant,
-    rtt_resend_factor: f32,
-    retry_delay: Duration,
+    congestion: CongestionControl,
 }
 
 impl<ChannelLabel: Label> PacketSender<ChannelLabel> {
@@ -29,21 +43,40 @@ impl<ChannelLabel: Label> PacketSender<ChannelLabel> {
         channels: &[ChannelLabel],
         packet_receiver: mpsc::Receiver<PacketBuilder<ChannelLabel>>,
         ack_receiver: mpsc::Receiver<Ack<ChannelLabel>>,
+        handshake_receiver: mpsc::Receiver<HandshakeEvent>,
+        key_exchange: Option<&KeyExchange>,
     ) -> Self {
         let send_trackers: Vec<_> = channels
             .iter()
             .map(|label| ChannelSendTracking::new(*label))
             .collect();
 
+        let handshake = key_exchange.map(|key_exchange| {
+            let packet: Packet<ChannelLabel> = Packet::Handshake {
+                public_key: key_exchange.public_key(),
+                identity_key: key_exchange.identity_key(),
+            };
+
+            SendingHandshake {
+                is_initiator: key_exchange.role().is_initiator(),
+                handshake_bytes: serialize(&packet),
+                encryptor: None,
+                confirmed: false,
+                reply_pending: false,
+                next_handshake: Instant::now(),
+            }
+        });
+
         Self {
             bytes_per_tick: config.bytes_per_tick,
             send_trackers,
             packet_receiver,
             ack_receiver,
+            handshake_receiver,
+            handshake,
             stored_packets: Vec::new(),
             last_receive_time: Instant::now(),
-            rtt_resend_factor: config.rtt_resend_factor,
-            retry_delay: config.initial_rtt.mul_f32(config.rtt_resend_factor),
+            congestion: CongestionControl::new(config, Instant::now()),
         }
     }
 
@@ -52,6 +85,16 @@ impl<ChannelLabel: Label> PacketSender<ChannelLabel> {
         self.last_receive_time
     }
 
+    /// True once a session key is negotiated, every packet sent from this point is encrypted
+    pub fn is_encrypted(&self) -> bool {
+        (self.handshake.as_ref()).is_some_and(|handshake| handshake.encryptor.is_some())
+    }
+
+    /// Estimates based on acks and packets sent, updates after a tick()
+    pub fn stats(&self) -> ConnectionStats {
+        self.congestion.stats()
+    }
+
     /// Sends pending packets including internally generated packets such as Acks, updates last_receive_time
     pub fn tick(&mut self, now: Instant, send: impl Fn(&[u8])) {
         while let Ok(ack) = self.ack_receiver.try_recv() {
@@ -66,12 +109,31 @@ impl<ChannelLabel: Label> PacketSender<ChannelLabel> {
             if let Some(index) = packets_iter.position(|packet| packet.header == header) {
                 let packet = self.stored_packets.remove(index);
 
-                let rtt = ack.time - packet.creation;
-                let updated_retry_delay = rtt.mul_f32(self.rtt_resend_factor);
-                self.retry_delay = self.retry_delay.min(updated_retry_delay);
+                // retransmitted packets are ambiguous, we can't tell which send was acked
+                let rtt_sample = (packet.send_count == 1).then(|| ack.time - packet.last_send);
+
+                self.congestion
+                    .on_packet_acked(packet.bytes.len(), packet.in_flight, rtt_sample);
             }
         }
 
+        if !self.update_handshake(now, &send) {
+            // messages wait for a session key
+            return;
+        }
+
+        let mut send = |bytes: &[u8]| match self.handshake.as_mut() {
+            Some(SendingHandshake {
+                encryptor: Some(encryptor),
+                ..
+            }) => {
+                let (nonce, data) = encryptor.encrypt(bytes);
+                let packet: Packet<ChannelLabel> = Packet::Encrypted { nonce, data: &data };
+                send(&serialize(&packet));
+            }
+            _ => send(bytes),
+        };
+
         let mut budget = self.bytes_per_tick;
 
         while let Ok(packet_instruction) = self.packet_receiver.try_recv() {
@@ -113,20 +175,19 @@ impl<ChannelLabel: Label> PacketSender<ChannelLabel> {
                         data: &data[range.start..range.end],
                     });
 
-                    let sending = bytes.len() <= budget;
-
-                    if sending {
-                        budget -= bytes.len();
-                        send(&bytes);
-                    }
-
                     if reliability.is_reliable() {
+                        // sent in order with retransmissions below
                         self.stored_packets.push(StoredPacket {
                             header,
                             bytes,
-                            creation: now,
-                            next_retry: if sending { now + self.retry_delay } else { now },
-                        })
+                            send_count: 0,
+                            last_send: now,
+                            next_retry: now,
+                            in_flight: false,
+                        });
+                    } else if bytes.len() <= budget {
+                        budget -= bytes.len();
+                        send(&bytes);
                     }
                 }
                 PacketBuilder::Ack { header, time } => {
@@ -142,11 +203,63 @@ impl<ChannelLabel: Label> PacketSender<ChannelLabel> {
         }
 
         for packet in &mut self.stored_packets {
-            if packet.next_retry <= now && packet.bytes.len() <= budget {
-                budget -= packet.bytes.len();
-                send(&packet.bytes);
-                packet.next_retry = now + self.retry_delay;
+            if packet.in_flight && packet.next_retry <= now {
+                packet.in_flight = false;
+
+                self.congestion
+                    .on_packet_lost(now, packet.bytes.len(), packet.last_send);
+            }
+        }
+
+        let retry_delay = self.congestion.retry_delay();
+
+        for packet in &mut self.stored_packets {
+            if packet.in_flight || packet.bytes.len() > budget {
+                continue;
+            }
+
+            if !self.congestion.can_send(packet.bytes.len()) {
+                break;
+            }
+
+            budget -= packet.bytes.len();
+            send(&packet.bytes);
+
+            packet.send_count += 1;
+            packet.last_send = now;
+            packet.next_retry = now + retry_delay;
+            packet.in_flight = true;
+
+            self.congestion.on_packet_sent(packet.bytes.len());
+        }
+
+        self.congestion
+            .update_rates(now, self.bytes_per_tick - budget);
+    }
+
+    /// Returns false while messages should be held back
+    fn update_handshake(&mut self, now: Instant, send: &impl Fn(&[u8])) -> bool {
+        let Some(handshake) = &mut self.handshake else {
+            return true;
+        };
+
+        while let Ok(event) = self.handshake_receiver.try_recv() {
+            match event {
+                HandshakeEvent::PeerHandshake => handshake.reply_pending = true,
+                HandshakeEvent::KeyEstablished(encryptor) => handshake.encryptor = Some(encryptor),
+                HandshakeEvent::Confirmed => handshake.confirmed = true,
             }
         }
+
+        let is_initiator = handshake.is_initiator;
+        let resend = is_initiator && !handshake.confirmed && handshake.next_handshake <= now;
+
+        if handshake.reply_pending || resend {
+            send(&handshake.handshake_bytes);
+            handshake.reply_pending = false;
+            handshake.next_handshake = now + self.congestion.retry_delay();
+        }
+
+        !is_initiator || handshake.encryptor.is_some()
     }
 }
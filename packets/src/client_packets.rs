@@ -1,6 +1,6 @@
 // Increment VERSION_ITERATION packets/src/lib.rs if packets are added or modified
 
-use super::structures::{BattleStatistics, Direction};
+use super::structures::{BattleStatistics, ChatChannel, Direction};
 use crate::structures::{ActorId, PackageId};
 use serde::{Deserialize, Serialize};
 use strum::IntoStaticStr;
@@ -60,6 +60,10 @@ pub enum ClientPacket {
     Emote {
         emote_id: String,
     },
+    Chat {
+        channel: ChatChannel,
+        message: String,
+    },
     CustomWarp {
         tile_object_id: u32,
     },
@@ -101,6 +105,12 @@ pub enum ClientPacket {
     ItemUse {
         item_id: String,
     },
+    MailRead {
+        mail_id: String,
+    },
+    MailClaim {
+        mail_id: String,
+    },
     EncounterStart,
     BattleResults {
         battle_stats: BattleStatistics,
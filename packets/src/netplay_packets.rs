@@ -3,6 +3,7 @@
 use crate::structures::{
     FileHash, Input, InstalledBlock, InstalledSwitchDrive, PackageCategory, PackageId,
 };
+use crate::NetplaySeedSecret;
 use serde::{Deserialize, Serialize};
 use strum::IntoStaticStr;
 
@@ -58,15 +59,56 @@ pub enum NetplayPacket {
         index: usize,
         data: Vec<u8>,
     },
+    /// Sent once every package is loaded, commits to a seed secret without revealing it
     Ready {
         index: usize,
-        seed: u64,
+        seed_commitment: FileHash,
+    },
+    /// Sent after receiving every player's commitment
+    SeedReveal {
+        index: usize,
+        seed_secret: NetplaySeedSecret,
     },
     Buffer {
         index: usize,
         data: NetplayBufferItem,
         lead: Vec<i16>,
     },
+    Checksum {
+        index: usize,
+        time: i64,
+        checksum: u64,
+    },
+    /// Sent by spectators to request a SpectatorSync from every player
+    SpectatorJoin {
+        index: usize,
+    },
+    /// Everything a spectator needs to simulate a player from the start of the battle
+    SpectatorSync {
+        index: usize,
+        seed: u64,
+        player_package: PackageId,
+        script_enabled: bool,
+        // package_id, code
+        cards: Vec<(PackageId, String)>,
+        regular_card: Option<usize>,
+        recipes: Vec<PackageId>,
+        blocks: Vec<InstalledBlock>,
+        drives: Vec<InstalledSwitchDrive>,
+        // category, package_id, hash
+        packages: Vec<(PackageCategory, PackageId, FileHash)>,
+        // input since the start of the battle, as (item, repeat count)
+        buffer: Vec<(NetplayBufferItem, usize)>,
+    },
+    SpectatorBuffer {
+        index: usize,
+        time: i64,
+        data: NetplayBufferItem,
+    },
+    SpectatorPackageZip {
+        index: usize,
+        data: Vec<u8>,
+    },
 }
 
 impl NetplayPacket {
@@ -92,7 +134,23 @@ impl NetplayPacket {
             NetplayPacket::ReadyForPackages { index } => *index,
             NetplayPacket::PackageZip { index, .. } => *index,
             NetplayPacket::Ready { index, .. } => *index,
+            NetplayPacket::SeedReveal { index, .. } => *index,
             NetplayPacket::Buffer { index, .. } => *index,
+            NetplayPacket::Checksum { index, .. } => *index,
+            NetplayPacket::SpectatorJoin { index } => *index,
+            NetplayPacket::SpectatorSync { index, .. } => *index,
+            NetplayPacket::SpectatorBuffer { index, .. } => *index,
+            NetplayPacket::SpectatorPackageZip { index, .. } => *index,
         }
     }
+
+    /// Packets that should only be forwarded to spectators
+    pub fn is_for_spectators(&self) -> bool {
+        matches!(
+            self,
+            NetplayPacket::SpectatorSync { .. }
+                | NetplayPacket::SpectatorBuffer { .. }
+                | NetplayPacket::SpectatorPackageZip { .. }
+        )
+    }
 }
@@ -0,0 +1,76 @@
+use crate::structures::FileHash;
+
+/// Random bytes every player contributes to the battle seed
+pub type NetplaySeedSecret = [u8; 32];
+
+/// The commitment includes the player's index, so copying another player's commitment and reveal
+/// can't be used to cancel out their contribution
+pub fn netplay_seed_commitment(index: usize, secret: &NetplaySeedSecret) -> FileHash {
+    let mut data = Vec::with_capacity(16 + secret.len());
+    data.extend(b"netplay seed");
+    data.extend((index as u32).to_le_bytes());
+    data.extend(secret);
+
+    FileHash::hash(&data)
+}
+
+/// Every player must reveal their secret for the seed to be resolved,
+/// the order of `secrets` doesn't matter
+pub fn combine_netplay_seed_secrets(secrets: &[(usize, NetplaySeedSecret)]) -> u64 {
+    let mut secrets = secrets.to_vec();
+    secrets.sort_by_key(|(index, _)| *index);
+
+    let mut data = Vec::with_capacity(secrets.len() * 36);
+
+    for (index, secret) in secrets {
+        data.extend((index as u32).to_le_bytes());
+        data.extend(secret);
+    }
+
+    let hash = FileHash::hash(&data);
+    let mut seed_bytes = [0; 8];
+    seed_bytes.copy_from_slice(&hash.as_bytes()[..8]);
+
+    u64::from_le_bytes(seed_bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn commitments_are_bound_to_index() {
+        let secret = [7; 32];
+
+        assert_eq!(
+            netplay_seed_commitment(0, &secret),
+            netplay_seed_commitment(0, &secret)
+        );
+        assert_ne!(
+            netplay_seed_commitment(0, &secret),
+            netplay_seed_commitment(1, &secret)
+        );
+        assert_ne!(
+            netplay_seed_commitment(0, &secret),
+            netplay_seed_commitment(0, &[8; 32])
+        );
+    }
+
+    #[test]
+    fn combined_seed_ignores_order() {
+        let a = (0, [1; 32]);
+        let b = (1, [2; 32]);
+
+        let seed = combine_netplay_seed_secrets(&[a, b]);
+
+        assert_eq!(seed, combine_netplay_seed_secrets(&[b, a]));
+
+        // duplicated secrets don't cancel out
+        assert_ne!(
+            combine_netplay_seed_secrets(&[(0, [1; 32]), (1, [1; 32])]),
+            combine_netplay_seed_secrets(&[(0, [2; 32]), (1, [2; 32])]),
+        );
+
+        assert_ne!(seed, combine_netplay_seed_secrets(&[a, (1, [3; 32])]));
+    }
+}
@@ -238,6 +238,22 @@ pub enum ServerPacket {
         data: Option<String>,
         remote_players: Vec<RemotePlayerInfo>,
     },
+    InitiateSpectating {
+        package_path: Option<String>,
+        data: Option<String>,
+        players: Vec<RemotePlayerInfo>,
+    },
+    /// Listed on the results screen of the latest battle
+    BattleReward {
+        reward: BattleReward,
+    },
+    /// Adds mail to the inbox, or replaces mail with a matching id
+    Mail {
+        mail: Mail,
+    },
+    RemoveMail {
+        id: String,
+    },
     ActorConnected {
         actor_id: ActorId,
         name: String,
@@ -279,6 +295,13 @@ pub enum ServerPacket {
         actor_id: ActorId,
         emote_id: String,
     },
+    ChatMessage {
+        /// None for messages from the server
+        sender_id: Option<ActorId>,
+        sender_name: String,
+        channel: ChatChannel,
+        message: String,
+    },
     ActorAnimate {
         actor_id: ActorId,
         state: String,
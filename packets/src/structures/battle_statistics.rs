@@ -1,4 +1,4 @@
-use super::Emotion;
+use super::{Emotion, PackageId};
 use serde::{Deserialize, Serialize};
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
@@ -7,25 +7,64 @@ pub struct BattleSurvivor {
     pub health: i32,
 }
 
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum BattleReward {
+    /// A custom line on the results screen
+    Text(String),
+    Item {
+        name: String,
+        count: isize,
+    },
+}
+
+#[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BattlePlayerStatistics {
+    pub index: usize,
+    /// Health at the end of the battle, 0 if deleted
+    pub health: i32,
+    pub damage_dealt: i32,
+    pub counters: usize,
+    pub cards_used: usize,
+    /// Uses of each card package, in order of first use
+    pub card_usage: Vec<(PackageId, usize)>,
+}
+
+impl BattlePlayerStatistics {
+    pub fn track_card_use(&mut self, package_id: &PackageId) {
+        self.cards_used += 1;
+
+        let usage = self.card_usage.iter_mut().find(|(id, _)| id == package_id);
+
+        match usage {
+            Some((_, count)) => *count += 1,
+            None => self.card_usage.push((package_id.clone(), 1)),
+        }
+    }
+}
+
 #[derive(Clone, Default, Debug, Serialize, Deserialize, PartialEq, Eq)]
 pub struct BattleStatistics {
     pub health: i32,
     pub won: bool,
-    pub emotion: Emotion, // todo: track
+    pub emotion: Emotion,
     pub ran: bool,
     pub turns: u32,
     pub score: i32,
     pub ally_survivors: Vec<BattleSurvivor>,
     pub enemy_survivors: Vec<BattleSurvivor>,
     pub neutral_survivors: Vec<BattleSurvivor>,
+    /// Added by the encounter package
+    pub rewards: Vec<BattleReward>,
+    /// Sorted by player index
+    pub players: Vec<BattlePlayerStatistics>,
 
     // used for score calculation
     pub boss_battle: bool,
     pub time: i64,
     pub hits_taken: usize,
     pub movements: usize,
-    pub max_kill_chain: usize, // todo: track
-    pub counters: usize,       // todo: track
+    pub max_kill_chain: usize,
+    pub counters: usize,
 }
 
 impl BattleStatistics {
@@ -33,6 +72,26 @@ impl BattleStatistics {
         Self::default()
     }
 
+    pub fn player_mut(&mut self, index: usize) -> &mut BattlePlayerStatistics {
+        let position = match self
+            .players
+            .binary_search_by_key(&index, |player| player.index)
+        {
+            Ok(position) => position,
+            Err(position) => {
+                let player = BattlePlayerStatistics {
+                    index,
+                    ..Default::default()
+                };
+
+                self.players.insert(position, player);
+                position
+            }
+        };
+
+        &mut self.players[position]
+    }
+
     pub fn calculate_score(&mut self) {
         let mut score = 0;
 
@@ -83,3 +142,27 @@ impl BattleStatistics {
         self.score = score;
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn player_statistics_stay_sorted() {
+        let mut statistics = BattleStatistics::new();
+        let sword = PackageId::from("sword");
+        let cannon = PackageId::from("cannon");
+
+        statistics.player_mut(1).track_card_use(&sword);
+        statistics.player_mut(0).track_card_use(&cannon);
+        statistics.player_mut(1).track_card_use(&cannon);
+        statistics.player_mut(1).track_card_use(&sword);
+
+        let indices: Vec<_> = statistics.players.iter().map(|p| p.index).collect();
+        assert_eq!(indices, [0, 1]);
+
+        let player = &statistics.players[1];
+        assert_eq!(player.cards_used, 3);
+        assert_eq!(player.card_usage, [(sword, 2), (cannon, 1)]);
+    }
+}
@@ -0,0 +1,54 @@
+use super::ActorId;
+use serde::{Deserialize, Serialize};
+
+/// Max length of a chat message in bytes, longer messages are truncated
+pub const MAX_CHAT_MESSAGE_LEN: usize = 256;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChatChannel {
+    /// Visible to every player in the sender's area
+    Area,
+    /// Visible to the sender and the target player
+    Whisper(ActorId),
+}
+
+/// Truncates to `MAX_CHAT_MESSAGE_LEN` without splitting characters, and strips line breaks
+pub fn sanitize_chat_message(message: &mut String) {
+    if message.contains(['\n', '\r']) {
+        *message = message.replace(['\n', '\r'], " ");
+    }
+
+    if message.len() <= MAX_CHAT_MESSAGE_LEN {
+        return;
+    }
+
+    let mut len = MAX_CHAT_MESSAGE_LEN;
+
+    while !message.is_char_boundary(len) {
+        len -= 1;
+    }
+
+    message.truncate(len);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn sanitizes_messages() {
+        let mut message = String::from("hello\nworld");
+        sanitize_chat_message(&mut message);
+        assert_eq!(message, "hello world");
+
+        // multi byte characters are kept whole
+        let mut message = "é".repeat(MAX_CHAT_MESSAGE_LEN);
+        sanitize_chat_message(&mut message);
+        assert_eq!(message.len(), MAX_CHAT_MESSAGE_LEN);
+        assert!(message.chars().all(|c| c == 'é'));
+
+        let mut message = format!("a{}", "é".repeat(MAX_CHAT_MESSAGE_LEN));
+        sanitize_chat_message(&mut message);
+        assert_eq!(message.len(), MAX_CHAT_MESSAGE_LEN - 1);
+    }
+}
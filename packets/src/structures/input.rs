@@ -43,6 +43,7 @@ pub enum Input {
     FaceRight,
     AdvanceFrame,
     RewindFrame,
+    Chat,
 }
 
 impl Input {
@@ -0,0 +1,53 @@
+use super::{PackageId, TextureAnimPathPair};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MailCategory {
+    #[default]
+    Message,
+    Announcement,
+    Important,
+    Mission,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MailAttachment {
+    Item {
+        id: String,
+        /// Resolved when the mail is sent, the item may not be registered on the client
+        name: String,
+        count: usize,
+    },
+    Card {
+        package_id: PackageId,
+        code: String,
+        count: usize,
+    },
+    Money {
+        amount: u32,
+    },
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Mail {
+    pub id: String,
+    pub category: MailCategory,
+    pub sender: String,
+    pub subject: String,
+    pub body: String,
+    pub mug: Option<TextureAnimPathPair<'static>>,
+    pub attachments: Vec<MailAttachment>,
+    pub read: bool,
+    pub claimed: bool,
+}
+
+impl Mail {
+    pub fn has_unclaimed_attachments(&self) -> bool {
+        !self.claimed && !self.attachments.is_empty()
+    }
+
+    pub fn dependencies(&self) -> impl Iterator<Item = &str> {
+        self.mug.iter().flat_map(|mug| mug.dependencies())
+    }
+}
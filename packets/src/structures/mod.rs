@@ -3,6 +3,7 @@ mod asset;
 mod battle_statistics;
 mod bbs_post;
 mod block_color;
+mod chat_channel;
 mod direction;
 mod emotion;
 mod file_hash;
@@ -11,6 +12,7 @@ mod input;
 mod installed_block;
 mod installed_switch_drive;
 mod inventory;
+mod mail;
 mod package_category;
 mod package_id;
 mod remote_player_info;
@@ -24,6 +26,7 @@ pub use asset::*;
 pub use battle_statistics::*;
 pub use bbs_post::*;
 pub use block_color::*;
+pub use chat_channel::*;
 pub use direction::*;
 pub use emotion::*;
 pub use file_hash::*;
@@ -32,6 +35,7 @@ pub use input::*;
 pub use installed_block::*;
 pub use installed_switch_drive::*;
 pub use inventory::*;
+pub use mail::*;
 pub use package_category::*;
 pub use package_id::*;
 pub use remote_player_info::*;
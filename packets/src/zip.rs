@@ -4,9 +4,9 @@ use std::io::{Cursor, Read, Write};
 use std::path::Path;
 use walkdir::WalkDir;
 use zip::read::ZipFile;
-use zip::result::ZipResult;
+use zip::result::{ZipError, ZipResult};
 use zip::write::FileOptions as ZipFileOptions;
-use zip::ZipWriter;
+use zip::{ZipArchive, ZipWriter};
 
 pub fn clean_path(path_str: &str) -> String {
     let path = path_clean::clean(path_str)
@@ -17,16 +17,174 @@ pub fn clean_path(path_str: &str) -> String {
     path.strip_prefix("./").map(String::from).unwrap_or(path)
 }
 
-pub fn extract(bytes: &[u8], mut file_callback: impl FnMut(String, ZipFile)) {
-    let cursor = Cursor::new(bytes);
-    let mut archive = match zip::ZipArchive::new(cursor) {
-        Ok(archive) => archive,
-        Err(err) => {
-            log::error!("Failed to read zip: {}", err);
-            return;
-        }
+/// Limits for extracting zips from untrusted sources, checked before and while decompressing
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ExtractionLimits {
+    /// Max combined uncompressed size of every file, in bytes
+    pub max_total_size: u64,
+    /// Max count of entries, including directories
+    pub max_file_count: usize,
+    /// Max ratio between a file's uncompressed and compressed size
+    pub max_compression_ratio: u64,
+    /// Files smaller than this are not checked against `max_compression_ratio`
+    pub compression_ratio_threshold: u64,
+}
+
+impl ExtractionLimits {
+    /// Limits for packages received from servers, netplay peers, and the package repo
+    pub const PACKAGE: Self = Self {
+        max_total_size: 256 * 1024 * 1024,
+        max_file_count: 8192,
+        max_compression_ratio: 100,
+        compression_ratio_threshold: 1024 * 1024,
     };
 
+    /// For zips created by us or shipped with the game
+    pub const TRUSTED: Self = Self {
+        max_total_size: u64::MAX,
+        max_file_count: usize::MAX,
+        max_compression_ratio: u64::MAX,
+        compression_ratio_threshold: u64::MAX,
+    };
+}
+
+impl Default for ExtractionLimits {
+    fn default() -> Self {
+        Self::PACKAGE
+    }
+}
+
+#[derive(Debug)]
+pub enum ExtractionError {
+    Zip(ZipError),
+    TooManyFiles { limit: usize },
+    TooLarge { limit: u64 },
+    CompressionRatio { path: String },
+    SizeMismatch { path: String },
+}
+
+impl std::fmt::Display for ExtractionError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExtractionError::Zip(err) => write!(f, "{err}"),
+            ExtractionError::TooManyFiles { limit } => {
+                write!(f, "Zip contains more than {limit} files")
+            }
+            ExtractionError::TooLarge { limit } => {
+                write!(f, "Zip expands to more than {limit} bytes")
+            }
+            ExtractionError::CompressionRatio { path } => {
+                write!(f, "{path:?} exceeds the compression ratio limit")
+            }
+            ExtractionError::SizeMismatch { path } => {
+                write!(f, "{path:?} is larger than its declared size")
+            }
+        }
+    }
+}
+
+impl std::error::Error for ExtractionError {}
+
+impl From<ZipError> for ExtractionError {
+    fn from(err: ZipError) -> Self {
+        ExtractionError::Zip(err)
+    }
+}
+
+/// A file within a zip, reading fails if the file expands past its declared size
+pub struct ZipEntry<'a> {
+    file: ZipFile<'a>,
+    remaining: u64,
+    exceeded_size: bool,
+}
+
+impl ZipEntry<'_> {
+    pub fn is_file(&self) -> bool {
+        self.file.is_file()
+    }
+
+    pub fn size(&self) -> u64 {
+        self.file.size()
+    }
+}
+
+impl Read for ZipEntry<'_> {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        // read one byte past the declared size to catch zips lying about their size
+        let len = buf.len().min(self.remaining.saturating_add(1) as usize);
+        let read_len = self.file.read(&mut buf[..len])?;
+
+        if read_len as u64 > self.remaining {
+            self.exceeded_size = true;
+
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                "file is larger than its declared size",
+            ));
+        }
+
+        self.remaining -= read_len as u64;
+
+        Ok(read_len)
+    }
+}
+
+/// Checks the sizes declared by the zip against the limits without decompressing anything
+pub fn check_limits(bytes: &[u8], limits: &ExtractionLimits) -> Result<(), ExtractionError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+
+    check_archive_limits(&mut archive, limits)
+}
+
+fn check_archive_limits(
+    archive: &mut ZipArchive<Cursor<&[u8]>>,
+    limits: &ExtractionLimits,
+) -> Result<(), ExtractionError> {
+    if archive.len() > limits.max_file_count {
+        return Err(ExtractionError::TooManyFiles {
+            limit: limits.max_file_count,
+        });
+    }
+
+    let mut total_size: u64 = 0;
+
+    for i in 0..archive.len() {
+        let file = archive.by_index_raw(i)?;
+        let size = file.size();
+
+        total_size = total_size.saturating_add(size);
+
+        if total_size > limits.max_total_size {
+            return Err(ExtractionError::TooLarge {
+                limit: limits.max_total_size,
+            });
+        }
+
+        let max_size = file
+            .compressed_size()
+            .saturating_mul(limits.max_compression_ratio);
+
+        if size >= limits.compression_ratio_threshold && size > max_size {
+            return Err(ExtractionError::CompressionRatio {
+                path: file.name().to_string(),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Rejects the zip before calling `file_callback` if the declared sizes exceed the limits,
+/// stops extracting if a file expands past its declared size
+pub fn extract(
+    bytes: &[u8],
+    limits: &ExtractionLimits,
+    mut file_callback: impl FnMut(String, &mut ZipEntry),
+) -> Result<(), ExtractionError> {
+    let mut archive = ZipArchive::new(Cursor::new(bytes))?;
+
+    check_archive_limits(&mut archive, limits)?;
+
     for i in 0..archive.len() {
         let file = match archive.by_index(i) {
             Ok(file) => file,
@@ -42,8 +200,20 @@ pub fn extract(bytes: &[u8], mut file_callback: impl FnMut(String, ZipFile)) {
         };
         let path = clean_path(path);
 
-        file_callback(path, file);
+        let mut entry = ZipEntry {
+            remaining: file.size(),
+            file,
+            exceeded_size: false,
+        };
+
+        file_callback(path.clone(), &mut entry);
+
+        if entry.exceeded_size {
+            return Err(ExtractionError::SizeMismatch { path });
+        }
     }
+
+    Ok(())
 }
 
 pub fn compress<S>(path: &S) -> ZipResult<Vec<u8>>
@@ -96,3 +266,76 @@ where
 
     Ok(data)
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn create_zip(files: &[(&str, Vec<u8>)]) -> Vec<u8> {
+        let file_options = ZipFileOptions::default();
+        let mut data = Vec::new();
+
+        let mut zip_writer = ZipWriter::new(Cursor::new(&mut data));
+
+        for (path, bytes) in files {
+            zip_writer.start_file(*path, file_options).unwrap();
+            zip_writer.write_all(bytes).unwrap();
+        }
+
+        zip_writer.finish().unwrap();
+        drop(zip_writer);
+
+        data
+    }
+
+    #[test]
+    fn extraction_limits() {
+        let zip = create_zip(&[("a.txt", b"hello".to_vec()), ("b/c.txt", b"world".to_vec())]);
+
+        let mut paths = Vec::new();
+        let result = extract(&zip, &ExtractionLimits::PACKAGE, |path, entry| {
+            let mut text = String::new();
+            entry.read_to_string(&mut text).unwrap();
+            paths.push((path, text));
+        });
+
+        assert!(result.is_ok());
+        assert_eq!(
+            paths,
+            [
+                (String::from("a.txt"), String::from("hello")),
+                (String::from("b/c.txt"), String::from("world"))
+            ]
+        );
+
+        let limits = ExtractionLimits {
+            max_file_count: 1,
+            ..ExtractionLimits::PACKAGE
+        };
+        assert!(matches!(
+            check_limits(&zip, &limits),
+            Err(ExtractionError::TooManyFiles { .. })
+        ));
+
+        let limits = ExtractionLimits {
+            max_total_size: 9,
+            ..ExtractionLimits::PACKAGE
+        };
+        assert!(matches!(
+            check_limits(&zip, &limits),
+            Err(ExtractionError::TooLarge { .. })
+        ));
+
+        // highly compressible data
+        let zip = create_zip(&[("zeros", vec![0; 2 * 1024 * 1024])]);
+        let mut called = false;
+        let result = extract(&zip, &ExtractionLimits::PACKAGE, |_, _| called = true);
+
+        assert!(matches!(
+            result,
+            Err(ExtractionError::CompressionRatio { .. })
+        ));
+        assert!(!called);
+        assert!(check_limits(&zip, &ExtractionLimits::TRUSTED).is_ok());
+    }
+}
@@ -61,6 +61,30 @@ pub struct Args {
     #[arg(long, value_name = "ASSET_PATH", 
     value_parser = clap::builder::ValueParser::new(optional_asset_path_parser))]
     pub emotes_texture_path: Option<String>,
+
+    /// Reloads areas and scripts when their files change (useful for development)
+    #[arg(long)]
+    pub hot_reload: bool,
+
+    /// Enables persistent player data, stored as files in the specified folder
+    #[arg(long, value_name = "FOLDER_PATH")]
+    pub player_data_path: Option<String>,
+
+    /// Seconds between saves of connected players' data
+    #[arg(long, value_name = "SECONDS", default_value = "300")]
+    pub player_data_save_interval: f32,
+
+    /// Rejects player movement through walls, or faster than an area's "Max Speed" property (tiles per second)
+    #[arg(long)]
+    pub validate_movement: bool,
+
+    /// Ignores packets from connections that haven't negotiated encryption, including messages from other servers
+    #[arg(long)]
+    pub require_encryption: bool,
+
+    /// File storing the key this server proves its identity with, generated if missing. Clients remember it after connecting
+    #[arg(long, value_name = "FILE_PATH", default_value = "identity.key")]
+    pub identity_key_path: String,
 }
 
 fn percentage_parser(value: &str) -> Result<f32, String> {
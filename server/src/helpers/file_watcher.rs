@@ -0,0 +1,161 @@
+use super::normalize_path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_RATE: Duration = Duration::from_secs(1);
+
+/// Polls a folder for created or modified files with a specific extension
+pub struct FileWatcher {
+    root: PathBuf,
+    extension: &'static str,
+    recursive: bool,
+    modified_times: HashMap<PathBuf, SystemTime>,
+    last_poll: Instant,
+}
+
+impl FileWatcher {
+    /// Watches files directly inside of the root folder
+    pub fn new(root: impl Into<PathBuf>, extension: &'static str) -> Self {
+        Self::create(root.into(), extension, false)
+    }
+
+    /// Watches files inside of the root folder and every subfolder
+    pub fn new_recursive(root: impl Into<PathBuf>, extension: &'static str) -> Self {
+        Self::create(root.into(), extension, true)
+    }
+
+    fn create(root: PathBuf, extension: &'static str, recursive: bool) -> Self {
+        let mut watcher = Self {
+            root,
+            extension,
+            recursive,
+            modified_times: HashMap::new(),
+            last_poll: Instant::now(),
+        };
+
+        // initial snapshot, existing files shouldn't be reported as changes
+        watcher.modified_times = watcher.scan();
+
+        watcher
+    }
+
+    /// Returns normalized paths for files that changed since the last poll
+    pub fn poll(&mut self) -> Vec<PathBuf> {
+        if self.last_poll.elapsed() < POLL_RATE {
+            return Vec::new();
+        }
+
+        self.last_poll = Instant::now();
+
+        self.collect_changes()
+    }
+
+    fn collect_changes(&mut self) -> Vec<PathBuf> {
+        let modified_times = self.scan();
+
+        let changed_paths = modified_times
+            .iter()
+            .filter(|(path, time)| self.modified_times.get(*path) != Some(time))
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        self.modified_times = modified_times;
+
+        changed_paths
+    }
+
+    fn scan(&self) -> HashMap<PathBuf, SystemTime> {
+        let mut modified_times = HashMap::new();
+        self.scan_dir(&self.root, &mut modified_times);
+        modified_times
+    }
+
+    fn scan_dir(&self, dir: &Path, modified_times: &mut HashMap<PathBuf, SystemTime>) {
+        let Ok(entries) = std::fs::read_dir(dir) else {
+            return;
+        };
+
+        for entry in entries.flatten() {
+            let path = entry.path();
+
+            let Ok(metadata) = entry.metadata() else {
+                continue;
+            };
+
+            if metadata.is_dir() {
+                if self.recursive {
+                    self.scan_dir(&path, modified_times);
+                }
+
+                continue;
+            }
+
+            if path.extension().and_then(|ext| ext.to_str()) != Some(self.extension) {
+                continue;
+            }
+
+            if let Ok(modified_time) = metadata.modified() {
+                modified_times.insert(normalize_path(&path), modified_time);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("file_watcher_{name}_{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        std::fs::write(dir.join("existing.tmx"), "").unwrap();
+        dir
+    }
+
+    fn add_files(dir: &Path) {
+        std::fs::write(dir.join("added.tmx"), "").unwrap();
+        std::fs::write(dir.join("ignored.txt"), "").unwrap();
+        std::fs::write(dir.join("sub/nested.tmx"), "").unwrap();
+    }
+
+    #[test]
+    fn reports_top_level_files() {
+        let dir = create_test_dir("top_level");
+        let mut watcher = FileWatcher::new(&dir, "tmx");
+
+        assert!(watcher.collect_changes().is_empty());
+
+        add_files(&dir);
+
+        let changes = watcher.collect_changes();
+        assert_eq!(changes, vec![normalize_path(&dir.join("added.tmx"))]);
+        assert!(watcher.collect_changes().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn reports_nested_files_when_recursive() {
+        let dir = create_test_dir("recursive");
+        let mut watcher = FileWatcher::new_recursive(&dir, "tmx");
+
+        assert!(watcher.collect_changes().is_empty());
+
+        add_files(&dir);
+
+        let mut changes = watcher.collect_changes();
+        changes.sort();
+
+        let mut expected = vec![
+            normalize_path(&dir.join("added.tmx")),
+            normalize_path(&dir.join("sub/nested.tmx")),
+        ];
+        expected.sort();
+
+        assert_eq!(changes, expected);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -24,4 +24,7 @@ pub fn normalize_path(path: &std::path::Path) -> std::path::PathBuf {
     normalized_path
 }
 
+mod file_watcher;
 pub mod iterators;
+
+pub use file_watcher::FileWatcher;
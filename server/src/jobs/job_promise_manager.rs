@@ -3,6 +3,7 @@ use std::collections::HashMap;
 
 pub struct JobPromiseManager {
     promises: HashMap<usize, JobPromise>,
+    owners: HashMap<usize, usize>,
     next_id: usize,
 }
 
@@ -10,6 +11,7 @@ impl JobPromiseManager {
     pub fn new() -> JobPromiseManager {
         JobPromiseManager {
             promises: HashMap::new(),
+            owners: HashMap::new(),
             next_id: 0,
         }
     }
@@ -22,10 +24,11 @@ impl JobPromiseManager {
         self.promises.get_mut(&id)
     }
 
-    pub fn add_promise(&mut self, promise: JobPromise) -> usize {
+    pub fn add_promise(&mut self, owner: usize, promise: JobPromise) -> usize {
         let id = self.next_id;
 
         self.promises.insert(id, promise);
+        self.owners.insert(id, owner);
 
         self.next_id += 1;
 
@@ -34,9 +37,16 @@ impl JobPromiseManager {
 
     pub fn remove_promise(&mut self, id: usize) {
         self.promises.remove(&id);
+        self.owners.remove(&id);
 
         if self.promises.is_empty() {
             self.next_id = 0;
         }
     }
+
+    pub fn has_promises_owned_by(&self, owner: usize) -> bool {
+        self.owners
+            .values()
+            .any(|&promise_owner| promise_owner == owner)
+    }
 }
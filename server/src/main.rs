@@ -25,17 +25,63 @@ async fn main() {
 
     let args = args::Args::parse();
 
+    let identity_key = match load_identity_key(&args.identity_key_path) {
+        Ok(identity_key) => identity_key,
+        Err(err) => panic!(
+            "Failed to load identity key {:?}: {err}",
+            args.identity_key_path
+        ),
+    };
+
     let config = net::ServerConfig {
         max_silence_duration: 5.0,
         heartbeat_rate: 0.5,
+        identity_key,
         args,
     };
 
-    let future = net::ServerBuilder::new(config)
-        .with_plugin_interface(Box::new(LuaPluginInterface::new()))
-        .start();
+    let player_data_path = config.args.player_data_path.clone();
+
+    let mut lua_plugin_interface = LuaPluginInterface::new();
+
+    if config.args.hot_reload {
+        lua_plugin_interface.enable_hot_reload();
+    }
+
+    let mut builder =
+        net::ServerBuilder::new(config).with_plugin_interface(Box::new(lua_plugin_interface));
+
+    if let Some(path) = player_data_path {
+        match net::FilePlayerDataStorage::new(&path) {
+            Ok(storage) => builder = builder.with_player_data_storage(Box::new(storage)),
+            Err(err) => panic!("Failed to create player data folder {path:?}: {err}"),
+        }
+    }
+
+    let future = builder.start();
 
     if let Err(err) = future.await {
         panic!("{}", err);
     }
 }
+
+fn load_identity_key(path: &str) -> std::io::Result<packets::IdentityKey> {
+    match std::fs::read(path) {
+        Ok(bytes) => {
+            let bytes = bytes.try_into().map_err(|_| {
+                std::io::Error::new(std::io::ErrorKind::InvalidData, "expected 32 bytes")
+            })?;
+
+            Ok(packets::IdentityKey::from_bytes(bytes))
+        }
+        Err(err) if err.kind() == std::io::ErrorKind::NotFound => {
+            let identity_key = packets::IdentityKey::generate();
+            std::fs::write(path, identity_key.to_bytes())?;
+
+            log::info!("Generated a new identity key at {path:?}");
+
+            Ok(identity_key)
+        }
+        Err(err) => Err(err),
+    }
+}
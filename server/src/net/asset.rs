@@ -1,4 +1,5 @@
 use packets::structures::{ActorId, AssetData, FileHash, PackageCategory};
+use std::borrow::Cow;
 
 #[derive(Clone, Debug)]
 pub struct PackageInfo {
@@ -104,6 +105,25 @@ impl Asset {
         asset
     }
 
+    /// Reads text data, decompressing it if necessary
+    pub fn text(&self) -> Option<Cow<'_, str>> {
+        match &self.data {
+            AssetData::Text(text) => Some(Cow::Borrowed(text)),
+            AssetData::CompressedText(data) => {
+                use flate2::read::ZlibDecoder;
+                use std::io::Read;
+
+                let mut text = String::new();
+                let mut decoder = ZlibDecoder::new(data.as_slice());
+
+                decoder.read_to_string(&mut text).ok()?;
+
+                Some(Cow::Owned(text))
+            }
+            _ => None,
+        }
+    }
+
     pub fn len(&self) -> usize {
         match &self.data {
             AssetData::Text(data) => data.len(),
@@ -218,19 +238,29 @@ impl Asset {
     }
 
     fn load_package_meta(zip_bytes: &[u8]) -> Option<String> {
-        use std::io::Cursor;
+        use packets::zip::ExtractionLimits;
         use std::io::Read;
-        use zip::ZipArchive;
 
-        let data_cursor = Cursor::new(zip_bytes);
+        let mut meta_text = None;
+
+        let result = packets::zip::extract(zip_bytes, &ExtractionLimits::PACKAGE, |path, file| {
+            if path != "package.toml" {
+                return;
+            }
+
+            let mut text = String::new();
 
-        let mut archive = ZipArchive::new(data_cursor).ok()?;
-        let mut meta_file = archive.by_name("package.toml").ok()?;
+            if file.read_to_string(&mut text).is_ok() {
+                meta_text = Some(text);
+            }
+        });
 
-        let mut meta_text = String::new();
-        meta_file.read_to_string(&mut meta_text).ok()?;
+        if let Err(err) = result {
+            log::error!("Failed to read package zip: {err}");
+            return None;
+        }
 
-        Some(meta_text)
+        meta_text
     }
 
     fn resolve_package_info(meta_table: &toml::Table) -> Option<PackageInfo> {
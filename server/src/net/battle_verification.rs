@@ -0,0 +1,333 @@
+use packets::structures::{ActorId, BattleStatistics};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+/// How long to wait for the remaining reports after the first report arrives
+const REPORT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BattleVerdict {
+    /// Every player reported, and the reports match
+    Agreed,
+    /// Reports contradict each other, at least one player is lying or desynced
+    Disagreed,
+    /// A player disconnected or didn't report in time
+    Incomplete,
+}
+
+impl BattleVerdict {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            BattleVerdict::Agreed => "agreed",
+            BattleVerdict::Disagreed => "disagreed",
+            BattleVerdict::Incomplete => "incomplete",
+        }
+    }
+}
+
+/// Compares the reports of every player in a netplay battle, indexed by player index.
+///
+/// Per player statistics are tracked by every peer's simulation, so they should match between reports.
+/// Each player's own health and win must also agree with what the other peers saw.
+pub fn verify_battle_reports(reports: &[Option<BattleStatistics>]) -> BattleVerdict {
+    let Some(reports) = reports
+        .iter()
+        .map(Option::as_ref)
+        .collect::<Option<Vec<_>>>()
+    else {
+        return BattleVerdict::Incomplete;
+    };
+
+    let Some(reference) = reports.first() else {
+        return BattleVerdict::Incomplete;
+    };
+
+    let players_match = reports
+        .iter()
+        .all(|report| report.players == reference.players);
+
+    if !players_match {
+        return BattleVerdict::Disagreed;
+    }
+
+    for (index, report) in reports.iter().enumerate() {
+        let Some(player) = reference
+            .players
+            .iter()
+            .find(|player| player.index == index)
+        else {
+            // every player should be included
+            return BattleVerdict::Disagreed;
+        };
+
+        if report.health != player.health {
+            return BattleVerdict::Disagreed;
+        }
+
+        if report.won && (report.ran || player.health <= 0) {
+            return BattleVerdict::Disagreed;
+        }
+    }
+
+    if reports.len() > 1 && reports.iter().all(|report| report.won) {
+        // someone must have lost
+        return BattleVerdict::Disagreed;
+    }
+
+    BattleVerdict::Agreed
+}
+
+pub(super) struct VerifiedBattleResult {
+    pub battle_id: usize,
+    pub player_id: ActorId,
+    pub statistics: BattleStatistics,
+    pub verdict: BattleVerdict,
+}
+
+struct PendingBattle {
+    players: Vec<ActorId>,
+    reports: Vec<Option<BattleStatistics>>,
+    dropped: Vec<bool>,
+    first_report_time: Option<Instant>,
+}
+
+impl PendingBattle {
+    fn resolvable(&self, now: Instant) -> bool {
+        let timed_out = self
+            .first_report_time
+            .is_some_and(|time| now.duration_since(time) >= REPORT_TIMEOUT);
+
+        let settled = (self.reports.iter())
+            .zip(&self.dropped)
+            .all(|(report, dropped)| report.is_some() || *dropped);
+
+        timed_out || settled
+    }
+}
+
+/// Holds results for netplay battles until every player has reported
+#[derive(Default)]
+pub(super) struct BattleVerifier {
+    next_id: usize,
+    battles: HashMap<usize, PendingBattle>,
+    results: Vec<VerifiedBattleResult>,
+}
+
+impl BattleVerifier {
+    /// `players` should be sorted by player index
+    pub fn track_battle(&mut self, players: Vec<ActorId>) -> usize {
+        let battle_id = self.next_id;
+        self.next_id += 1;
+
+        let battle = PendingBattle {
+            reports: vec![None; players.len()],
+            dropped: vec![false; players.len()],
+            players,
+            first_report_time: None,
+        };
+
+        self.battles.insert(battle_id, battle);
+
+        battle_id
+    }
+
+    /// Returns false if the battle is no longer tracked, such as after timing out
+    pub fn submit_report(
+        &mut self,
+        battle_id: usize,
+        player_index: usize,
+        statistics: BattleStatistics,
+        now: Instant,
+    ) -> bool {
+        let Some(battle) = self.battles.get_mut(&battle_id) else {
+            return false;
+        };
+
+        let Some(report) = battle.reports.get_mut(player_index) else {
+            return false;
+        };
+
+        if report.is_some() {
+            // only the first report counts
+            return true;
+        }
+
+        *report = Some(statistics);
+        battle.first_report_time.get_or_insert(now);
+
+        self.resolve(battle_id, now);
+
+        true
+    }
+
+    /// Stops waiting for reports from a disconnected player
+    pub fn drop_player(&mut self, player_id: ActorId, now: Instant) {
+        let mut affected_battles = Vec::new();
+
+        for (battle_id, battle) in &mut self.battles {
+            for (id, dropped) in battle.players.iter().zip(&mut battle.dropped) {
+                if *id == player_id {
+                    *dropped = true;
+                    affected_battles.push(*battle_id);
+                }
+            }
+        }
+
+        for battle_id in affected_battles {
+            self.resolve(battle_id, now);
+        }
+    }
+
+    pub fn tick(&mut self, now: Instant) {
+        let timed_out_battles: Vec<_> = (self.battles.iter())
+            .filter(|(_, battle)| battle.resolvable(now))
+            .map(|(battle_id, _)| *battle_id)
+            .collect();
+
+        for battle_id in timed_out_battles {
+            self.resolve(battle_id, now);
+        }
+    }
+
+    pub fn take_results(&mut self) -> Vec<VerifiedBattleResult> {
+        std::mem::take(&mut self.results)
+    }
+
+    fn resolve(&mut self, battle_id: usize, now: Instant) {
+        let Some(battle) = self.battles.get(&battle_id) else {
+            return;
+        };
+
+        if !battle.resolvable(now) {
+            return;
+        }
+
+        let battle = self.battles.remove(&battle_id).unwrap();
+        let verdict = verify_battle_reports(&battle.reports);
+
+        let reports = battle.players.into_iter().zip(battle.reports);
+
+        for (player_id, report) in reports {
+            let Some(statistics) = report else {
+                continue;
+            };
+
+            self.results.push(VerifiedBattleResult {
+                battle_id,
+                player_id,
+                statistics,
+                verdict,
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packets::structures::BattlePlayerStatistics;
+
+    fn create_reports(healths: &[i32]) -> Vec<Option<BattleStatistics>> {
+        let players: Vec<_> = healths
+            .iter()
+            .enumerate()
+            .map(|(index, health)| BattlePlayerStatistics {
+                index,
+                health: *health,
+                damage_dealt: 100,
+                ..Default::default()
+            })
+            .collect();
+
+        healths
+            .iter()
+            .map(|health| {
+                Some(BattleStatistics {
+                    health: *health,
+                    won: *health > 0,
+                    players: players.clone(),
+                    ..Default::default()
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn detects_disagreements() {
+        let reports = create_reports(&[50, 0]);
+        assert_eq!(verify_battle_reports(&reports), BattleVerdict::Agreed);
+
+        // claiming a win after being deleted
+        let mut lying_reports = reports.clone();
+        lying_reports[1].as_mut().unwrap().won = true;
+        assert_eq!(
+            verify_battle_reports(&lying_reports),
+            BattleVerdict::Disagreed
+        );
+
+        // reporting more health than the other player saw
+        let mut lying_reports = reports.clone();
+        lying_reports[0].as_mut().unwrap().health = 1000;
+        assert_eq!(
+            verify_battle_reports(&lying_reports),
+            BattleVerdict::Disagreed
+        );
+
+        // altering the shared statistics
+        let mut lying_reports = reports.clone();
+        lying_reports[0].as_mut().unwrap().players[0].damage_dealt = 9999;
+        assert_eq!(
+            verify_battle_reports(&lying_reports),
+            BattleVerdict::Disagreed
+        );
+
+        let mut missing_reports = reports;
+        missing_reports[1] = None;
+        assert_eq!(
+            verify_battle_reports(&missing_reports),
+            BattleVerdict::Incomplete
+        );
+    }
+
+    #[test]
+    fn waits_for_every_report() {
+        let mut ids = slotmap::SlotMap::<ActorId, ()>::with_key();
+        let players = vec![ids.insert(()), ids.insert(())];
+
+        let mut verifier = BattleVerifier::default();
+        let battle_id = verifier.track_battle(players.clone());
+        let mut reports = create_reports(&[50, 0]);
+
+        let now = Instant::now();
+        assert!(verifier.submit_report(battle_id, 0, reports[0].take().unwrap(), now));
+        assert!(verifier.take_results().is_empty());
+
+        assert!(verifier.submit_report(battle_id, 1, reports[1].take().unwrap(), now));
+
+        let results = verifier.take_results();
+        assert_eq!(results.len(), 2);
+        assert!(results
+            .iter()
+            .all(|result| result.verdict == BattleVerdict::Agreed));
+
+        // a disconnected player leaves the battle incomplete
+        let battle_id = verifier.track_battle(players.clone());
+        let reports = create_reports(&[50, 0]);
+
+        verifier.submit_report(battle_id, 0, reports[0].clone().unwrap(), now);
+        verifier.drop_player(players[1], now);
+
+        let results = verifier.take_results();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].player_id, players[0]);
+        assert_eq!(results[0].verdict, BattleVerdict::Incomplete);
+
+        // late reports are rejected after timing out
+        let battle_id = verifier.track_battle(players);
+        verifier.submit_report(battle_id, 0, reports[0].clone().unwrap(), now);
+        verifier.tick(now + REPORT_TIMEOUT);
+
+        assert_eq!(verifier.take_results().len(), 1);
+        assert!(!verifier.submit_report(battle_id, 1, reports[1].clone().unwrap(), now));
+    }
+}
@@ -1,6 +1,7 @@
-use packets::structures::ActorId;
+use packets::structures::{ActorId, RemotePlayerInfo};
 
-use super::{Actor, Direction, PlayerData, WidgetTracker};
+use super::movement_validator::MovementValidator;
+use super::{Actor, BattleVerdict, Direction, PlayerData, WidgetTracker};
 use std::collections::HashSet;
 use std::collections::VecDeque;
 use std::net::SocketAddr;
@@ -10,6 +11,12 @@ pub(super) struct BattleTrackingInfo {
     pub plugin_index: usize,
     pub player_index: usize,
     pub remote_addresses: Vec<SocketAddr>,
+    // used for spectators
+    pub package_path: Option<String>,
+    pub data: Option<String>,
+    pub players: Vec<RemotePlayerInfo>,
+    /// Results are held until every player reports when set
+    pub verification_id: Option<usize>,
 }
 
 pub(super) struct Client {
@@ -31,8 +38,13 @@ pub(super) struct Client {
     pub mugshot_animation_buffer: Vec<u8>,
     pub widget_tracker: WidgetTracker<usize>,
     pub battle_tracker: VecDeque<BattleTrackingInfo>,
+    /// Verdict for the last battle results shared with plugins, None if the battle wasn't verified
+    pub latest_battle_verdict: Option<BattleVerdict>,
     pub player_data: PlayerData,
+    pub player_data_loaded: bool,
     pub input_locks: usize,
+    pub excluded_objects: Vec<u32>,
+    pub movement_validator: MovementValidator,
 }
 
 impl Client {
@@ -94,8 +106,12 @@ impl Client {
             mugshot_animation_buffer: Vec::new(),
             widget_tracker: WidgetTracker::new(),
             battle_tracker: VecDeque::new(),
+            latest_battle_verdict: None,
             player_data: PlayerData::new(identity),
+            player_data_loaded: false,
             input_locks: 0,
+            excluded_objects: Vec::new(),
+            movement_validator: MovementValidator::default(),
         }
     }
 
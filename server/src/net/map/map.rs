@@ -1,17 +1,26 @@
+use super::super::asset_manager::AssetManager;
 use super::super::{Asset, Direction};
 use super::map_layer::MapLayer;
 use super::map_object::{MapObject, MapObjectData, MapObjectSpecification};
+use super::tileset::{TileMeta, Tileset};
 use super::Tile;
 use indexmap::IndexMap;
 use packets::structures::FileHash;
 use std::collections::HashMap;
+use std::sync::Arc;
 use structures::parse_util::parse_or_default;
 use structures::shapes::Projection;
 
+/// Limits how far `set_tile` and `resize` will expand a map, prevents a stray value from allocating huge layers
+const MAX_EXPANDED_SIZE: usize = 1024;
+const MAX_EXPANDED_LAYERS: usize = 64;
+
 #[derive(Clone)]
 pub struct TilesetInfo {
     pub first_gid: u32,
     pub path: String,
+    /// Only available for tilesets provided by the server, see `Map::load_tilesets`
+    pub tileset: Option<Arc<Tileset>>,
 }
 
 #[derive(Clone)]
@@ -49,8 +58,9 @@ pub struct Map {
 }
 
 impl Map {
-    pub fn from(text: &str) -> Map {
-        let mut map = Map {
+    /// Creates an empty map without layers or tilesets
+    pub fn new(width: usize, height: usize, tile_width: u32, tile_height: u32) -> Map {
+        Map {
             name: String::new(),
             background_texture_path: String::new(),
             background_animation_path: String::new(),
@@ -64,10 +74,10 @@ impl Map {
             foreground_parallax: 0.0,
             music_path: String::new(),
             custom_properties: HashMap::new(),
-            width: 0,
-            height: 0,
-            tile_width: 0,
-            tile_height: 0,
+            width,
+            height,
+            tile_width,
+            tile_height,
             projection: Projection::Isometric,
             spawn_x: 0.0,
             spawn_y: 0.0,
@@ -75,13 +85,17 @@ impl Map {
             spawn_direction: Direction::None,
             tilesets: Vec::new(),
             layers: Vec::new(),
-            next_layer_id: 0,
+            next_layer_id: 1,
             objects: Default::default(),
-            next_object_id: 0,
+            next_object_id: 1,
             asset_stale: true,
             cached: false,
             cached_string: String::from(""),
-        };
+        }
+    }
+
+    pub fn from(text: &str) -> Map {
+        let mut map = Map::new(0, 0, 0, 0);
 
         let Ok(map_document) = roxmltree::Document::parse(text) else {
             log::error!("Invalid Tiled map file");
@@ -139,7 +153,11 @@ impl Map {
                             String::from("/server/assets/") + &path[ASSETS_RELATIVE_PATH.len()..];
                     }
 
-                    map.tilesets.push(TilesetInfo { first_gid, path });
+                    map.tilesets.push(TilesetInfo {
+                        first_gid,
+                        path,
+                        tileset: None,
+                    });
                 }
                 "layer" => {
                     let id: u32 = parse_or_default(child.attribute("id"));
@@ -274,6 +292,40 @@ impl Map {
         &self.tilesets
     }
 
+    /// Resolves collision data for tilesets provided by the server
+    pub(in super::super) fn load_tilesets(&mut self, asset_manager: &AssetManager) {
+        for tileset_info in &mut self.tilesets {
+            tileset_info.tileset = asset_manager
+                .get_asset(&tileset_info.path)
+                .and_then(|asset| Tileset::from(&asset.text()?))
+                .map(Arc::new);
+        }
+    }
+
+    /// Replaces any tileset using the same path, call `load_tilesets` to resolve collisions
+    pub fn add_tileset(&mut self, first_gid: u32, path: String) {
+        self.tilesets.retain(|tileset| tileset.path != path);
+
+        let index = self
+            .tilesets
+            .partition_point(|tileset| tileset.first_gid < first_gid);
+
+        self.tilesets.insert(
+            index,
+            TilesetInfo {
+                first_gid,
+                path,
+                tileset: None,
+            },
+        );
+
+        self.mark_dirty();
+    }
+
+    pub(in super::super) fn uses_tileset(&self, path: &str) -> bool {
+        self.tilesets.iter().any(|tileset| tileset.path == path)
+    }
+
     pub fn name(&self) -> &str {
         &self.name
     }
@@ -465,6 +517,79 @@ impl Map {
         self.layers.len()
     }
 
+    /// True if the size is usable for new maps, see `MAX_EXPANDED_SIZE`
+    pub fn is_valid_size(width: usize, height: usize) -> bool {
+        (1..=MAX_EXPANDED_SIZE).contains(&width) && (1..=MAX_EXPANDED_SIZE).contains(&height)
+    }
+
+    /// True if the layer count is usable for new maps, see `MAX_EXPANDED_LAYERS`
+    pub fn is_valid_layer_count(layer_count: usize) -> bool {
+        layer_count <= MAX_EXPANDED_LAYERS
+    }
+
+    /// Keeps tiles at the same coordinates, tiles outside of the new bounds are dropped.
+    /// Returns false if the size is empty or larger than `MAX_EXPANDED_SIZE` and the current size
+    pub fn resize(&mut self, width: usize, height: usize) -> bool {
+        if self.width == width && self.height == height {
+            return true;
+        }
+
+        if width == 0
+            || height == 0
+            || width > MAX_EXPANDED_SIZE.max(self.width)
+            || height > MAX_EXPANDED_SIZE.max(self.height)
+        {
+            return false;
+        }
+
+        self.width = width;
+        self.height = height;
+
+        for layer in &mut self.layers {
+            layer.resize(width, height);
+        }
+
+        self.mark_dirty();
+
+        true
+    }
+
+    /// Adds an empty layer above every other layer, returns the index of the new layer
+    pub fn add_layer(&mut self, name: String) -> usize {
+        let layer = MapLayer::new(
+            self.next_layer_id,
+            name,
+            self.width,
+            self.height,
+            Vec::new(),
+        );
+
+        self.next_layer_id += 1;
+        self.layers.push(layer);
+        self.mark_dirty();
+
+        self.layers.len() - 1
+    }
+
+    /// Removes objects on the layer, objects on higher layers move down to stay with their tiles
+    pub fn remove_layer(&mut self, index: usize) {
+        if index >= self.layers.len() {
+            return;
+        }
+
+        self.layers.remove(index);
+
+        self.objects.retain(|_, object| object.layer != index);
+
+        for object in self.objects.values_mut() {
+            if object.layer > index {
+                object.layer -= 1;
+            }
+        }
+
+        self.mark_dirty();
+    }
+
     pub fn tile_width(&self) -> u32 {
         self.tile_width
     }
@@ -503,11 +628,12 @@ impl Map {
         layer.get_tile(x, y)
     }
 
+    /// Expands the map to fit the tile, up to `MAX_EXPANDED_SIZE` and `MAX_EXPANDED_LAYERS`
     pub fn set_tile(&mut self, x: usize, y: usize, z: usize, tile: Tile) {
-        // todo: expand world instead of rejecting
-        if self.width <= x || self.height <= y || self.layers.len() <= z {
+        if !self.expand_to_fit(x, y, z) {
             return;
         }
+
         let layer: &mut MapLayer = &mut self.layers[z];
 
         if layer.get_tile(x, y) != tile {
@@ -516,6 +642,316 @@ impl Map {
         }
     }
 
+    /// Writes rows of `width` tiles starting at (x, y), expanding the map like `set_tile`
+    pub fn set_tiles(&mut self, x: usize, y: usize, z: usize, width: usize, tiles: &[Tile]) {
+        if width == 0 || tiles.is_empty() {
+            return;
+        }
+
+        let height = tiles.len().div_ceil(width);
+
+        if !self.expand_to_fit(x + width - 1, y + height - 1, z) {
+            return;
+        }
+
+        let layer: &mut MapLayer = &mut self.layers[z];
+        let mut changed = false;
+
+        for (i, tile) in tiles.iter().enumerate() {
+            let (tile_x, tile_y) = (x + i % width, y + i / width);
+
+            if layer.get_tile(tile_x, tile_y) != *tile {
+                layer.set_tile(tile_x, tile_y, tile.clone());
+                changed = true;
+            }
+        }
+
+        if changed {
+            self.mark_dirty();
+        }
+    }
+
+    fn expand_to_fit(&mut self, x: usize, y: usize, z: usize) -> bool {
+        if x >= MAX_EXPANDED_SIZE.max(self.width)
+            || y >= MAX_EXPANDED_SIZE.max(self.height)
+            || z >= MAX_EXPANDED_LAYERS.max(self.layers.len())
+        {
+            return false;
+        }
+
+        self.resize(self.width.max(x + 1), self.height.max(y + 1));
+
+        while self.layers.len() <= z {
+            let name = format!("Layer {}", self.layers.len() + 1);
+            self.add_layer(name);
+        }
+
+        true
+    }
+
+    /// Tile space position, matches the client's `Map::can_move_to`
+    pub fn can_move_to(&self, x: f32, y: f32, z: f32) -> bool {
+        self.can_move_to_excluding(x, y, z, &[])
+    }
+
+    /// Same as `can_move_to`, but ignores objects a player has excluded
+    pub fn can_move_to_excluding(&self, x: f32, y: f32, z: f32, excluded_objects: &[u32]) -> bool {
+        let layer_index = z as i32;
+
+        if layer_index < 0 || layer_index >= self.layers.len() as i32 {
+            return false;
+        }
+
+        let tile = self.tile_at_f32(x, y, layer_index as usize);
+
+        if tile.gid == 0 {
+            return false;
+        }
+
+        // get decimal part
+        let mut test_x = x.fract();
+        let mut test_y = y.fract();
+
+        // get positive coords
+        if test_x < 0.0 {
+            test_x += 1.0;
+        }
+        if test_y < 0.0 {
+            test_y += 1.0;
+        }
+
+        // convert to iso pixels
+        test_x *= (self.tile_width / 2) as f32;
+        test_y *= self.tile_height as f32;
+
+        let layer_relative_z = z.fract() * self.tile_height as f32;
+        let tile_test_pos = (test_x - layer_relative_z, test_y - layer_relative_z);
+
+        if self.tile_intersects(&tile, tile_test_pos) {
+            return false;
+        }
+
+        test_x += x.floor() * (self.tile_width / 2) as f32;
+        test_y += y.floor() * self.tile_height as f32;
+
+        !self.tile_object_intersects((test_x, test_y), layer_index as usize, excluded_objects)
+    }
+
+    /// Tile space position, matches the client's `Map::elevation_at`
+    pub fn elevation_at(&self, x: f32, y: f32, layer_index: i32) -> f32 {
+        let total_layers = self.layers.len() as i32;
+
+        if total_layers == 0 {
+            return 0.0;
+        }
+
+        let layer_index = layer_index.clamp(0, total_layers - 1);
+        let layer_elevation = layer_index as f32;
+
+        let tile = self.tile_at_f32(x, y, layer_index as usize);
+
+        let Some((_, tile_meta)) = self.tile_meta_for_tile(tile.gid) else {
+            return layer_elevation;
+        };
+
+        if !tile_meta.is_stairs() {
+            return layer_elevation;
+        }
+
+        let relative_x = x.fract();
+        let relative_y = y.fract();
+
+        let (flipped_horizontal, flipped_vertical, _) = resolve_tile_flips(&tile);
+        let mut direction = tile_meta.direction();
+
+        if flipped_horizontal {
+            direction = direction.horizontal_mirror();
+        }
+
+        if flipped_vertical {
+            direction = direction.vertical_mirror();
+        }
+
+        let layer_relative_elevation = match direction {
+            Direction::UpLeft => 1.0 - relative_x,
+            Direction::UpRight => 1.0 - relative_y,
+            Direction::DownLeft => relative_y,
+            Direction::DownRight => relative_x,
+            _ => 0.0,
+        };
+
+        layer_elevation + layer_relative_elevation
+    }
+
+    fn tile_at_f32(&self, x: f32, y: f32, z: usize) -> Tile {
+        if x < 0.0 || y < 0.0 {
+            return Tile::default();
+        }
+
+        self.get_tile(x as usize, y as usize, z)
+    }
+
+    fn tileset_for_tile(&self, tile_gid: u32) -> Option<&TilesetInfo> {
+        self.tilesets
+            .iter()
+            .take_while(|tileset| tileset.first_gid <= tile_gid)
+            .last()
+    }
+
+    fn tile_meta_for_tile(&self, tile_gid: u32) -> Option<(&Tileset, &TileMeta)> {
+        let tileset_info = self.tileset_for_tile(tile_gid)?;
+        let tileset = tileset_info.tileset.as_deref()?;
+        let tile_meta = tileset
+            .tile_metas
+            .get((tile_gid - tileset_info.first_gid) as usize)?;
+
+        Some((tileset, tile_meta))
+    }
+
+    /// Point relative to the tile, in world space pixels
+    fn tile_intersects(&self, tile: &Tile, point: (f32, f32)) -> bool {
+        let Some(tileset_info) = self.tileset_for_tile(tile.gid) else {
+            return true;
+        };
+
+        let Some(tileset) = &tileset_info.tileset else {
+            // tileset is provided by the client, we can't resolve collisions
+            return false;
+        };
+
+        let tile_id = tile.gid - tileset_info.first_gid;
+
+        let Some(tile_meta) = tileset.tile_metas.get(tile_id as usize) else {
+            return true;
+        };
+
+        let tile_size = (self.tile_width as f32, self.tile_height as f32);
+        let sprite_height = tileset.tile_height as f32;
+        let (flipped_horizontal, flipped_vertical, rotated) = resolve_tile_flips(tile);
+
+        // convert to orthogonal to simplify transformations
+        let mut test_position = self.projection.world_to_screen(tile_size, point);
+
+        if rotated {
+            let tile_center_y = (self.tile_height / 2) as f32;
+
+            // rotate position counter clockwise
+            test_position = (
+                test_position.1 - tile_center_y,
+                -test_position.0 + tile_center_y,
+            );
+        }
+
+        test_position.0 -= tileset.drawing_offset.0;
+        test_position.1 -= tileset.drawing_offset.1;
+
+        if flipped_horizontal {
+            test_position.0 *= -1.0;
+        }
+
+        if flipped_vertical {
+            test_position.1 = sprite_height - test_position.1;
+        }
+
+        if tileset.orientation == Projection::Orthographic {
+            // tiled uses position on sprite with orthographic projection
+            test_position.0 += (self.tile_width / 2) as f32;
+            test_position.1 += sprite_height - self.tile_height as f32;
+        } else {
+            // isometric orientation
+            test_position = self.projection.screen_to_world(tile_size, test_position);
+        }
+
+        tile_meta
+            .collision_shapes
+            .iter()
+            .any(|shape| shape.intersects(test_position))
+    }
+
+    /// Point in world space pixels
+    fn tile_object_intersects(
+        &self,
+        point: (f32, f32),
+        layer_index: usize,
+        excluded_objects: &[u32],
+    ) -> bool {
+        let tile_size = (self.tile_width as f32, self.tile_height as f32);
+        let world_scale = ((self.tile_width / 2) as f32, self.tile_height as f32);
+
+        for object in self.objects.values() {
+            let MapObjectData::TileObject { tile } = &object.data else {
+                continue;
+            };
+
+            if object.private || object.layer != layer_index || is_warp(&object.class) {
+                continue;
+            }
+
+            if excluded_objects.contains(&object.id) {
+                continue;
+            }
+
+            let Some((tileset, _)) = self.tile_meta_for_tile(tile.gid) else {
+                continue;
+            };
+
+            let sprite_size = (tileset.tile_width as f32, tileset.tile_height as f32);
+
+            if sprite_size.0 == 0.0 || sprite_size.1 == 0.0 {
+                continue;
+            }
+
+            // objects are stored in tile space
+            let world_position = (
+                point.0 - object.x * world_scale.0,
+                point.1 - object.y * world_scale.1,
+            );
+            let size = (object.width * world_scale.0, object.height * world_scale.1);
+
+            let mut screen_position = self.projection.world_to_screen(tile_size, world_position);
+
+            // rotation
+            if object.rotation != 0.0 {
+                let magnitude = screen_position.0.hypot(screen_position.1);
+                let ortho_radians = screen_position.1.atan2(screen_position.0);
+                let rotation_radians = -object.rotation / 180.0 * std::f32::consts::PI;
+                screen_position.0 = (ortho_radians + rotation_radians).cos() * magnitude;
+                screen_position.1 = (ortho_radians + rotation_radians).sin() * magnitude;
+            }
+
+            // scale
+            screen_position.0 /= size.0 / sprite_size.0;
+            screen_position.1 /= size.1 / sprite_size.1;
+
+            // tiled starts (0, 0) at the top left of a tile sitting at the bottom of the sprite
+            let mut screen_offset = (
+                -tileset.alignment_offset.0 - tile_size.0 * 0.5,
+                -tileset.alignment_offset.1 - (sprite_size.1 - tile_size.1),
+            );
+
+            let (flipped_horizontal, flipped_vertical, _) = resolve_tile_flips(tile);
+
+            if flipped_horizontal {
+                screen_offset.0 *= -1.0;
+            }
+
+            if flipped_vertical {
+                screen_offset.1 *= -1.0;
+            }
+
+            screen_position.0 += screen_offset.0;
+            screen_position.1 += screen_offset.1;
+
+            let world_position = self.projection.screen_to_world(tile_size, screen_position);
+
+            if self.tile_intersects(tile, world_position) {
+                return true;
+            }
+        }
+
+        false
+    }
+
     pub fn objects(&self) -> impl Iterator<Item = &MapObject> {
         self.objects.values()
     }
@@ -782,3 +1218,90 @@ impl Map {
         }
     }
 }
+
+/// Matches the client's interpretation of tile flags, (flipped_horizontal, flipped_vertical, rotated)
+fn resolve_tile_flips(tile: &Tile) -> (bool, bool, bool) {
+    if tile.flipped_anti_diagonally {
+        (tile.flipped_vertically, !tile.flipped_horizontally, true)
+    } else {
+        (tile.flipped_horizontally, tile.flipped_vertically, false)
+    }
+}
+
+fn is_warp(class: &str) -> bool {
+    matches!(
+        class.to_lowercase().as_str(),
+        "custom warp" | "server warp" | "position warp" | "custom server warp" | "home warp"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expands_and_shrinks() {
+        let mut map = Map::new(2, 2, 64, 32);
+        map.add_layer(String::from("Floor"));
+
+        map.set_tiles(1, 1, 0, 2, &[Tile::from(1), Tile::from(2), Tile::from(3)]);
+        assert_eq!((map.width(), map.height()), (3, 3));
+        assert!(map.get_tile(2, 1, 0) == Tile::from(2));
+        assert!(map.get_tile(1, 2, 0) == Tile::from(3));
+
+        // layers are created to fit tiles
+        map.set_tile(0, 0, 2, Tile::from(4));
+        assert_eq!(map.layer_count(), 3);
+
+        // unreasonable sizes are rejected
+        map.set_tile(MAX_EXPANDED_SIZE, 0, 0, Tile::from(1));
+        assert_eq!(map.width(), 3);
+
+        map.resize(2, 2);
+        assert!(map.get_tile(1, 1, 0) == Tile::from(1));
+        assert!(map.get_tile(2, 1, 0) == Tile::default());
+
+        map.create_object(MapObjectSpecification {
+            name: String::new(),
+            class: String::new(),
+            visible: true,
+            private: false,
+            x: 0.0,
+            y: 0.0,
+            layer: 2,
+            width: 0.0,
+            height: 0.0,
+            rotation: 0.0,
+            custom_properties: HashMap::new(),
+            data: MapObjectData::Point,
+        });
+        map.remove_layer(1);
+        assert_eq!(map.layer_count(), 2);
+        assert!(map.get_tile(0, 0, 1) == Tile::from(4));
+        assert_eq!(map.objects().next().map(|object| object.layer), Some(1));
+    }
+
+    #[test]
+    fn rejects_invalid_sizes() {
+        let mut map = Map::new(2, 2, 64, 32);
+        map.add_layer(String::from("Floor"));
+        map.set_tile(1, 1, 0, Tile::from(1));
+
+        assert!(!map.resize(0, 2));
+        assert!(!map.resize(2, 0));
+        assert!(!map.resize(MAX_EXPANDED_SIZE + 1, 2));
+        assert!(!map.resize(2, MAX_EXPANDED_SIZE + 1));
+        assert_eq!((map.width(), map.height()), (2, 2));
+        assert!(map.get_tile(1, 1, 0) == Tile::from(1));
+
+        assert!(map.resize(MAX_EXPANDED_SIZE, 1));
+        assert_eq!((map.width(), map.height()), (MAX_EXPANDED_SIZE, 1));
+
+        assert!(Map::is_valid_size(1, MAX_EXPANDED_SIZE));
+        assert!(!Map::is_valid_size(0, 1));
+        assert!(!Map::is_valid_size(1, MAX_EXPANDED_SIZE + 1));
+
+        assert!(Map::is_valid_layer_count(MAX_EXPANDED_LAYERS));
+        assert!(!Map::is_valid_layer_count(MAX_EXPANDED_LAYERS + 1));
+    }
+}
@@ -28,6 +28,27 @@ impl MapLayer {
         }
     }
 
+    /// Keeps tiles at the same coordinates, tiles outside of the new bounds are dropped
+    pub fn resize(&mut self, width: usize, height: usize) {
+        if width == self.width && height == self.height {
+            return;
+        }
+
+        let mut data = vec![0; width * height];
+
+        for (y, row) in data.chunks_mut(width).enumerate().take(self.height) {
+            let copied_width = width.min(self.width);
+            let start = y * self.width;
+
+            row[..copied_width].copy_from_slice(&self.data[start..start + copied_width]);
+        }
+
+        self.data = data;
+        self.width = width;
+        self.height = height;
+        self.cached = false;
+    }
+
     #[allow(dead_code)]
     pub fn is_visible(&self) -> bool {
         self.visible
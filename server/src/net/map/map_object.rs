@@ -199,7 +199,7 @@ impl MapObject {
     }
 }
 
-fn read_points(points_str: &str) -> Vec<(f32, f32)> {
+pub(super) fn read_points(points_str: &str) -> Vec<(f32, f32)> {
     points_str
         .split(' ')
         .flat_map(|point_str| {
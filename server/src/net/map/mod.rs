@@ -3,8 +3,13 @@ mod map;
 mod map_layer;
 mod map_object;
 mod render_helpers;
+#[cfg(test)]
+mod test_helpers;
 mod tile;
+mod tileset;
 
 pub use map::Map;
 pub use map_object::{MapObject, MapObjectData, MapObjectSpecification};
+#[cfg(test)]
+pub use test_helpers::create_test_map;
 pub use tile::Tile;
@@ -0,0 +1,41 @@
+use super::super::asset_manager::AssetManager;
+use super::super::Asset;
+use super::Map;
+
+const TILESET: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<tileset name="test" tilewidth="64" tileheight="32" tilecount="3" columns="3">
+  <grid orientation="isometric" width="64" height="32"/>
+  <tile id="1">
+    <objectgroup>
+      <object id="1" x="0" y="0" width="32" height="32"/>
+    </objectgroup>
+  </tile>
+  <tile id="2" type="Stairs">
+    <properties>
+      <property name="Direction" value="Up Right"/>
+    </properties>
+  </tile>
+</tileset>"#;
+
+/// Creates a single layer map from comma separated tile ids. 1 is floor, 2 is a wall, 3 is stairs rising to the right
+pub fn create_test_map(width: usize, height: usize, tiles: &str) -> Map {
+    let mut asset_manager = AssetManager::new();
+    let tileset_path = std::path::Path::new("test.tsx");
+    let tileset_asset = Asset::load_from_memory(tileset_path, TILESET.as_bytes().to_vec());
+    asset_manager.set_asset(String::from("/server/assets/test.tsx"), tileset_asset);
+
+    let map_xml = format!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<map orientation="isometric" width="{width}" height="{height}" tilewidth="64" tileheight="32">
+  <tileset firstgid="1" source="../assets/test.tsx"/>
+  <layer id="1" name="Floor" width="{width}" height="{height}">
+    <data encoding="csv">{tiles}</data>
+  </layer>
+  <objectgroup id="2"/>
+</map>"#
+    );
+
+    let mut map = Map::from(&map_xml);
+    map.load_tilesets(&asset_manager);
+    map
+}
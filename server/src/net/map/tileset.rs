@@ -0,0 +1,172 @@
+use super::map_object::read_points;
+use packets::structures::Direction;
+use std::collections::HashMap;
+use structures::parse_util::parse_or_default;
+use structures::shapes::{Ellipse, Point, Polygon, Projection, Rect, Shape};
+
+/// The subset of a .tsx file needed to resolve collisions and elevation on the server
+pub struct Tileset {
+    pub tile_width: u32,
+    pub tile_height: u32,
+    pub drawing_offset: (f32, f32),
+    pub alignment_offset: (f32, f32),
+    pub orientation: Projection, // used for collisions
+    pub tile_metas: Vec<TileMeta>,
+}
+
+#[derive(Default)]
+pub struct TileMeta {
+    pub class: String,
+    pub custom_properties: HashMap<String, String>,
+    pub collision_shapes: Vec<Box<dyn Shape>>,
+}
+
+impl TileMeta {
+    fn from(tile_element: roxmltree::Node) -> TileMeta {
+        let class = tile_element
+            .attribute("class")
+            .or_else(|| tile_element.attribute("type"))
+            .unwrap_or_default()
+            .to_string();
+
+        let mut custom_properties = HashMap::new();
+        let mut collision_shapes = Vec::new();
+
+        for child in tile_element.children() {
+            match child.tag_name().name() {
+                "properties" => {
+                    for property in child.children() {
+                        if property.tag_name().name() != "property" {
+                            continue;
+                        }
+
+                        // the client lowercases tile property names
+                        let name = property.attribute("name").unwrap_or_default();
+                        let value = property.attribute("value").unwrap_or_default();
+
+                        custom_properties.insert(name.to_lowercase(), value.to_string());
+                    }
+                }
+                "objectgroup" => {
+                    collision_shapes.extend(child.children().filter_map(shape_from_xml));
+                }
+                _ => {}
+            }
+        }
+
+        TileMeta {
+            class,
+            custom_properties,
+            collision_shapes,
+        }
+    }
+
+    pub fn is_stairs(&self) -> bool {
+        self.class.eq_ignore_ascii_case("stairs")
+    }
+
+    pub fn direction(&self) -> Direction {
+        self.custom_properties
+            .get("direction")
+            .map(|s| Direction::from(s.as_str()))
+            .unwrap_or(Direction::None)
+    }
+}
+
+impl Tileset {
+    pub fn from(text: &str) -> Option<Tileset> {
+        let tileset_document = roxmltree::Document::parse(text).ok()?;
+        let tileset_element = tileset_document.root_element();
+
+        let tile_count: usize = parse_or_default(tileset_element.attribute("tilecount"));
+        let tile_width: u32 = parse_or_default(tileset_element.attribute("tilewidth"));
+        let tile_height: u32 = parse_or_default(tileset_element.attribute("tileheight"));
+
+        let mut drawing_offset = (0.0, 0.0);
+        let mut orientation = Projection::Orthographic;
+
+        let mut tile_metas = Vec::new();
+        tile_metas.resize_with(tile_count, TileMeta::default);
+
+        for child in tileset_element.children() {
+            match child.tag_name().name() {
+                "tileoffset" => {
+                    drawing_offset.0 = parse_or_default(child.attribute("x"));
+                    drawing_offset.1 = parse_or_default(child.attribute("y"));
+                }
+                "grid" if child.attribute("orientation") == Some("isometric") => {
+                    orientation = Projection::Isometric;
+                }
+                "tile" => {
+                    let tile_id: usize = parse_or_default(child.attribute("id"));
+
+                    if let Some(tile_meta) = tile_metas.get_mut(tile_id) {
+                        *tile_meta = TileMeta::from(child);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let tile_width_i = tile_width as i32;
+        let tile_height_i = tile_height as i32;
+
+        let alignment_offset = match tileset_element.attribute("objectalignment") {
+            Some("top") => (-tile_width_i / 2, 0),
+            Some("topleft") => (0, 0),
+            Some("topright") => (-tile_width_i, 0),
+            Some("center") => (-tile_width_i / 2, -tile_height_i / 2),
+            Some("left") => (0, -tile_height_i / 2),
+            Some("right") => (-tile_width_i, -tile_height_i / 2),
+            Some("bottomleft") => (0, -tile_height_i),
+            Some("bottomright") => (-tile_width_i, -tile_height_i),
+            // default to bottom
+            _ => (-tile_width_i / 2, -tile_height_i),
+        };
+
+        Some(Tileset {
+            tile_width,
+            tile_height,
+            drawing_offset,
+            alignment_offset: (alignment_offset.0 as f32, alignment_offset.1 as f32),
+            orientation,
+            tile_metas,
+        })
+    }
+}
+
+fn shape_from_xml(object_element: roxmltree::Node) -> Option<Box<dyn Shape>> {
+    if object_element.tag_name().name() != "object" {
+        return None;
+    }
+
+    let x = parse_or_default::<f32>(object_element.attribute("x"));
+    let y = parse_or_default::<f32>(object_element.attribute("y"));
+    let width = parse_or_default::<f32>(object_element.attribute("width"));
+    let height = parse_or_default::<f32>(object_element.attribute("height"));
+    let rotation = parse_or_default::<f32>(object_element.attribute("rotation"));
+
+    let child_element = object_element
+        .children()
+        .find(|child| !child.tag_name().name().is_empty());
+
+    let child_name = child_element.map(|child| child.tag_name().name());
+
+    if let Some(polygon_element) = child_element.filter(|_| child_name == Some("polygon")) {
+        let points_str = polygon_element.attribute("points").unwrap_or_default();
+
+        let mut polygon = Polygon::new(x, y, rotation);
+
+        for point in read_points(points_str) {
+            polygon.add_point(point);
+        }
+
+        Some(Box::new(polygon))
+    } else if width == 0.0 && height == 0.0 {
+        Some(Box::new(Point::new(x, y)))
+    } else if child_name == Some("ellipse") {
+        Some(Box::new(Ellipse::new(x, y, width, height, rotation)))
+    } else {
+        Some(Box::new(Rect::new(x, y, width, height, rotation)))
+    }
+}
@@ -0,0 +1,389 @@
+use packets::structures::ActorId;
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+#[derive(Clone)]
+pub struct MatchmakingQueueOptions {
+    pub team_size: usize,
+    pub team_count: usize,
+    /// Applied to every matched player before the battle starts
+    pub restrictions_path: Option<String>,
+    pub package_path: Option<String>,
+    pub data: Option<String>,
+    /// The max rating difference between players in a match
+    pub rating_range: f32,
+    /// How much the rating range widens for every second a player waits
+    pub rating_range_growth: f32,
+    pub timeout: Option<Duration>,
+    /// Compare results from every player before sharing them
+    pub verify_results: bool,
+}
+
+impl Default for MatchmakingQueueOptions {
+    fn default() -> Self {
+        Self {
+            team_size: 1,
+            team_count: 2,
+            restrictions_path: None,
+            package_path: None,
+            data: None,
+            rating_range: 100.0,
+            rating_range_growth: 10.0,
+            timeout: None,
+            verify_results: false,
+        }
+    }
+}
+
+impl MatchmakingQueueOptions {
+    pub fn match_size(&self) -> usize {
+        self.team_size.max(1) * self.team_count.max(1)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchmakingDropReason {
+    Cancelled,
+    TimedOut,
+    Disconnected,
+    Battling,
+    QueueRemoved,
+}
+
+impl MatchmakingDropReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MatchmakingDropReason::Cancelled => "cancelled",
+            MatchmakingDropReason::TimedOut => "timeout",
+            MatchmakingDropReason::Disconnected => "disconnect",
+            MatchmakingDropReason::Battling => "battle",
+            MatchmakingDropReason::QueueRemoved => "queue_removed",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub enum MatchmakingEvent {
+    MatchFound {
+        queue_id: String,
+        teams: Vec<Vec<ActorId>>,
+    },
+    PlayerDropped {
+        queue_id: String,
+        player_id: ActorId,
+        reason: MatchmakingDropReason,
+    },
+}
+
+struct QueuedPlayer {
+    id: ActorId,
+    rating: f32,
+    join_time: Instant,
+}
+
+struct MatchmakingQueue {
+    plugin_index: usize,
+    options: MatchmakingQueueOptions,
+    players: Vec<QueuedPlayer>,
+}
+
+pub(super) struct FormedMatch {
+    pub plugin_index: usize,
+    pub options: MatchmakingQueueOptions,
+    pub teams: Vec<Vec<ActorId>>,
+}
+
+#[derive(Default)]
+pub(super) struct Matchmaker {
+    queues: HashMap<String, MatchmakingQueue>,
+    player_queues: HashMap<ActorId, String>,
+    events: Vec<MatchmakingEvent>,
+}
+
+impl Matchmaker {
+    /// Replaces the options of an existing queue, players already in the queue are kept
+    pub fn create_queue(
+        &mut self,
+        queue_id: String,
+        plugin_index: usize,
+        options: MatchmakingQueueOptions,
+    ) {
+        if let Some(queue) = self.queues.get_mut(&queue_id) {
+            queue.plugin_index = plugin_index;
+            queue.options = options;
+            return;
+        }
+
+        let queue = MatchmakingQueue {
+            plugin_index,
+            options,
+            players: Vec::new(),
+        };
+
+        self.queues.insert(queue_id, queue);
+    }
+
+    pub fn remove_queue(&mut self, queue_id: &str) {
+        let Some(queue) = self.queues.remove(queue_id) else {
+            return;
+        };
+
+        for player in queue.players {
+            self.player_queues.remove(&player.id);
+
+            self.events.push(MatchmakingEvent::PlayerDropped {
+                queue_id: queue_id.to_string(),
+                player_id: player.id,
+                reason: MatchmakingDropReason::QueueRemoved,
+            });
+        }
+    }
+
+    pub fn has_queue(&self, queue_id: &str) -> bool {
+        self.queues.contains_key(queue_id)
+    }
+
+    pub fn queue_players(&self, queue_id: &str) -> impl Iterator<Item = ActorId> + '_ {
+        self.queues
+            .get(queue_id)
+            .into_iter()
+            .flat_map(|queue| queue.players.iter().map(|player| player.id))
+    }
+
+    pub fn player_queue(&self, player_id: ActorId) -> Option<&str> {
+        self.player_queues.get(&player_id).map(String::as_str)
+    }
+
+    /// Joining another queue drops the player from their current queue
+    pub fn join(&mut self, queue_id: &str, player_id: ActorId, rating: f32, now: Instant) -> bool {
+        if !self.queues.contains_key(queue_id) {
+            return false;
+        }
+
+        if self.player_queue(player_id) == Some(queue_id) {
+            // just update the rating
+            let queue = self.queues.get_mut(queue_id).unwrap();
+
+            if let Some(player) = queue.players.iter_mut().find(|p| p.id == player_id) {
+                player.rating = rating;
+            }
+
+            return true;
+        }
+
+        self.leave(player_id, MatchmakingDropReason::Cancelled);
+
+        let queue = self.queues.get_mut(queue_id).unwrap();
+        queue.players.push(QueuedPlayer {
+            id: player_id,
+            rating,
+            join_time: now,
+        });
+
+        self.player_queues.insert(player_id, queue_id.to_string());
+
+        true
+    }
+
+    pub fn leave(&mut self, player_id: ActorId, reason: MatchmakingDropReason) -> bool {
+        let Some(queue_id) = self.player_queues.remove(&player_id) else {
+            return false;
+        };
+
+        if let Some(queue) = self.queues.get_mut(&queue_id) {
+            queue.players.retain(|player| player.id != player_id);
+        }
+
+        self.events.push(MatchmakingEvent::PlayerDropped {
+            queue_id,
+            player_id,
+            reason,
+        });
+
+        true
+    }
+
+    /// Drops timed out players and pulls matches out of every queue
+    pub fn tick(&mut self, now: Instant) -> Vec<FormedMatch> {
+        let mut formed_matches = Vec::new();
+
+        for (queue_id, queue) in &mut self.queues {
+            if let Some(timeout) = queue.options.timeout {
+                queue.players.retain(|player| {
+                    if now.duration_since(player.join_time) < timeout {
+                        return true;
+                    }
+
+                    self.player_queues.remove(&player.id);
+
+                    self.events.push(MatchmakingEvent::PlayerDropped {
+                        queue_id: queue_id.clone(),
+                        player_id: player.id,
+                        reason: MatchmakingDropReason::TimedOut,
+                    });
+
+                    false
+                });
+            }
+
+            while let Some(teams) = queue.take_match(now) {
+                for player_id in teams.iter().flatten() {
+                    self.player_queues.remove(player_id);
+                }
+
+                self.events.push(MatchmakingEvent::MatchFound {
+                    queue_id: queue_id.clone(),
+                    teams: teams.clone(),
+                });
+
+                formed_matches.push(FormedMatch {
+                    plugin_index: queue.plugin_index,
+                    options: queue.options.clone(),
+                    teams,
+                });
+            }
+        }
+
+        formed_matches
+    }
+
+    pub fn take_events(&mut self) -> Vec<MatchmakingEvent> {
+        std::mem::take(&mut self.events)
+    }
+}
+
+impl MatchmakingQueue {
+    fn rating_tolerance(&self, player: &QueuedPlayer, now: Instant) -> f32 {
+        let wait_time = now.duration_since(player.join_time).as_secs_f32();
+
+        self.options.rating_range + self.options.rating_range_growth * wait_time
+    }
+
+    fn take_match(&mut self, now: Instant) -> Option<Vec<Vec<ActorId>>> {
+        let match_size = self.options.match_size();
+
+        if self.players.len() < match_size {
+            return None;
+        }
+
+        self.players.sort_by(|a, b| a.rating.total_cmp(&b.rating));
+
+        // prefer the group containing the player who has waited the longest
+        let mut best_window: Option<(usize, Instant)> = None;
+
+        for (start, window) in self.players.windows(match_size).enumerate() {
+            let spread = window[match_size - 1].rating - window[0].rating;
+
+            let fits_tolerance = window
+                .iter()
+                .all(|player| spread <= self.rating_tolerance(player, now));
+
+            if !fits_tolerance {
+                continue;
+            }
+
+            let oldest_join = window.iter().map(|player| player.join_time).min()?;
+
+            let waited_longer = match best_window {
+                Some((_, join_time)) => oldest_join < join_time,
+                None => true,
+            };
+
+            if waited_longer {
+                best_window = Some((start, oldest_join));
+            }
+        }
+
+        let (start, _) = best_window?;
+        let matched: Vec<_> = self.players.drain(start..start + match_size).collect();
+
+        // snake draft from the highest rating for balanced teams
+        let team_count = self.options.team_count.max(1);
+        let mut teams = vec![Vec::new(); team_count];
+
+        for (i, player) in matched.iter().rev().enumerate() {
+            let round = i / team_count;
+            let position = i % team_count;
+
+            let reversed_round = round % 2 == 1;
+
+            let team_index = if reversed_round {
+                team_count - 1 - position
+            } else {
+                position
+            };
+
+            teams[team_index].push(player.id);
+        }
+
+        Some(teams)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use slotmap::SlotMap;
+
+    #[test]
+    fn matches_by_rating() {
+        let mut ids = SlotMap::<ActorId, ()>::with_key();
+        let players: Vec<_> = (0..4).map(|_| ids.insert(())).collect();
+
+        let mut matchmaker = Matchmaker::default();
+        let options = MatchmakingQueueOptions {
+            rating_range: 50.0,
+            rating_range_growth: 0.0,
+            ..Default::default()
+        };
+        matchmaker.create_queue(String::from("ranked"), 0, options);
+
+        let now = Instant::now();
+        matchmaker.join("ranked", players[0], 1000.0, now);
+        matchmaker.join("ranked", players[1], 2000.0, now);
+        matchmaker.join("ranked", players[2], 1020.0, now);
+
+        let formed_matches = matchmaker.tick(now);
+        assert_eq!(formed_matches.len(), 1);
+        assert_eq!(
+            formed_matches[0].teams,
+            vec![vec![players[2]], vec![players[0]]]
+        );
+
+        // the remaining player is still waiting
+        assert_eq!(matchmaker.player_queue(players[1]), Some("ranked"));
+        assert_eq!(matchmaker.player_queue(players[0]), None);
+
+        matchmaker.join("ranked", players[3], 1500.0, now);
+        assert!(matchmaker.tick(now).is_empty());
+    }
+
+    #[test]
+    fn drops_timed_out_players() {
+        let mut ids = SlotMap::<ActorId, ()>::with_key();
+        let player_id = ids.insert(());
+
+        let mut matchmaker = Matchmaker::default();
+        let options = MatchmakingQueueOptions {
+            timeout: Some(Duration::from_secs(5)),
+            ..Default::default()
+        };
+        matchmaker.create_queue(String::from("casual"), 0, options);
+
+        let now = Instant::now();
+        matchmaker.join("casual", player_id, 0.0, now);
+        matchmaker.take_events();
+
+        matchmaker.tick(now + Duration::from_secs(6));
+
+        assert_eq!(
+            matchmaker.take_events(),
+            vec![MatchmakingEvent::PlayerDropped {
+                queue_id: String::from("casual"),
+                player_id,
+                reason: MatchmakingDropReason::TimedOut,
+            }]
+        );
+        assert_eq!(matchmaker.player_queue(player_id), None);
+    }
+}
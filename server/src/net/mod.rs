@@ -5,12 +5,17 @@ mod actor;
 mod area;
 pub mod asset;
 mod asset_manager;
+mod battle_verification;
 mod boot;
 mod client;
 pub mod map;
+mod matchmaking;
+mod movement_validator;
+mod navigation;
 mod packet_orchestrator;
 mod packet_scope;
 mod player_data;
+mod player_data_storage;
 mod plugin_wrapper;
 mod server;
 mod server_builder;
@@ -23,10 +28,14 @@ pub(super) use packet_orchestrator::*;
 pub use actor::Actor;
 pub use area::Area;
 pub use asset::{Asset, AssetId, PackageInfo};
+pub use battle_verification::BattleVerdict;
+pub use matchmaking::{MatchmakingDropReason, MatchmakingEvent, MatchmakingQueueOptions};
+pub use navigation::{NavigationResult, DEFAULT_BOT_SPEED};
 pub use net::Net;
 pub use packet_scope::*;
 pub use packets::structures::*;
 pub use player_data::PlayerData;
+pub use player_data_storage::*;
 pub use server_builder::*;
 pub use server_config::*;
 pub use sprite::*;
@@ -0,0 +1,195 @@
+use super::map::Map;
+use std::time::{Duration, Instant};
+
+/// Tiles per second, fast enough for conveyors and ice at their default speeds
+const DEFAULT_MAX_SPEED: f32 = 8.0;
+/// Area custom property overriding `DEFAULT_MAX_SPEED`
+const MAX_SPEED_PROPERTY: &str = "Max Speed";
+/// Extra distance allowed per move, covers position packets bunching up in transit
+const DISTANCE_LENIENCY: f32 = 0.5;
+/// Caps the time credited to a single move, prevents banking distance while standing still
+const MAX_CREDITED_TIME: f32 = 1.0;
+/// Distance a reported position can be from a server provided position and still match
+const EXPECTED_POSITION_TOLERANCE: f32 = 0.1;
+/// Grace period added to suspensions for the client to report its final position
+const SUSPENSION_GRACE: Duration = Duration::from_secs(1);
+/// Minimum time between teleports sent to correct a player
+const RUBBER_BAND_COOLDOWN: Duration = Duration::from_secs(1);
+
+#[derive(Default)]
+pub(super) struct MovementValidator {
+    last_report_time: Option<Instant>,
+    expected_positions: Vec<(f32, f32, f32)>,
+    suspended_until: Option<Instant>,
+    last_rubber_band_time: Option<Instant>,
+}
+
+impl MovementValidator {
+    /// Resets tracking, used when a player enters an area
+    pub fn reset(&mut self) {
+        *self = Default::default();
+    }
+
+    /// The server moved the player, this position will be accepted from any distance
+    pub fn expect_position(&mut self, x: f32, y: f32, z: f32) {
+        // only the most recent positions are relevant, older teleports were overridden
+        const MAX_EXPECTED_POSITIONS: usize = 8;
+
+        if self.expected_positions.len() >= MAX_EXPECTED_POSITIONS {
+            self.expected_positions.remove(0);
+        }
+
+        self.expected_positions.push((x, y, z));
+    }
+
+    /// Accepts any movement for a duration, used while the server animates the player
+    pub fn suspend(&mut self, duration: Duration) {
+        let suspended_until = Instant::now() + duration + SUSPENSION_GRACE;
+
+        self.suspended_until = self.suspended_until.max(Some(suspended_until));
+    }
+
+    pub fn validate(
+        &mut self,
+        map: &Map,
+        excluded_objects: &[u32],
+        from: (f32, f32, f32),
+        to: (f32, f32, f32),
+    ) -> bool {
+        let now = Instant::now();
+        let elapsed = self
+            .last_report_time
+            .map(|time| (now - time).as_secs_f32())
+            .unwrap_or(MAX_CREDITED_TIME)
+            .min(MAX_CREDITED_TIME);
+
+        self.last_report_time = Some(now);
+
+        if from == to {
+            return true;
+        }
+
+        if self.suspended_until.is_some_and(|time| now < time) {
+            return true;
+        }
+
+        let expected_index = self
+            .expected_positions
+            .iter()
+            .position(|&position| distance(position, to) <= EXPECTED_POSITION_TOLERANCE);
+
+        if let Some(index) = expected_index {
+            // older positions were sent before this one, the client has moved past them
+            self.expected_positions.drain(..=index);
+            return true;
+        }
+
+        let max_speed = map
+            .get_custom_property(MAX_SPEED_PROPERTY)
+            .and_then(|value| value.parse::<f32>().ok())
+            .filter(|speed| *speed > 0.0)
+            .unwrap_or(DEFAULT_MAX_SPEED);
+
+        let max_distance = max_speed * elapsed + DISTANCE_LENIENCY;
+
+        if distance(from, to) > max_distance && !is_position_warp(map, from, to) {
+            return false;
+        }
+
+        map.can_move_to_excluding(to.0, to.1, to.2, excluded_objects)
+    }
+
+    /// Returns true if enough time has passed since the last correction
+    pub fn try_rubber_band(&mut self) -> bool {
+        let now = Instant::now();
+
+        let cooling_down = self
+            .last_rubber_band_time
+            .is_some_and(|time| now - time < RUBBER_BAND_COOLDOWN);
+
+        if cooling_down {
+            return false;
+        }
+
+        self.last_rubber_band_time = Some(now);
+        true
+    }
+}
+
+/// Position warps are resolved by the client, allow jumps from a warp to its destination
+fn is_position_warp(map: &Map, from: (f32, f32, f32), to: (f32, f32, f32)) -> bool {
+    map.objects().any(|object| {
+        if !object.class.eq_ignore_ascii_case("position warp") || object.layer != from.2 as usize {
+            return false;
+        }
+
+        // the client reads object properties case insensitively
+        let property = |name: &str| {
+            object
+                .custom_properties
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case(name))
+                .and_then(|(_, value)| value.parse::<f32>().ok())
+                .unwrap_or_default()
+        };
+
+        let destination = (property("x"), property("y"), property("z"));
+        let warp_radius = object.width.max(object.height) + 1.0;
+
+        distance((object.x, object.y, from.2), from) <= warp_radius
+            && distance(destination, to) <= 1.0
+    })
+}
+
+fn distance(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+    let (x, y, z) = (a.0 - b.0, a.1 - b.1, a.2 - b.2);
+
+    (x * x + y * y + z * z).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::map::create_test_map;
+    use super::*;
+
+    fn create_map() -> Map {
+        create_test_map(3, 1, "1,2,3")
+    }
+
+    #[test]
+    fn resolves_collisions_and_elevation() {
+        let map = create_map();
+
+        assert!(map.can_move_to(0.5, 0.5, 0.0));
+        assert!(!map.can_move_to(1.5, 0.5, 0.0));
+        assert!(map.can_move_to(2.5, 0.5, 0.0));
+
+        // outside of the map or layers
+        assert!(!map.can_move_to(3.5, 0.5, 0.0));
+        assert!(!map.can_move_to(0.5, 0.5, 1.0));
+
+        assert_eq!(map.elevation_at(0.5, 0.5, 0), 0.0);
+        assert_eq!(map.elevation_at(2.5, 0.25, 0), 0.75);
+    }
+
+    #[test]
+    fn rejects_impossible_moves() {
+        let mut map = create_map();
+        map.set_custom_property(MAX_SPEED_PROPERTY, String::from("1"));
+
+        let mut validator = MovementValidator::default();
+
+        assert!(validator.validate(&map, &[], (0.25, 0.5, 0.0), (0.75, 0.5, 0.0)));
+
+        // walls
+        assert!(!validator.validate(&map, &[], (0.75, 0.5, 0.0), (1.25, 0.5, 0.0)));
+
+        // speed limit, moving two tiles shortly after the last move
+        assert!(!validator.validate(&map, &[], (0.5, 0.5, 0.0), (2.5, 0.5, 0.0)));
+
+        // teleports from the server are trusted
+        validator.expect_position(2.5, 0.5, 0.0);
+        assert!(validator.validate(&map, &[], (0.5, 0.5, 0.0), (2.5, 0.5, 0.0)));
+        assert!(!validator.validate(&map, &[], (2.5, 0.5, 0.0), (0.5, 0.5, 0.0)));
+    }
+}
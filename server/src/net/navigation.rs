@@ -0,0 +1,581 @@
+use super::map::Map;
+use super::{Actor, Area, Direction};
+use packets::structures::ActorId;
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::time::Instant;
+
+/// Tiles per second, matches the client's walking speed on 64x32 tiles
+pub const DEFAULT_BOT_SPEED: f32 = 1.875;
+/// Limits the time spent searching for a path on large or disconnected maps
+const MAX_SEARCHED_NODES: usize = 20_000;
+/// The largest change in elevation between neighboring tiles, allows for stairs but not cliffs
+const MAX_ELEVATION_STEP: f32 = 0.51;
+/// Spacing between samples when testing straight lines for obstacles
+const LINE_SAMPLE_DISTANCE: f32 = 0.25;
+/// Caps the time simulated in a single update, avoids skipping through walls after stalls
+const MAX_UPDATE_DURATION: f32 = 0.25;
+/// Obstructed steps in a row before giving up, paths are planned again after each
+const MAX_OBSTRUCTED_STEPS: u8 = 3;
+
+const ORTHOGONAL_COST: u32 = 10;
+const DIAGONAL_COST: u32 = 14;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NavigationResult {
+    Arrived,
+    Blocked,
+}
+
+impl NavigationResult {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            NavigationResult::Arrived => "arrived",
+            NavigationResult::Blocked => "blocked",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+pub struct NavigationEvent {
+    pub bot_id: ActorId,
+    pub result: NavigationResult,
+}
+
+enum Route {
+    Destination((f32, f32, f32)),
+    Patrol {
+        points: Vec<(f32, f32, f32)>,
+        next_index: usize,
+        /// Patrols along open routes turn around at the ends, closed routes loop
+        closed: bool,
+        reversed: bool,
+    },
+}
+
+impl Route {
+    fn target(&self) -> Option<(f32, f32, f32)> {
+        match self {
+            Route::Destination(position) => Some(*position),
+            Route::Patrol {
+                points, next_index, ..
+            } => points.get(*next_index).cloned(),
+        }
+    }
+
+    /// Returns false if the route is complete
+    fn advance(&mut self) -> bool {
+        let Route::Patrol {
+            points,
+            next_index,
+            closed,
+            reversed,
+        } = self
+        else {
+            return false;
+        };
+
+        if points.len() < 2 {
+            return false;
+        }
+
+        if *closed {
+            *next_index = (*next_index + 1) % points.len();
+            return true;
+        }
+
+        if *reversed && *next_index == 0 || !*reversed && *next_index + 1 == points.len() {
+            *reversed = !*reversed;
+        }
+
+        if *reversed {
+            *next_index -= 1;
+        } else {
+            *next_index += 1;
+        }
+
+        true
+    }
+}
+
+struct BotNavigator {
+    route: Route,
+    speed: f32,
+    waypoints: VecDeque<(f32, f32, f32)>,
+    obstructed_steps: u8,
+}
+
+#[derive(Default)]
+pub(super) struct Navigation {
+    navigators: HashMap<ActorId, BotNavigator>,
+    events: Vec<NavigationEvent>,
+    last_update: Option<Instant>,
+}
+
+impl Navigation {
+    pub fn walk_to(&mut self, bot_id: ActorId, destination: (f32, f32, f32), speed: f32) {
+        self.start(bot_id, Route::Destination(destination), speed);
+    }
+
+    /// Closed routes return to the first point after the last, open routes walk back
+    pub fn patrol(
+        &mut self,
+        bot_id: ActorId,
+        points: Vec<(f32, f32, f32)>,
+        closed: bool,
+        speed: f32,
+    ) {
+        let route = Route::Patrol {
+            points,
+            next_index: 0,
+            closed,
+            reversed: false,
+        };
+
+        self.start(bot_id, route, speed);
+    }
+
+    fn start(&mut self, bot_id: ActorId, route: Route, speed: f32) {
+        let navigator = BotNavigator {
+            route,
+            speed: if speed > 0.0 {
+                speed
+            } else {
+                DEFAULT_BOT_SPEED
+            },
+            waypoints: VecDeque::new(),
+            obstructed_steps: 0,
+        };
+
+        self.navigators.insert(bot_id, navigator);
+    }
+
+    pub fn stop(&mut self, bot_id: ActorId) {
+        self.navigators.remove(&bot_id);
+    }
+
+    pub fn is_navigating(&self, bot_id: ActorId) -> bool {
+        self.navigators.contains_key(&bot_id)
+    }
+
+    pub fn take_events(&mut self) -> Vec<NavigationEvent> {
+        std::mem::take(&mut self.events)
+    }
+
+    pub fn tick(
+        &mut self,
+        now: Instant,
+        bots: &mut HashMap<ActorId, Actor>,
+        areas: &HashMap<String, Area>,
+    ) {
+        let elapsed = self
+            .last_update
+            .map(|time| (now - time).as_secs_f32())
+            .unwrap_or_default()
+            .min(MAX_UPDATE_DURATION);
+
+        self.last_update = Some(now);
+
+        let events = &mut self.events;
+
+        self.navigators.retain(|bot_id, navigator| {
+            let Some(bot) = bots.get_mut(bot_id) else {
+                return false;
+            };
+
+            let Some(area) = areas.get(&bot.area_id) else {
+                return false;
+            };
+
+            let result = navigator.update(bot, area.map(), elapsed);
+
+            if let Some(result) = result {
+                events.push(NavigationEvent {
+                    bot_id: *bot_id,
+                    result,
+                });
+            }
+
+            result.is_none()
+        });
+    }
+}
+
+impl BotNavigator {
+    fn update(&mut self, bot: &mut Actor, map: &Map, elapsed: f32) -> Option<NavigationResult> {
+        let mut distance_budget = self.speed * elapsed;
+        let mut position = (bot.x, bot.y, bot.z);
+        let mut direction = Direction::None;
+        let mut advanced = false;
+
+        let result = loop {
+            if self.obstructed_steps > MAX_OBSTRUCTED_STEPS {
+                break Some(NavigationResult::Blocked);
+            }
+
+            let Some(&waypoint) = self.waypoints.front() else {
+                let Some(target) = self.route.target() else {
+                    break Some(NavigationResult::Arrived);
+                };
+
+                if position == target {
+                    if advanced {
+                        // one patrol point per update, avoids spinning on routes with repeated points
+                        break None;
+                    }
+
+                    if self.route.advance() {
+                        advanced = true;
+                        continue;
+                    }
+
+                    break Some(NavigationResult::Arrived);
+                }
+
+                let Some(path) = find_path(map, position, target) else {
+                    break Some(NavigationResult::Blocked);
+                };
+
+                self.waypoints = path.into();
+                continue;
+            };
+
+            if distance_budget <= 0.0 {
+                break None;
+            }
+
+            let remaining = distance(position, waypoint);
+
+            if remaining > 0.0 {
+                direction =
+                    Direction::from_offset((waypoint.0 - position.0, waypoint.1 - position.1));
+            }
+
+            if remaining <= distance_budget {
+                if !map.can_move_to(waypoint.0, waypoint.1, waypoint.2) {
+                    // the map changed, plan again next update
+                    self.waypoints.clear();
+                    self.obstructed_steps += 1;
+                    break None;
+                }
+
+                distance_budget -= remaining;
+                position = waypoint;
+                self.waypoints.pop_front();
+                self.obstructed_steps = 0;
+                continue;
+            }
+
+            let progress = distance_budget / remaining;
+            let next_position = (
+                position.0 + (waypoint.0 - position.0) * progress,
+                position.1 + (waypoint.1 - position.1) * progress,
+                position.2 + (waypoint.2 - position.2) * progress,
+            );
+
+            if !map.can_move_to(next_position.0, next_position.1, next_position.2) {
+                self.waypoints.clear();
+                self.obstructed_steps += 1;
+                break None;
+            }
+
+            position = next_position;
+            break None;
+        };
+
+        if !direction.is_none() {
+            bot.set_direction(direction);
+        }
+
+        // applied even when the route completes, the final step may have reached the destination
+        bot.set_position(position.0, position.1, position.2);
+
+        result
+    }
+}
+
+type Node = (i32, i32, i32);
+
+/// Tile space positions, returns waypoints leading to the goal, excluding the start
+pub fn find_path(
+    map: &Map,
+    start: (f32, f32, f32),
+    goal: (f32, f32, f32),
+) -> Option<Vec<(f32, f32, f32)>> {
+    let start_node = (
+        start.0.floor() as i32,
+        start.1.floor() as i32,
+        start.2.floor() as i32,
+    );
+    let goal_node = (
+        goal.0.floor() as i32,
+        goal.1.floor() as i32,
+        goal.2.floor() as i32,
+    );
+
+    if !map.can_move_to(goal.0, goal.1, goal.2) {
+        return None;
+    }
+
+    if start_node == goal_node {
+        return Some(vec![goal]);
+    }
+
+    let heuristic = |node: Node| {
+        let dx = (node.0 - goal_node.0).unsigned_abs();
+        let dy = (node.1 - goal_node.1).unsigned_abs();
+        let dz = (node.2 - goal_node.2).unsigned_abs();
+
+        DIAGONAL_COST * dx.min(dy) + ORTHOGONAL_COST * (dx.max(dy) - dx.min(dy) + dz)
+    };
+
+    let mut open = BinaryHeap::new();
+    let mut costs = HashMap::<Node, u32>::new();
+    let mut previous = HashMap::<Node, Node>::new();
+
+    let start_elevation = start.2;
+    let mut elevations = HashMap::<Node, f32>::new();
+    elevations.insert(start_node, start_elevation);
+    costs.insert(start_node, 0);
+    open.push(Reverse((heuristic(start_node), start_node)));
+
+    let mut searched_nodes = 0;
+
+    while let Some(Reverse((_, node))) = open.pop() {
+        if node == goal_node {
+            break;
+        }
+
+        searched_nodes += 1;
+
+        if searched_nodes > MAX_SEARCHED_NODES {
+            return None;
+        }
+
+        let node_cost = costs[&node];
+        let node_elevation = elevations[&node];
+
+        for offset in NEIGHBOR_OFFSETS {
+            let Some((neighbor, elevation)) =
+                resolve_neighbor(map, node, node_elevation, offset.0, offset.1)
+            else {
+                continue;
+            };
+
+            let diagonal = offset.0 != 0 && offset.1 != 0;
+
+            // avoid cutting corners
+            if diagonal
+                && (resolve_neighbor(map, node, node_elevation, offset.0, 0).is_none()
+                    || resolve_neighbor(map, node, node_elevation, 0, offset.1).is_none())
+            {
+                continue;
+            }
+
+            let step_cost = if diagonal {
+                DIAGONAL_COST
+            } else {
+                ORTHOGONAL_COST
+            };
+
+            let cost = node_cost + step_cost;
+
+            if costs.get(&neighbor).is_some_and(|c| *c <= cost) {
+                continue;
+            }
+
+            costs.insert(neighbor, cost);
+            elevations.insert(neighbor, elevation);
+            previous.insert(neighbor, node);
+            open.push(Reverse((cost + heuristic(neighbor), neighbor)));
+        }
+    }
+
+    if !previous.contains_key(&goal_node) {
+        return None;
+    }
+
+    let mut path = vec![goal];
+    let mut node = previous[&goal_node];
+
+    while node != start_node {
+        path.push((node.0 as f32 + 0.5, node.1 as f32 + 0.5, elevations[&node]));
+        node = previous[&node];
+    }
+
+    path.reverse();
+
+    Some(smooth_path(map, start, path))
+}
+
+const NEIGHBOR_OFFSETS: [(i32, i32); 8] = [
+    (1, 0),
+    (-1, 0),
+    (0, 1),
+    (0, -1),
+    (1, 1),
+    (1, -1),
+    (-1, 1),
+    (-1, -1),
+];
+
+/// Finds the layer a step lands on, preferring the current layer, stairs lead to other layers
+fn resolve_neighbor(
+    map: &Map,
+    node: Node,
+    node_elevation: f32,
+    offset_x: i32,
+    offset_y: i32,
+) -> Option<(Node, f32)> {
+    let x = node.0 + offset_x;
+    let y = node.1 + offset_y;
+
+    if x < 0 || y < 0 {
+        return None;
+    }
+
+    let center_x = x as f32 + 0.5;
+    let center_y = y as f32 + 0.5;
+
+    [node.2, node.2 + 1, node.2 - 1]
+        .into_iter()
+        .filter(|layer| *layer >= 0 && (*layer as usize) < map.layer_count())
+        .find_map(|layer| {
+            let elevation = map.elevation_at(center_x, center_y, layer);
+
+            if elevation.floor() as i32 != layer
+                || (elevation - node_elevation).abs() > MAX_ELEVATION_STEP
+                || !map.can_move_to(center_x, center_y, elevation)
+            {
+                return None;
+            }
+
+            Some(((x, y, layer), elevation))
+        })
+}
+
+/// Removes waypoints that can be skipped by walking in a straight line on flat ground
+fn smooth_path(
+    map: &Map,
+    start: (f32, f32, f32),
+    path: Vec<(f32, f32, f32)>,
+) -> Vec<(f32, f32, f32)> {
+    let mut smoothed: Vec<(f32, f32, f32)> = Vec::with_capacity(path.len());
+    let mut anchor = start;
+
+    for (i, waypoint) in path.iter().enumerate() {
+        let Some(next) = path.get(i + 1) else {
+            smoothed.push(*waypoint);
+            break;
+        };
+
+        let flat = anchor.2 == waypoint.2 && waypoint.2 == next.2;
+
+        if flat && is_line_walkable(map, anchor, *next) {
+            continue;
+        }
+
+        smoothed.push(*waypoint);
+        anchor = *waypoint;
+    }
+
+    smoothed
+}
+
+fn is_line_walkable(map: &Map, a: (f32, f32, f32), b: (f32, f32, f32)) -> bool {
+    let length = distance(a, b);
+    let samples = (length / LINE_SAMPLE_DISTANCE).ceil() as usize;
+
+    (1..=samples).all(|i| {
+        let progress = i as f32 / samples as f32;
+
+        map.can_move_to(
+            a.0 + (b.0 - a.0) * progress,
+            a.1 + (b.1 - a.1) * progress,
+            a.2 + (b.2 - a.2) * progress,
+        )
+    })
+}
+
+fn distance(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+    let (x, y, z) = (a.0 - b.0, a.1 - b.1, a.2 - b.2);
+
+    (x * x + y * y + z * z).sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::map::create_test_map;
+    use super::*;
+
+    #[test]
+    fn paths_around_walls() {
+        let map = create_test_map(3, 3, "1,2,1,1,2,1,1,1,1");
+
+        let start = (0.5, 0.5, 0.0);
+        let goal = (2.5, 0.5, 0.0);
+        let path = find_path(&map, start, goal).unwrap();
+
+        assert_eq!(path.last(), Some(&goal));
+
+        // the only opening in the wall is on the last row
+        assert!(path.iter().any(|&(_, y, _)| y >= 2.0));
+
+        let mut previous = start;
+
+        for &waypoint in &path {
+            assert!(is_line_walkable(&map, previous, waypoint));
+            previous = waypoint;
+        }
+
+        // destinations inside of walls are unreachable
+        assert_eq!(find_path(&map, start, (1.5, 0.5, 0.0)), None);
+    }
+
+    #[test]
+    fn walks_to_the_destination() {
+        let map = create_test_map(3, 1, "1,1,1");
+
+        let mut bot = Actor {
+            id: ActorId::new(0, 0),
+            name: String::new(),
+            area_id: String::new(),
+            texture_path: String::new(),
+            animation_path: String::new(),
+            mugshot_texture_path: String::new(),
+            mugshot_animation_path: String::new(),
+            direction: Direction::Down,
+            x: 0.5,
+            y: 0.5,
+            z: 0.0,
+            last_movement_time: Instant::now(),
+            scale_x: 1.0,
+            scale_y: 1.0,
+            rotation: 0.0,
+            map_color: (0, 0, 0, 0),
+            current_animation: None,
+            solid: false,
+            child_sprites: Vec::new(),
+        };
+
+        let mut navigator = BotNavigator {
+            route: Route::Destination((2.5, 0.5, 0.0)),
+            speed: DEFAULT_BOT_SPEED,
+            waypoints: VecDeque::new(),
+            obstructed_steps: 0,
+        };
+
+        let mut result = None;
+
+        for _ in 0..20 {
+            result = navigator.update(&mut bot, &map, MAX_UPDATE_DURATION);
+
+            if result.is_some() {
+                break;
+            }
+        }
+
+        assert_eq!(result, Some(NavigationResult::Arrived));
+        assert_eq!((bot.x, bot.y, bot.z), (2.5, 0.5, 0.0));
+    }
+}
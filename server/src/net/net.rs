@@ -1,7 +1,10 @@
 use super::asset_manager::AssetManager;
+use super::battle_verification::{BattleVerifier, VerifiedBattleResult};
 use super::boot::Boot;
 use super::client::{BattleTrackingInfo, Client};
 use super::map::Map;
+use super::matchmaking::Matchmaker;
+use super::navigation::{Navigation, NavigationEvent};
 use super::*;
 use crate::jobs::JobPromise;
 use crate::threads::ThreadMessage;
@@ -27,12 +30,17 @@ pub struct Net {
     active_plugin: usize,
     kick_list: Vec<Boot>,
     item_registry: HashMap<String, ItemDefinition>,
+    player_data_storage: Option<Box<dyn PlayerDataStorage>>,
+    matchmaker: Matchmaker,
+    battle_verifier: BattleVerifier,
+    navigation: Navigation,
 }
 
 impl Net {
     pub fn new(
         packet_orchestrator: Rc<RefCell<PacketOrchestrator>>,
         config: Rc<ServerConfig>,
+        player_data_storage: Option<Box<dyn PlayerDataStorage>>,
         message_sender: Sender<ThreadMessage>,
     ) -> Net {
         use super::asset::get_map_path;
@@ -58,6 +66,7 @@ impl Net {
 
             if let Ok(raw_map) = read_to_string(&map_path) {
                 let mut map = Map::from(&raw_map);
+                map.load_tilesets(&asset_manager);
 
                 if area_id == "default" {
                     default_area_provided = true
@@ -89,6 +98,10 @@ impl Net {
             active_plugin: 0,
             kick_list: Vec::new(),
             item_registry: HashMap::new(),
+            player_data_storage,
+            matchmaker: Matchmaker::default(),
+            battle_verifier: BattleVerifier::default(),
+            navigation: Navigation::default(),
         }
     }
 
@@ -107,6 +120,14 @@ impl Net {
     pub fn set_asset(&mut self, path: String, asset: Asset) {
         self.asset_manager.set_asset(path.clone(), asset);
 
+        if path.ends_with(".tsx") {
+            for area in self.areas.values_mut() {
+                if area.map().uses_tileset(&path) {
+                    area.map_mut().load_tilesets(&self.asset_manager);
+                }
+            }
+        }
+
         update_cached_clients(
             &mut self.packet_orchestrator.borrow_mut(),
             self.config.args.max_payload_size,
@@ -130,6 +151,7 @@ impl Net {
 
     pub fn add_area(&mut self, id: String, map: Map) {
         let mut map = map;
+        map.load_tilesets(&self.asset_manager);
 
         if let Some(area) = self.areas.get_mut(&id) {
             area.set_map(map);
@@ -142,6 +164,17 @@ impl Net {
         }
     }
 
+    /// See `Map::add_tileset`, also resolves collisions for tilesets provided by the server
+    pub fn add_area_tileset(&mut self, area_id: &str, first_gid: u32, path: String) {
+        let Some(area) = self.areas.get_mut(area_id) else {
+            return;
+        };
+
+        let map = area.map_mut();
+        map.add_tileset(first_gid, path);
+        map.load_tilesets(&self.asset_manager);
+    }
+
     pub fn remove_area(&mut self, id: &str) {
         use super::asset::get_map_path;
 
@@ -174,6 +207,12 @@ impl Net {
         self.clients.get(&id).map(|client| client.socket_address)
     }
 
+    pub fn get_player_connection_stats(&self, id: ActorId) -> Option<packets::ConnectionStats> {
+        self.packet_orchestrator
+            .borrow()
+            .get_connection_stats_by_id(id)
+    }
+
     #[allow(dead_code)]
     pub(super) fn get_client(&self, id: ActorId) -> Option<&Client> {
         self.clients.get(&id)
@@ -329,6 +368,101 @@ impl Net {
             .send_by_id(target_id, Reliability::Reliable, packet);
     }
 
+    /// Sends a chat message from one player using the default behavior for the channel
+    pub fn relay_player_chat(&mut self, sender_id: ActorId, channel: ChatChannel, message: String) {
+        let Some(client) = self.clients.get(&sender_id) else {
+            return;
+        };
+
+        let sender_name = client.actor.name.clone();
+
+        match channel {
+            ChatChannel::Area => {
+                let area_id = client.actor.area_id.clone();
+                self.broadcast_chat_message(&area_id, Some(sender_id), sender_name, message);
+            }
+            ChatChannel::Whisper(target_id) => {
+                if !self.clients.contains_key(&target_id) {
+                    // bots and disconnected players can't receive whispers
+                    self.send_chat_message(
+                        sender_id,
+                        None,
+                        String::new(),
+                        String::from("No such player"),
+                    );
+                    return;
+                }
+
+                let packet = ServerPacket::ChatMessage {
+                    sender_id: Some(sender_id),
+                    sender_name,
+                    channel,
+                    message,
+                };
+
+                let mut packet_orchestrator = self.packet_orchestrator.borrow_mut();
+
+                // echo back to the sender to confirm the whisper was delivered
+                if target_id != sender_id {
+                    packet_orchestrator.send_by_id(
+                        sender_id,
+                        Reliability::ReliableOrdered,
+                        packet.clone(),
+                    );
+                }
+
+                packet_orchestrator.send_by_id(target_id, Reliability::ReliableOrdered, packet);
+            }
+        }
+    }
+
+    pub fn send_chat_message(
+        &mut self,
+        player_id: ActorId,
+        sender_id: Option<ActorId>,
+        sender_name: String,
+        message: String,
+    ) {
+        let packet = ServerPacket::ChatMessage {
+            sender_id,
+            sender_name,
+            channel: ChatChannel::Whisper(player_id),
+            message,
+        };
+
+        self.packet_orchestrator.borrow_mut().send_by_id(
+            player_id,
+            Reliability::ReliableOrdered,
+            packet,
+        );
+    }
+
+    pub fn broadcast_chat_message(
+        &mut self,
+        area_id: &str,
+        sender_id: Option<ActorId>,
+        sender_name: String,
+        message: String,
+    ) {
+        let Some(area) = self.areas.get(area_id) else {
+            return;
+        };
+
+        let packet = ServerPacket::ChatMessage {
+            sender_id,
+            sender_name,
+            channel: ChatChannel::Area,
+            message,
+        };
+
+        broadcast_to_area(
+            &mut self.packet_orchestrator.borrow_mut(),
+            area,
+            Reliability::ReliableOrdered,
+            packet,
+        );
+    }
+
     pub fn set_player_map_color(&mut self, id: ActorId, color: (u8, u8, u8, u8)) {
         let Some(client) = self.clients.get_mut(&id) else {
             return;
@@ -387,11 +521,15 @@ impl Net {
         };
 
         let mut asset_paths = HashSet::<&str>::new();
+        let mut moves_player = false;
 
         // store final values for new players, also track assets
         for keyframe in &animation {
             for (property, _) in &keyframe.property_steps {
                 match property {
+                    ActorProperty::X(_) | ActorProperty::Y(_) | ActorProperty::Z(_) => {
+                        moves_player = true;
+                    }
                     ActorProperty::Animation(value) => {
                         client.actor.current_animation = Some(value.clone())
                     }
@@ -408,6 +546,14 @@ impl Net {
             }
         }
 
+        if moves_player {
+            let duration: f32 = animation.iter().map(|keyframe| keyframe.duration).sum();
+
+            client
+                .movement_validator
+                .suspend(std::time::Duration::from_secs_f32(duration.max(0.0)));
+        }
+
         ensure_assets(
             &mut self.packet_orchestrator.borrow_mut(),
             self.config.args.max_payload_size,
@@ -485,6 +631,12 @@ impl Net {
     }
 
     pub fn exclude_object_for_player(&mut self, id: ActorId, object_id: u32) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            if !client.excluded_objects.contains(&object_id) {
+                client.excluded_objects.push(object_id);
+            }
+        }
+
         self.packet_orchestrator.borrow_mut().send_by_id(
             id,
             Reliability::ReliableOrdered,
@@ -493,6 +645,12 @@ impl Net {
     }
 
     pub fn include_object_for_player(&mut self, id: ActorId, object_id: u32) {
+        if let Some(client) = self.clients.get_mut(&id) {
+            client
+                .excluded_objects
+                .retain(|excluded_id| *excluded_id != object_id);
+        }
+
         self.packet_orchestrator.borrow_mut().send_by_id(
             id,
             Reliability::ReliableOrdered,
@@ -642,10 +800,48 @@ impl Net {
             client.warp_z = z;
             client.warp_direction = direction;
 
+            client.movement_validator.expect_position(x, y, z);
+
             // don't update internal position, allow the client to update this
         }
     }
 
+    /// Returns false and moves the player back to their last accepted position if the move is impossible
+    pub(super) fn validate_player_movement(&mut self, id: ActorId, x: f32, y: f32, z: f32) -> bool {
+        let Some(client) = self.clients.get_mut(&id) else {
+            return false;
+        };
+
+        let Some(area) = self.areas.get(&client.actor.area_id) else {
+            // area deleted, should be getting kicked
+            return false;
+        };
+
+        let actor = &client.actor;
+        let from = (actor.x, actor.y, actor.z);
+
+        let valid = client.movement_validator.validate(
+            area.map(),
+            &client.excluded_objects,
+            from,
+            (x, y, z),
+        );
+
+        if valid {
+            return true;
+        }
+
+        if client.movement_validator.try_rubber_band() {
+            log::debug!("Rejected movement from {id:?} to ({x}, {y}, {z}), moving back");
+
+            let direction = client.actor.direction;
+            let (x, y, z) = from;
+            self.teleport_player(id, false, x, y, z, direction);
+        }
+
+        false
+    }
+
     pub(crate) fn update_player_position(
         &mut self,
         id: ActorId,
@@ -1011,12 +1207,32 @@ impl Net {
         false
     }
 
+    /// Results from every player are compared before they're shared with plugins when `verify_results` is set
     pub fn initiate_netplay(
         &mut self,
         ids: &[ActorId],
         package_path: Option<String>,
         data: Option<String>,
+        verify_results: bool,
     ) {
+        for id in ids {
+            self.matchmaker.leave(*id, MatchmakingDropReason::Battling);
+        }
+
+        let verification_id =
+            verify_results.then(|| self.battle_verifier.track_battle(ids.to_vec()));
+
+        if verification_id.is_some() {
+            // missing players will never report
+            let now = std::time::Instant::now();
+
+            for id in ids {
+                if !self.clients.contains_key(id) {
+                    self.battle_verifier.drop_player(*id, now);
+                }
+            }
+        }
+
         if let Some(package_path) = package_path.as_ref() {
             self.preload_package(ids, package_path);
         }
@@ -1051,6 +1267,10 @@ impl Net {
                     plugin_index: self.active_plugin,
                     player_index,
                     remote_addresses,
+                    package_path: package_path.clone(),
+                    data: data.clone(),
+                    players: remote_players.clone(),
+                    verification_id,
                 };
 
                 client.battle_tracker.push_back(tracking_info);
@@ -1074,6 +1294,170 @@ impl Net {
         }
     }
 
+    pub fn add_netplay_spectator(&mut self, spectator_id: ActorId, player_id: ActorId) {
+        if spectator_id == player_id {
+            return;
+        }
+
+        let info = (self.clients.get(&player_id))
+            .and_then(|client| client.battle_tracker.front())
+            .filter(|info| !info.players.is_empty());
+
+        let Some(info) = info else {
+            log::warn!("Can't spectate {player_id:?}, player is not in a netplay battle");
+            return;
+        };
+
+        let package_path = info.package_path.clone();
+        let data = info.data.clone();
+        let players = info.players.clone();
+
+        if let Some(package_path) = package_path.as_ref() {
+            self.preload_package(&[spectator_id], package_path);
+        }
+
+        let Some(spectator) = self.clients.get(&spectator_id) else {
+            return;
+        };
+
+        let spectator_address = spectator.socket_address;
+
+        if players.iter().any(|info| info.address == spectator_address) {
+            // already participating
+            return;
+        }
+
+        let mut orchestrator = self.packet_orchestrator.borrow_mut();
+
+        orchestrator.add_netplay_spectator(
+            spectator_address,
+            players.len(),
+            players.iter().map(|info| info.address).collect(),
+        );
+
+        orchestrator.send(
+            spectator_address,
+            Reliability::ReliableOrdered,
+            ServerPacket::InitiateSpectating {
+                package_path,
+                data,
+                players,
+            },
+        );
+    }
+
+    pub fn remove_netplay_spectator(&mut self, spectator_id: ActorId) {
+        if let Some(spectator) = self.clients.get(&spectator_id) {
+            let mut orchestrator = self.packet_orchestrator.borrow_mut();
+            orchestrator.remove_netplay_spectator(spectator.socket_address);
+        }
+    }
+
+    /// Creates or updates a queue, matched players are placed in a netplay battle owned by the active plugin
+    pub fn create_matchmaking_queue(&mut self, queue_id: String, options: MatchmakingQueueOptions) {
+        (self.matchmaker).create_queue(queue_id, self.active_plugin, options);
+    }
+
+    pub fn remove_matchmaking_queue(&mut self, queue_id: &str) {
+        self.matchmaker.remove_queue(queue_id);
+    }
+
+    pub fn has_matchmaking_queue(&self, queue_id: &str) -> bool {
+        self.matchmaker.has_queue(queue_id)
+    }
+
+    pub fn get_matchmaking_queue_players(&self, queue_id: &str) -> Vec<ActorId> {
+        self.matchmaker.queue_players(queue_id).collect()
+    }
+
+    pub fn get_player_matchmaking_queue(&self, player_id: ActorId) -> Option<&str> {
+        self.matchmaker.player_queue(player_id)
+    }
+
+    /// Returns false if the queue doesn't exist, or if the player is missing or already battling
+    pub fn join_matchmaking_queue(
+        &mut self,
+        queue_id: &str,
+        player_id: ActorId,
+        rating: f32,
+    ) -> bool {
+        use std::time::Instant;
+
+        let Some(client) = self.clients.get(&player_id) else {
+            return false;
+        };
+
+        if client.is_battling() {
+            return false;
+        }
+
+        (self.matchmaker).join(queue_id, player_id, rating, Instant::now())
+    }
+
+    pub fn leave_matchmaking_queue(&mut self, player_id: ActorId) -> bool {
+        self.matchmaker
+            .leave(player_id, MatchmakingDropReason::Cancelled)
+    }
+
+    pub(super) fn take_matchmaking_events(&mut self) -> Vec<MatchmakingEvent> {
+        self.matchmaker.take_events()
+    }
+
+    /// Returns false if the battle stopped waiting for results, such as after timing out
+    pub(super) fn submit_battle_report(
+        &mut self,
+        verification_id: usize,
+        player_index: usize,
+        battle_stats: BattleStatistics,
+    ) -> bool {
+        use std::time::Instant;
+
+        (self.battle_verifier).submit_report(
+            verification_id,
+            player_index,
+            battle_stats,
+            Instant::now(),
+        )
+    }
+
+    pub(super) fn take_verified_battle_results(&mut self) -> Vec<VerifiedBattleResult> {
+        self.battle_verifier.take_results()
+    }
+
+    fn update_matchmaking(&mut self) {
+        use std::time::Instant;
+
+        let formed_matches = self.matchmaker.tick(Instant::now());
+
+        if formed_matches.is_empty() {
+            return;
+        }
+
+        let active_plugin = self.active_plugin;
+
+        for formed_match in formed_matches {
+            let player_ids: Vec<_> = formed_match.teams.into_iter().flatten().collect();
+            let options = formed_match.options;
+
+            if let Some(restrictions_path) = options.restrictions_path.as_deref() {
+                for player_id in &player_ids {
+                    self.set_player_restrictions(*player_id, Some(restrictions_path));
+                }
+            }
+
+            // results should be sent to the plugin that created the queue
+            self.active_plugin = formed_match.plugin_index;
+            self.initiate_netplay(
+                &player_ids,
+                options.package_path,
+                options.data,
+                options.verify_results,
+            );
+        }
+
+        self.active_plugin = active_plugin;
+    }
+
     pub fn set_player_restrictions(&mut self, player_id: ActorId, restrictions_path: Option<&str>) {
         if let Some(restrictions_path) = restrictions_path {
             ensure_asset(
@@ -1221,6 +1605,9 @@ impl Net {
         package_path: &str,
         data: Option<String>,
     ) {
+        self.matchmaker
+            .leave(player_id, MatchmakingDropReason::Battling);
+
         self.preload_package(&[player_id], package_path);
 
         let Some(client) = self.clients.get_mut(&player_id) else {
@@ -1370,6 +1757,26 @@ impl Net {
         );
     }
 
+    /// True if the player's latest battle was verified and the players didn't agree on the results.
+    /// Battles without verification, such as encounters, are never disputed
+    pub fn is_latest_battle_disputed(&self, player_id: ActorId) -> bool {
+        self.clients.get(&player_id).is_some_and(|client| {
+            matches!(
+                client.latest_battle_verdict,
+                Some(BattleVerdict::Disagreed | BattleVerdict::Incomplete)
+            )
+        })
+    }
+
+    /// Lists a reward on the results screen of the player's latest battle
+    pub fn add_battle_reward(&mut self, player_id: ActorId, reward: BattleReward) {
+        self.packet_orchestrator.borrow_mut().send_by_id(
+            player_id,
+            Reliability::ReliableOrdered,
+            ServerPacket::BattleReward { reward },
+        );
+    }
+
     pub fn give_player_card(
         &mut self,
         player_id: ActorId,
@@ -1436,6 +1843,307 @@ impl Net {
         }
     }
 
+    /// Adds the mail to the player's inbox, replacing mail with a matching id
+    pub fn send_mail(&mut self, player_id: ActorId, mail: Mail) {
+        ensure_assets(
+            &mut self.packet_orchestrator.borrow_mut(),
+            self.config.args.max_payload_size,
+            &self.asset_manager,
+            &mut self.clients,
+            &[player_id],
+            mail.dependencies(),
+        );
+
+        let Some(client) = self.clients.get_mut(&player_id) else {
+            return;
+        };
+
+        insert_mail(&mut client.player_data.mail, mail.clone());
+
+        self.packet_orchestrator.borrow_mut().send(
+            client.socket_address,
+            Reliability::ReliableOrdered,
+            ServerPacket::Mail { mail },
+        );
+    }
+
+    /// Delivers mail to a player who may be offline, using the identity from `get_player_secret`
+    pub fn send_mail_to_identity(&mut self, identity: &[u8], mail: Mail) -> std::io::Result<()> {
+        let online_player_id = self
+            .clients
+            .iter()
+            .find(|(_, client)| client.player_data.identity == identity)
+            .map(|(id, _)| *id);
+
+        if let Some(player_id) = online_player_id {
+            self.send_mail(player_id, mail);
+            return Ok(());
+        }
+
+        let Some(storage) = &mut self.player_data_storage else {
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::Unsupported,
+                "player data storage is disabled",
+            ));
+        };
+
+        let mut data = storage.load(identity)?.unwrap_or_default();
+
+        insert_mail(&mut data.mail, mail);
+
+        storage.save(identity, &data)
+    }
+
+    pub fn delete_mail(&mut self, player_id: ActorId, mail_id: &str) {
+        let Some(client) = self.clients.get_mut(&player_id) else {
+            return;
+        };
+
+        let inbox = &mut client.player_data.mail;
+        let original_len = inbox.len();
+        inbox.retain(|mail| mail.id != mail_id);
+
+        if inbox.len() == original_len {
+            return;
+        }
+
+        self.packet_orchestrator.borrow_mut().send(
+            client.socket_address,
+            Reliability::ReliableOrdered,
+            ServerPacket::RemoveMail {
+                id: mail_id.to_string(),
+            },
+        );
+    }
+
+    /// Returns true if the mail was previously unread
+    pub(super) fn mark_mail_read(&mut self, player_id: ActorId, mail_id: &str) -> bool {
+        let Some(client) = self.clients.get_mut(&player_id) else {
+            return false;
+        };
+
+        let Some(mail) = client.player_data.mail.iter_mut().find(|m| m.id == mail_id) else {
+            return false;
+        };
+
+        !std::mem::replace(&mut mail.read, true)
+    }
+
+    /// Gives the player the mail's attachments, returns false if there was nothing to claim
+    pub(super) fn claim_mail(&mut self, player_id: ActorId, mail_id: &str) -> bool {
+        let Some(client) = self.clients.get_mut(&player_id) else {
+            return false;
+        };
+
+        let Some(mail) = client.player_data.mail.iter_mut().find(|m| m.id == mail_id) else {
+            return false;
+        };
+
+        if !mail.has_unclaimed_attachments() {
+            return false;
+        }
+
+        mail.read = true;
+        mail.claimed = true;
+
+        let mail = mail.clone();
+        let mut money = client.player_data.money;
+
+        for attachment in &mail.attachments {
+            match attachment {
+                MailAttachment::Item { id, count, .. } => {
+                    self.give_player_item(player_id, id.clone(), *count as isize);
+                }
+                MailAttachment::Card {
+                    package_id,
+                    code,
+                    count,
+                } => {
+                    self.give_player_card(
+                        player_id,
+                        package_id.clone(),
+                        code.clone(),
+                        *count as isize,
+                    );
+                }
+                MailAttachment::Money { amount } => {
+                    money = money.saturating_add(*amount);
+                }
+            }
+        }
+
+        self.set_player_money(player_id, money);
+
+        // sync the claimed state
+        self.packet_orchestrator.borrow_mut().send_by_id(
+            player_id,
+            Reliability::ReliableOrdered,
+            ServerPacket::Mail { mail },
+        );
+
+        true
+    }
+
+    pub(super) fn load_player_data(&mut self, player_id: ActorId) {
+        let Some(client) = self.clients.get_mut(&player_id) else {
+            return;
+        };
+
+        let Some(storage) = &mut self.player_data_storage else {
+            return;
+        };
+
+        let data = match storage.load(&client.player_data.identity) {
+            Ok(Some(data)) => data,
+            Ok(None) => {
+                client.player_data_loaded = true;
+                return;
+            }
+            Err(err) => {
+                // avoid overwriting data we failed to read
+                log::error!("Failed to load player data for {player_id:?}: {err}");
+                return;
+            }
+        };
+
+        client.player_data_loaded = true;
+
+        let player_data = &mut client.player_data;
+        player_data.health = data.health;
+        player_data.emotion = data.emotion.clone();
+        player_data.money = data.money;
+
+        let mut packets = vec![
+            ServerPacket::Health {
+                health: data.health,
+            },
+            ServerPacket::Emotion {
+                emotion: data.emotion,
+            },
+            ServerPacket::Money { money: data.money },
+        ];
+
+        for item in data.items {
+            // store the item even if it's unregistered to avoid losing it on the next save
+            player_data
+                .inventory
+                .give_item(&item.id, item.count as isize);
+
+            let Some(item_definition) = self.item_registry.get(&item.id).cloned() else {
+                log::warn!("No item found with id {:?}", item.id);
+                continue;
+            };
+
+            packets.push(ServerPacket::RegisterItem {
+                id: item.id.clone(),
+                item_definition,
+            });
+
+            packets.push(ServerPacket::AddItem {
+                id: item.id,
+                count: item.count as isize,
+            });
+        }
+
+        for card in data.cards {
+            let package_id = PackageId::from(card.package_id);
+            player_data.add_card(&package_id, &card.code, card.count as isize);
+
+            packets.push(ServerPacket::AddCard {
+                package_id,
+                code: card.code,
+                count: card.count as isize,
+            });
+        }
+
+        for block in data.blocks {
+            let package_id = PackageId::from(block.package_id);
+            player_data.add_block(&package_id, block.color, block.count as isize);
+
+            packets.push(ServerPacket::AddBlock {
+                package_id,
+                color: block.color,
+                count: block.count as isize,
+            });
+        }
+
+        for package_id in data.players {
+            let package_id = PackageId::from(package_id);
+            player_data.enable_player_character(&package_id, true);
+
+            packets.push(ServerPacket::EnablePlayableCharacter {
+                package_id,
+                enabled: true,
+            });
+        }
+
+        // keep mail sent while the data was loading, it's newer than the stored copy
+        let mut inbox = data.mail;
+
+        for mail in std::mem::take(&mut player_data.mail) {
+            insert_mail(&mut inbox, mail);
+        }
+
+        for mail in &inbox {
+            packets.push(ServerPacket::Mail { mail: mail.clone() });
+        }
+
+        let mail_dependencies: Vec<String> = inbox
+            .iter()
+            .flat_map(|mail| mail.dependencies())
+            .map(String::from)
+            .collect();
+
+        player_data.mail = inbox;
+
+        let socket_address = client.socket_address;
+
+        ensure_assets(
+            &mut self.packet_orchestrator.borrow_mut(),
+            self.config.args.max_payload_size,
+            &self.asset_manager,
+            &mut self.clients,
+            &[player_id],
+            mail_dependencies,
+        );
+
+        self.packet_orchestrator.borrow_mut().send_packets(
+            socket_address,
+            Reliability::ReliableOrdered,
+            packets,
+        );
+    }
+
+    pub fn save_player_data(&mut self, player_id: ActorId) {
+        let Some(client) = self.clients.get(&player_id) else {
+            return;
+        };
+
+        let Some(storage) = &mut self.player_data_storage else {
+            return;
+        };
+
+        if !client.player_data_loaded {
+            // saving now would overwrite data we haven't read
+            return;
+        }
+
+        let player_data = &client.player_data;
+        let data = PersistentPlayerData::from(player_data);
+
+        if let Err(err) = storage.save(&player_data.identity, &data) {
+            log::error!("Failed to save player data for {player_id:?}: {err}");
+        }
+    }
+
+    pub(super) fn save_all_player_data(&mut self) {
+        let player_ids: Vec<_> = self.clients.keys().cloned().collect();
+
+        for player_id in player_ids {
+            self.save_player_data(player_id);
+        }
+    }
+
     #[allow(clippy::too_many_arguments)]
     pub fn transfer_player(
         &mut self,
@@ -1514,6 +2222,9 @@ impl Net {
             ServerPacket::TransferStart,
         );
 
+        // the client forgets exclusions when transferring
+        client.excluded_objects.clear();
+
         let area_id = client.warp_area.clone();
         let Some(area) = self.areas.get_mut(&area_id) else {
             self.kick_player(player_id, "Area destroyed", true);
@@ -1926,6 +2637,11 @@ impl Net {
 
         self.free_actor_id(id);
 
+        self.matchmaker
+            .leave(id, MatchmakingDropReason::Disconnected);
+
+        (self.battle_verifier).drop_player(id, std::time::Instant::now());
+
         // remove assets
         let remove_list = [
             asset::get_player_texture_path(id),
@@ -2019,6 +2735,8 @@ impl Net {
             return;
         };
 
+        self.navigation.stop(id);
+
         self.free_actor_id(id);
 
         // delete sprites
@@ -2076,7 +2794,10 @@ impl Net {
         );
     }
 
+    /// Also stops navigation, see `walk_bot_to`
     pub fn move_bot(&mut self, id: ActorId, x: f32, y: f32, z: f32) {
+        self.navigation.stop(id);
+
         if let Some(bot) = self.bots.get_mut(&id) {
             let updated_direction = Direction::from_offset((x - bot.x, y - bot.y));
 
@@ -2088,6 +2809,38 @@ impl Net {
         }
     }
 
+    /// Walks the bot around obstacles at `speed` tiles per second
+    pub fn walk_bot_to(&mut self, id: ActorId, x: f32, y: f32, z: f32, speed: f32) {
+        if self.bots.contains_key(&id) {
+            self.navigation.walk_to(id, (x, y, z), speed);
+        }
+    }
+
+    /// Walks the bot between each point in order, closed routes loop and open routes walk back
+    pub fn patrol_bot(
+        &mut self,
+        id: ActorId,
+        points: Vec<(f32, f32, f32)>,
+        closed: bool,
+        speed: f32,
+    ) {
+        if self.bots.contains_key(&id) {
+            self.navigation.patrol(id, points, closed, speed);
+        }
+    }
+
+    pub fn stop_bot(&mut self, id: ActorId) {
+        self.navigation.stop(id);
+    }
+
+    pub fn is_bot_navigating(&self, id: ActorId) -> bool {
+        self.navigation.is_navigating(id)
+    }
+
+    pub(super) fn take_navigation_events(&mut self) -> Vec<NavigationEvent> {
+        self.navigation.take_events()
+    }
+
     pub fn set_bot_direction(&mut self, id: ActorId, direction: Direction) {
         if let Some(bot) = self.bots.get_mut(&id) {
             bot.set_direction(direction);
@@ -2262,6 +3015,8 @@ impl Net {
             return;
         };
 
+        self.navigation.stop(id);
+
         if let Some(previous_area) = self.areas.get_mut(&bot.area_id) {
             previous_area.remove_bot(id);
 
@@ -2576,6 +3331,12 @@ impl Net {
     }
 
     pub(super) fn tick(&mut self) {
+        use std::time::Instant;
+
+        self.update_matchmaking();
+        self.battle_verifier.tick(Instant::now());
+        self.navigation
+            .tick(Instant::now(), &mut self.bots, &self.areas);
         self.broadcast_bot_positions();
         self.broadcast_map_changes();
     }
@@ -2888,3 +3649,12 @@ fn ensure_scope_has_assets<AI, A>(
         }
     }
 }
+
+/// Adds the mail to the inbox, replacing mail with a matching id
+fn insert_mail(inbox: &mut Vec<Mail>, mail: Mail) {
+    if let Some(existing_mail) = inbox.iter_mut().find(|m| m.id == mail.id) {
+        *existing_mail = mail;
+    } else {
+        inbox.push(mail);
+    }
+}
@@ -1,14 +1,13 @@
 use packets::structures::ActorId;
 use packets::{
-    serialize, ChannelSender, ConnectionBuilder, NetplayPacket, PacketChannels, PacketReceiver,
-    PacketSender, Reliability, ServerCommPacket, ServerPacket,
+    serialize, ChannelSender, ConnectionBuilder, IdentityKey, NetplayPacket, PacketChannels,
+    PacketReceiver, PacketSender, Reliability, ServerCommPacket, ServerPacket,
 };
 use slotmap::SlotMap;
 use std::collections::{HashMap, HashSet};
 use std::net::{SocketAddr, UdpSocket};
 use std::rc::Rc;
 use std::sync::Arc;
-use std::time::Duration;
 
 use super::boot::Boot;
 use super::ServerConfig;
@@ -27,8 +26,11 @@ impl Connection {
     fn new(
         connection_config: &packets::Config,
         socket_address: SocketAddr,
+        require_encryption: bool,
+        identity_key: IdentityKey,
     ) -> (Self, PacketReceiver<PacketChannels>) {
         let mut connection_builder = ConnectionBuilder::new(connection_config);
+        connection_builder.accept_handshake(require_encryption, identity_key);
         let server_comm_channel =
             connection_builder.bidirectional_channel(PacketChannels::ServerComm);
         let server_channel = connection_builder.sending_channel(PacketChannels::Server);
@@ -61,6 +63,9 @@ pub struct PacketOrchestrator {
     client_room_map: HashMap<SocketAddr, Vec<String>>,
     rooms: HashMap<String, Vec<slotmap::DefaultKey>>,
     netplay_route_map: HashMap<SocketAddr, Vec<SocketAddr>>,
+    netplay_spectator_map: HashMap<SocketAddr, Vec<SocketAddr>>,
+    // player address -> spectators that joined since the player's last SpectatorSync
+    pending_spectator_syncs: HashMap<SocketAddr, Vec<SocketAddr>>,
     synchronize_updates: bool,
     synchronize_requests: usize,
     synchronize_locked_clients: HashSet<SocketAddr>,
@@ -72,8 +77,7 @@ impl PacketOrchestrator {
         let connection_config = packets::Config {
             mtu: server_config.args.max_payload_size,
             bytes_per_tick: server_config.args.resend_budget,
-            rtt_resend_factor: 0.5,
-            initial_rtt: Duration::from_millis(500),
+            ..Default::default()
         };
 
         PacketOrchestrator {
@@ -86,6 +90,8 @@ impl PacketOrchestrator {
             client_room_map: HashMap::new(),
             rooms: HashMap::new(),
             netplay_route_map: HashMap::new(),
+            netplay_spectator_map: HashMap::new(),
+            pending_spectator_syncs: HashMap::new(),
             synchronize_updates: false,
             synchronize_requests: 0,
             synchronize_locked_clients: HashSet::new(),
@@ -129,7 +135,12 @@ impl PacketOrchestrator {
             return;
         }
 
-        let (connection, receiver) = Connection::new(&self.connection_config, socket_address);
+        let (connection, receiver) = Connection::new(
+            &self.connection_config,
+            socket_address,
+            self.server_config.args.require_encryption,
+            self.server_config.identity_key.clone(),
+        );
         self.pending_receivers.push((socket_address, receiver));
 
         let index = self.connections.insert(connection);
@@ -141,6 +152,12 @@ impl PacketOrchestrator {
         std::mem::take(&mut self.pending_receivers)
     }
 
+    pub fn get_connection_stats_by_id(&self, id: ActorId) -> Option<packets::ConnectionStats> {
+        let index = self.client_id_map.get(&id)?;
+
+        Some(self.connections[*index].packet_sender.stats())
+    }
+
     pub fn register_client(&mut self, socket_address: SocketAddr, id: ActorId) {
         self.unregister_client(socket_address);
 
@@ -195,6 +212,9 @@ impl PacketOrchestrator {
                 }
             }
         }
+
+        self.detach_netplay_spectator(socket_address);
+        self.clear_netplay_spectators(socket_address);
     }
 
     pub fn drop_connection(&mut self, socket_address: SocketAddr) {
@@ -214,12 +234,123 @@ impl PacketOrchestrator {
         if let Some(index) = self.connection_map.get(&socket_address) {
             self.connections[*index].netplay_index = player_index;
 
+            // stop spectating any other battle
+            self.detach_netplay_spectator(socket_address);
+
             self.netplay_route_map
                 .insert(socket_address, destination_addresses);
         }
     }
 
-    pub fn forward_netplay_packet(&self, socket_address: SocketAddr, packet: NetplayPacket) {
+    pub fn add_netplay_spectator(
+        &mut self,
+        spectator_address: SocketAddr,
+        spectator_index: usize,
+        player_addresses: Vec<SocketAddr>,
+    ) {
+        let Some(index) = self.connection_map.get(&spectator_address) else {
+            return;
+        };
+
+        self.connections[*index].netplay_index = spectator_index;
+
+        // stop spectating any other battle
+        self.detach_netplay_spectator(spectator_address);
+
+        for address in &player_addresses {
+            self.netplay_spectator_map
+                .entry(*address)
+                .or_default()
+                .push(spectator_address);
+        }
+
+        // spectators share their requests with every player
+        self.netplay_route_map
+            .insert(spectator_address, player_addresses);
+    }
+
+    pub fn remove_netplay_spectator(&mut self, spectator_address: SocketAddr) {
+        let player_addresses = self.detach_netplay_spectator(spectator_address);
+
+        // end the battle for the spectator
+        for address in player_addresses {
+            if let Some(index) = self.connection_map.get(&address) {
+                let player_index = self.connections[*index].netplay_index;
+
+                self.send_netplay(
+                    spectator_address,
+                    NetplayPacket::new_disconnect_signal(player_index),
+                );
+            }
+        }
+    }
+
+    /// Stops sharing a player's packets with spectators, such as when the player's battle ends
+    pub fn clear_netplay_spectators(&mut self, socket_address: SocketAddr) {
+        self.pending_spectator_syncs.remove(&socket_address);
+
+        let Some(spectator_addresses) = self.netplay_spectator_map.remove(&socket_address) else {
+            return;
+        };
+
+        let Some(index) = self.connection_map.get(&socket_address) else {
+            return;
+        };
+
+        let player_index = self.connections[*index].netplay_index;
+
+        for spectator_address in spectator_addresses {
+            if let Some(address_list) = self.netplay_route_map.get_mut(&spectator_address) {
+                address_list.retain(|address| *address != socket_address);
+            }
+
+            self.send_netplay(
+                spectator_address,
+                NetplayPacket::new_disconnect_signal(player_index),
+            );
+        }
+    }
+
+    /// Returns the addresses of the players the spectator was watching
+    fn detach_netplay_spectator(&mut self, spectator_address: SocketAddr) -> Vec<SocketAddr> {
+        let mut player_addresses = Vec::new();
+
+        for (player_address, spectator_addresses) in &mut self.netplay_spectator_map {
+            if let Some(index) = spectator_addresses
+                .iter()
+                .position(|address| *address == spectator_address)
+            {
+                spectator_addresses.remove(index);
+                player_addresses.push(*player_address);
+            }
+        }
+
+        self.netplay_spectator_map
+            .retain(|_, spectator_addresses| !spectator_addresses.is_empty());
+
+        for spectator_addresses in self.pending_spectator_syncs.values_mut() {
+            spectator_addresses.retain(|address| *address != spectator_address);
+        }
+
+        self.pending_spectator_syncs
+            .retain(|_, spectator_addresses| !spectator_addresses.is_empty());
+
+        if !player_addresses.is_empty() {
+            self.netplay_route_map.remove(&spectator_address);
+        }
+
+        player_addresses
+    }
+
+    fn send_netplay(&self, socket_address: SocketAddr, packet: NetplayPacket) {
+        if let Some(index) = self.connection_map.get(&socket_address) {
+            self.connections[*index]
+                .netplay_channel
+                .send_serialized(Reliability::ReliableOrdered, packet);
+        }
+    }
+
+    pub fn forward_netplay_packet(&mut self, socket_address: SocketAddr, packet: NetplayPacket) {
         if let Some(index) = self.connection_map.get(&socket_address) {
             if self.connections[*index].netplay_index != packet.index() {
                 // client is attempting to impersonate another player, reject the packet
@@ -227,15 +358,58 @@ impl PacketOrchestrator {
             }
         }
 
+        let addresses = self.resolve_netplay_destinations(socket_address, &packet);
+
         let data = Arc::new(serialize(packet));
 
-        if let Some(addresses) = self.netplay_route_map.get(&socket_address) {
-            for address in addresses {
-                if let Some(index) = self.connection_map.get(address) {
-                    self.connections[*index]
-                        .netplay_channel
-                        .send_shared_bytes(Reliability::ReliableOrdered, data.clone());
+        for address in addresses {
+            if let Some(index) = self.connection_map.get(&address) {
+                self.connections[*index]
+                    .netplay_channel
+                    .send_shared_bytes(Reliability::ReliableOrdered, data.clone());
+            }
+        }
+    }
+
+    fn resolve_netplay_destinations(
+        &mut self,
+        socket_address: SocketAddr,
+        packet: &NetplayPacket,
+    ) -> Vec<SocketAddr> {
+        match packet {
+            NetplayPacket::SpectatorJoin { .. } => {
+                // the players will answer with a SpectatorSync meant only for this spectator
+                let player_addresses = self
+                    .netplay_route_map
+                    .get(&socket_address)
+                    .cloned()
+                    .unwrap_or_default();
+
+                for address in &player_addresses {
+                    let pending_spectators =
+                        self.pending_spectator_syncs.entry(*address).or_default();
+
+                    if !pending_spectators.contains(&socket_address) {
+                        pending_spectators.push(socket_address);
+                    }
                 }
+
+                player_addresses
+            }
+            NetplayPacket::SpectatorSync { .. } => {
+                // spectators that joined earlier were already synced
+                self.pending_spectator_syncs
+                    .remove(&socket_address)
+                    .unwrap_or_default()
+            }
+            _ => {
+                let route_map = if packet.is_for_spectators() {
+                    &self.netplay_spectator_map
+                } else {
+                    &self.netplay_route_map
+                };
+
+                route_map.get(&socket_address).cloned().unwrap_or_default()
             }
         }
     }
@@ -628,12 +802,13 @@ mod tests {
     use super::*;
 
     fn create_orchestrator() -> PacketOrchestrator {
-        let socket = UdpSocket::bind("127.0.0.1:8765").unwrap();
+        let socket = UdpSocket::bind("127.0.0.1:0").unwrap();
         socket.take_error().unwrap();
 
         let config = ServerConfig {
             max_silence_duration: 0.0,
             heartbeat_rate: 0.0,
+            identity_key: IdentityKey::generate(),
             args: crate::args::Args {
                 port: 8765,
                 log_connections: false,
@@ -645,6 +820,12 @@ mod tests {
                 avatar_dimensions_limit: 0,
                 emotes_animation_path: None,
                 emotes_texture_path: None,
+                hot_reload: false,
+                player_data_path: None,
+                player_data_save_interval: 0.0,
+                validate_movement: false,
+                require_encryption: false,
+                identity_key_path: String::new(),
             },
         };
 
@@ -723,4 +904,62 @@ mod tests {
             "room C should not exist"
         );
     }
+
+    #[test]
+    fn spectator_syncs_only_reach_joining_spectators() {
+        let mut orchestrator = create_orchestrator();
+        let player: SocketAddr = "127.0.0.1:3000".parse().unwrap();
+        let spectator_a: SocketAddr = "127.0.0.1:3001".parse().unwrap();
+        let spectator_b: SocketAddr = "127.0.0.1:3002".parse().unwrap();
+
+        let sync = NetplayPacket::SpectatorSync {
+            index: 0,
+            seed: 0,
+            player_package: Default::default(),
+            script_enabled: false,
+            cards: Vec::new(),
+            regular_card: None,
+            recipes: Vec::new(),
+            blocks: Vec::new(),
+            drives: Vec::new(),
+            packages: Vec::new(),
+            buffer: Vec::new(),
+        };
+
+        let buffer = NetplayPacket::SpectatorBuffer {
+            index: 0,
+            time: 0,
+            data: Default::default(),
+        };
+
+        for address in [player, spectator_a, spectator_b] {
+            orchestrator.create_connection(address);
+        }
+
+        orchestrator.add_netplay_spectator(spectator_a, 1, vec![player]);
+
+        let join = NetplayPacket::SpectatorJoin { index: 1 };
+        let destinations = orchestrator.resolve_netplay_destinations(spectator_a, &join);
+        assert_eq!(destinations, vec![player]);
+
+        let destinations = orchestrator.resolve_netplay_destinations(player, &sync);
+        assert_eq!(destinations, vec![spectator_a]);
+
+        orchestrator.add_netplay_spectator(spectator_b, 1, vec![player]);
+        orchestrator.resolve_netplay_destinations(spectator_b, &join);
+
+        let destinations = orchestrator.resolve_netplay_destinations(player, &sync);
+        assert_eq!(destinations, vec![spectator_b]);
+
+        // every spectator still receives input
+        let destinations = orchestrator.resolve_netplay_destinations(player, &buffer);
+        assert_eq!(destinations, vec![spectator_a, spectator_b]);
+
+        // spectators that leave before the sync arrives are forgotten
+        orchestrator.resolve_netplay_destinations(spectator_a, &join);
+        orchestrator.remove_netplay_spectator(spectator_a);
+
+        let destinations = orchestrator.resolve_netplay_destinations(player, &sync);
+        assert!(destinations.is_empty());
+    }
 }
@@ -1,4 +1,4 @@
-use packets::structures::{BlockColor, Emotion, Inventory, PackageId};
+use packets::structures::{BlockColor, Emotion, Inventory, Mail, PackageId};
 use std::borrow::Cow;
 use std::collections::{HashMap, HashSet};
 
@@ -15,6 +15,7 @@ pub struct PlayerData {
     pub owned_cards: HashMap<(Cow<'static, str>, Cow<'static, str>), usize>,
     pub owned_blocks: HashMap<(Cow<'static, str>, BlockColor), usize>,
     pub owned_players: HashSet<String>,
+    pub mail: Vec<Mail>,
 }
 
 impl PlayerData {
@@ -32,6 +33,7 @@ impl PlayerData {
             owned_cards: HashMap::new(),
             owned_blocks: HashMap::new(),
             owned_players: HashSet::new(),
+            mail: Vec::new(),
         }
     }
 
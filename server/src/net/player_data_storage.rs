@@ -0,0 +1,185 @@
+use super::PlayerData;
+use packets::structures::{BlockColor, Emotion, FileHash, Mail};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A snapshot of the parts of PlayerData that survive reconnects and restarts
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistentPlayerData {
+    pub health: i32,
+    pub emotion: Emotion,
+    pub money: u32,
+    pub players: Vec<String>,
+    pub items: Vec<PersistentItem>,
+    pub cards: Vec<PersistentCard>,
+    pub blocks: Vec<PersistentBlock>,
+    pub mail: Vec<Mail>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PersistentItem {
+    pub id: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PersistentCard {
+    pub package_id: String,
+    pub code: String,
+    pub count: usize,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct PersistentBlock {
+    pub package_id: String,
+    pub color: BlockColor,
+    pub count: usize,
+}
+
+impl From<&PlayerData> for PersistentPlayerData {
+    fn from(player_data: &PlayerData) -> Self {
+        let items = player_data
+            .inventory
+            .items()
+            .map(|(id, &count)| PersistentItem {
+                id: id.clone(),
+                count,
+            })
+            .collect();
+
+        let cards = player_data
+            .owned_cards
+            .iter()
+            .filter(|(_, &count)| count > 0)
+            .map(|((package_id, code), &count)| PersistentCard {
+                package_id: package_id.to_string(),
+                code: code.to_string(),
+                count,
+            })
+            .collect();
+
+        let blocks = player_data
+            .owned_blocks
+            .iter()
+            .filter(|(_, &count)| count > 0)
+            .map(|((package_id, color), &count)| PersistentBlock {
+                package_id: package_id.to_string(),
+                color: *color,
+                count,
+            })
+            .collect();
+
+        Self {
+            health: player_data.health,
+            emotion: player_data.emotion.clone(),
+            money: player_data.money,
+            players: player_data.owned_players.iter().cloned().collect(),
+            items,
+            cards,
+            blocks,
+            mail: player_data.mail.clone(),
+        }
+    }
+}
+
+/// Backend used to load and save player data, keyed by the player's identity
+pub trait PlayerDataStorage {
+    fn load(&mut self, identity: &[u8]) -> std::io::Result<Option<PersistentPlayerData>>;
+    fn save(&mut self, identity: &[u8], data: &PersistentPlayerData) -> std::io::Result<()>;
+}
+
+/// Stores each player as a toml file named by the hash of their identity
+pub struct FilePlayerDataStorage {
+    folder: PathBuf,
+}
+
+impl FilePlayerDataStorage {
+    pub fn new(folder: impl Into<PathBuf>) -> std::io::Result<Self> {
+        let folder = folder.into();
+        std::fs::create_dir_all(&folder)?;
+
+        Ok(Self { folder })
+    }
+
+    fn resolve_path(&self, identity: &[u8]) -> PathBuf {
+        // hashing to avoid leaking the identity through file names
+        let hash = FileHash::hash(identity);
+
+        self.folder.join(format!("{hash}.toml"))
+    }
+}
+
+impl PlayerDataStorage for FilePlayerDataStorage {
+    fn load(&mut self, identity: &[u8]) -> std::io::Result<Option<PersistentPlayerData>> {
+        use std::io::{Error, ErrorKind};
+
+        let path = self.resolve_path(identity);
+
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(err),
+        };
+
+        let data = toml::from_str(&text).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+        Ok(Some(data))
+    }
+
+    fn save(&mut self, identity: &[u8], data: &PersistentPlayerData) -> std::io::Result<()> {
+        use std::io::{Error, ErrorKind};
+
+        let path = self.resolve_path(identity);
+        let text = toml::to_string(data).map_err(|err| Error::new(ErrorKind::InvalidData, err))?;
+
+        // write to a temporary file first to avoid corrupting the save if we're interrupted
+        let temp_path = path.with_extension("toml.tmp");
+        std::fs::write(&temp_path, text)?;
+        std::fs::rename(temp_path, path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use packets::structures::{MailAttachment, MailCategory, PackageId, TextureAnimPathPair};
+
+    #[test]
+    fn mail_survives_toml() {
+        let data = PersistentPlayerData {
+            mail: vec![Mail {
+                id: String::from("welcome"),
+                category: MailCategory::Announcement,
+                sender: String::from("Admin"),
+                subject: String::from("Welcome!"),
+                body: String::from("Here's something to get you started."),
+                mug: Some(TextureAnimPathPair {
+                    texture: "/server/assets/mug.png".into(),
+                    animation: "/server/assets/mug.animation".into(),
+                }),
+                attachments: vec![
+                    MailAttachment::Item {
+                        id: String::from("key"),
+                        name: String::from("Key"),
+                        count: 1,
+                    },
+                    MailAttachment::Card {
+                        package_id: PackageId::from("dev.example.card"),
+                        code: String::from("*"),
+                        count: 2,
+                    },
+                    MailAttachment::Money { amount: 500 },
+                ],
+                read: false,
+                claimed: false,
+            }],
+            ..Default::default()
+        };
+
+        let text = toml::to_string(&data).unwrap();
+        let loaded: PersistentPlayerData = toml::from_str(&text).unwrap();
+
+        assert_eq!(loaded.mail, data.mail);
+    }
+}
@@ -1,6 +1,7 @@
-use super::{BattleStatistics, Net};
+use super::battle_verification::VerifiedBattleResult;
+use super::{BattleStatistics, BattleVerdict, MatchmakingDropReason, NavigationResult, Net};
 use crate::plugins::PluginInterface;
-use packets::structures::{ActorId, PackageId};
+use packets::structures::{ActorId, ChatChannel, PackageId};
 
 pub(super) struct PluginWrapper {
     plugin_interfaces: Vec<Box<dyn PluginInterface>>,
@@ -17,6 +18,61 @@ impl PluginWrapper {
         self.plugin_interfaces.push(plugin_interface);
     }
 
+    /// Results for verified battles are held until every player reports
+    pub(super) fn handle_battle_report(
+        &mut self,
+        net: &mut Net,
+        player_id: ActorId,
+        battle_stats: &BattleStatistics,
+    ) {
+        let Some(client) = net.get_client_mut(player_id) else {
+            return;
+        };
+
+        let Some(info) = client.battle_tracker.front() else {
+            return;
+        };
+
+        let mut verdict = None;
+
+        if let Some(verification_id) = info.verification_id {
+            let player_index = info.player_index;
+
+            if net.submit_battle_report(verification_id, player_index, battle_stats.clone()) {
+                return;
+            }
+
+            // the other players were not waited on
+            verdict = Some(BattleVerdict::Incomplete);
+        }
+
+        self.handle_battle_results(net, player_id, battle_stats, verdict);
+    }
+
+    pub(super) fn handle_verified_battle_result(
+        &mut self,
+        net: &mut Net,
+        result: VerifiedBattleResult,
+    ) {
+        let Some(client) = net.get_client_mut(result.player_id) else {
+            return;
+        };
+
+        let is_tracked = (client.battle_tracker.front())
+            .is_some_and(|info| info.verification_id == Some(result.battle_id));
+
+        if !is_tracked {
+            return;
+        }
+
+        self.handle_battle_results(
+            net,
+            result.player_id,
+            &result.statistics,
+            Some(result.verdict),
+        );
+    }
+
     fn wrap_call<C>(&mut self, i: usize, net: &mut Net, call: C)
     where
         C: FnMut(&mut Box<dyn PluginInterface>, &mut Net),
@@ -130,6 +186,22 @@ impl PluginInterface for PluginWrapper {
         prevent_default
     }
 
+    fn handle_player_chat(
+        &mut self,
+        net: &mut Net,
+        player_id: ActorId,
+        channel: ChatChannel,
+        message: &mut String,
+    ) -> bool {
+        let mut prevent_default = false;
+
+        self.wrap_calls(net, |plugin_interface, net| {
+            prevent_default |= plugin_interface.handle_player_chat(net, player_id, channel, message)
+        });
+
+        prevent_default
+    }
+
     fn handle_custom_warp(&mut self, net: &mut Net, player_id: ActorId, tile_object_id: u32) {
         self.wrap_calls(net, |plugin_interface, net| {
             plugin_interface.handle_custom_warp(net, player_id, tile_object_id)
@@ -301,23 +373,66 @@ impl PluginInterface for PluginWrapper {
         });
     }
 
+    fn handle_mail_read(&mut self, net: &mut Net, player_id: ActorId, mail_id: &str) {
+        self.wrap_calls(net, |plugin_interface, net| {
+            plugin_interface.handle_mail_read(net, player_id, mail_id)
+        });
+    }
+
+    fn handle_mail_claimed(&mut self, net: &mut Net, player_id: ActorId, mail_id: &str) {
+        self.wrap_calls(net, |plugin_interface, net| {
+            plugin_interface.handle_mail_claimed(net, player_id, mail_id)
+        });
+    }
+
     fn handle_battle_results(
         &mut self,
         net: &mut Net,
         player_id: ActorId,
         battle_stats: &BattleStatistics,
+        verdict: Option<BattleVerdict>,
     ) {
         if let Some(client) = net.get_client_mut(player_id) {
             if let Some(info) = client.battle_tracker.pop_front() {
                 let i = info.plugin_index;
+                client.latest_battle_verdict = verdict;
 
                 self.wrap_call(i, net, |plugin_interface, net| {
-                    plugin_interface.handle_battle_results(net, player_id, battle_stats)
+                    plugin_interface.handle_battle_results(net, player_id, battle_stats, verdict)
                 });
             }
         }
     }
 
+    fn handle_match_found(&mut self, net: &mut Net, queue_id: &str, teams: &[Vec<ActorId>]) {
+        self.wrap_calls(net, |plugin_interface, net| {
+            plugin_interface.handle_match_found(net, queue_id, teams)
+        });
+    }
+
+    fn handle_matchmaking_drop(
+        &mut self,
+        net: &mut Net,
+        queue_id: &str,
+        player_id: ActorId,
+        reason: MatchmakingDropReason,
+    ) {
+        self.wrap_calls(net, |plugin_interface, net| {
+            plugin_interface.handle_matchmaking_drop(net, queue_id, player_id, reason)
+        });
+    }
+
+    fn handle_bot_navigation_end(
+        &mut self,
+        net: &mut Net,
+        bot_id: ActorId,
+        result: NavigationResult,
+    ) {
+        self.wrap_calls(net, |plugin_interface, net| {
+            plugin_interface.handle_bot_navigation_end(net, bot_id, result)
+        });
+    }
+
     fn handle_server_message(
         &mut self,
         net: &mut Net,
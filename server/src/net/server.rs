@@ -1,10 +1,11 @@
 use super::plugin_wrapper::PluginWrapper;
-use super::{Net, PacketOrchestrator, ServerConfig};
+use super::{MatchmakingEvent, Net, PacketOrchestrator, PlayerDataStorage, ServerConfig};
+use crate::helpers::FileWatcher;
 use crate::jobs::{JobPromise, PromiseValue};
 use crate::plugins::PluginInterface;
 use crate::threads::{create_listening_thread, ListenerMessage, ThreadMessage};
 use flume::{Receiver, Sender};
-use packets::structures::ActorId;
+use packets::structures::{sanitize_chat_message, ActorId};
 use packets::{
     ClientAssetType, ClientPacket, Reliability, ServerCommPacket, ServerPacket, SERVER_TICK_RATE,
 };
@@ -24,7 +25,9 @@ pub struct Server {
     message_sender: Sender<ThreadMessage>,
     time: Instant,
     last_heartbeat: Instant,
+    last_player_data_save: Instant,
     pending_server_polls: HashMap<SocketAddr, Vec<JobPromise>>,
+    area_watcher: Option<FileWatcher>,
 }
 
 impl Server {
@@ -32,6 +35,7 @@ impl Server {
         config: ServerConfig,
         socket: UdpSocket,
         mut plugin_wrapper: PluginWrapper,
+        player_data_storage: Option<Box<dyn PlayerDataStorage>>,
         message_sender: Sender<ThreadMessage>,
     ) -> Self {
         let config = Rc::new(config);
@@ -45,10 +49,17 @@ impl Server {
         let mut net = Net::new(
             packet_orchestrator.clone(),
             config.clone(),
+            player_data_storage,
             message_sender.clone(),
         );
         plugin_wrapper.init(&mut net);
 
+        let area_watcher = if config.args.hot_reload {
+            Some(FileWatcher::new("./areas", "tmx"))
+        } else {
+            None
+        };
+
         Self {
             player_id_map: HashMap::new(),
             plugin_wrapper,
@@ -59,7 +70,9 @@ impl Server {
             message_sender,
             time: Instant::now(),
             last_heartbeat: Instant::now(),
+            last_player_data_save: Instant::now(),
             pending_server_polls: HashMap::new(),
+            area_watcher,
         }
     }
 
@@ -113,7 +126,7 @@ impl Server {
                             );
                         }
                         ThreadMessage::NetplayPacket  { socket_address, packet } => {
-                            let packet_orchestrator = self.packet_orchestrator.borrow_mut();
+                            let mut packet_orchestrator = self.packet_orchestrator.borrow_mut();
                             packet_orchestrator.forward_netplay_packet(socket_address, packet);
                         }
                         ThreadMessage::MessageServer { socket_address, data } => {
@@ -184,8 +197,42 @@ impl Server {
             }
         }
 
+        self.reload_changed_areas();
+
         self.net.tick();
 
+        for event in self.net.take_matchmaking_events() {
+            match event {
+                MatchmakingEvent::MatchFound { queue_id, teams } => {
+                    (self.plugin_wrapper).handle_match_found(&mut self.net, &queue_id, &teams);
+                }
+                MatchmakingEvent::PlayerDropped {
+                    queue_id,
+                    player_id,
+                    reason,
+                } => {
+                    self.plugin_wrapper.handle_matchmaking_drop(
+                        &mut self.net,
+                        &queue_id,
+                        player_id,
+                        reason,
+                    );
+                }
+            }
+        }
+
+        for event in self.net.take_navigation_events() {
+            (self.plugin_wrapper).handle_bot_navigation_end(
+                &mut self.net,
+                event.bot_id,
+                event.result,
+            );
+        }
+
+        for result in self.net.take_verified_battle_results() {
+            (self.plugin_wrapper).handle_verified_battle_result(&mut self.net, result);
+        }
+
         if self.last_heartbeat.elapsed().as_secs_f32() >= self.config.heartbeat_rate {
             self.packet_orchestrator
                 .borrow_mut()
@@ -194,6 +241,14 @@ impl Server {
             self.last_heartbeat = self.time;
         }
 
+        if self.last_player_data_save.elapsed().as_secs_f32()
+            >= self.config.args.player_data_save_interval
+        {
+            self.net.save_all_player_data();
+
+            self.last_player_data_save = self.time;
+        }
+
         // handle connections created in tick
         let receivers = self
             .packet_orchestrator
@@ -205,6 +260,37 @@ impl Server {
             .unwrap();
     }
 
+    fn reload_changed_areas(&mut self) {
+        use super::map::Map;
+
+        let Some(area_watcher) = &mut self.area_watcher else {
+            return;
+        };
+
+        for path in area_watcher.poll() {
+            let area_id = path
+                .file_stem()
+                .unwrap_or_default()
+                .to_string_lossy()
+                .into_owned();
+
+            let Ok(raw_map) = std::fs::read_to_string(&path) else {
+                continue;
+            };
+
+            if roxmltree::Document::parse(&raw_map).is_err() {
+                // likely caught the file mid write, we'll see it again on the next change
+                log::warn!("Skipping reload of {path:?}, failed to parse");
+                continue;
+            }
+
+            log::info!("Reloading area {area_id:?}");
+
+            // changes to the map will be sent to players in the area on the next net tick
+            self.net.add_area(area_id, Map::from(&raw_map));
+        }
+    }
+
     fn handle_server_comm_packet(&mut self, socket_address: SocketAddr, packet: ServerCommPacket) {
         if self.config.args.log_packets {
             let packet_name: &'static str = (&packet).into();
@@ -311,6 +397,7 @@ impl Server {
                 }
                 ClientPacket::RequestJoin => {
                     net.spawn_client(player_id);
+                    net.load_player_data(player_id);
 
                     self.plugin_wrapper.handle_player_connect(net, player_id);
 
@@ -335,8 +422,17 @@ impl Server {
                             let position_changed =
                                 client.actor.x != x || client.actor.y != y || client.actor.z != z;
 
+                            let movement_rejected = self.config.args.validate_movement
+                                && !net.validate_player_movement(player_id, x, y, z);
+
+                            if movement_rejected {
+                                return;
+                            }
+
                             if position_changed {
-                                client.actor.current_animation = None;
+                                if let Some(client) = net.get_client_mut(player_id) {
+                                    client.actor.current_animation = None;
+                                }
 
                                 self.plugin_wrapper
                                     .handle_player_move(net, player_id, x, y, z);
@@ -352,6 +448,7 @@ impl Server {
                         client.actor.x = client.warp_x;
                         client.actor.y = client.warp_y;
                         client.actor.z = client.warp_z;
+                        client.movement_validator.reset();
 
                         if client.transferring {
                             self.plugin_wrapper.handle_player_transfer(net, player_id);
@@ -434,6 +531,28 @@ impl Server {
                         net.set_player_emote(player_id, emote_id);
                     }
                 }
+                ClientPacket::Chat {
+                    channel,
+                    mut message,
+                } => {
+                    sanitize_chat_message(&mut message);
+
+                    if !message.trim().is_empty() {
+                        let prevent_default = self.plugin_wrapper.handle_player_chat(
+                            net,
+                            player_id,
+                            channel,
+                            &mut message,
+                        );
+
+                        // plugins may have rewritten the message
+                        sanitize_chat_message(&mut message);
+
+                        if !prevent_default && !message.trim().is_empty() {
+                            net.relay_player_chat(player_id, channel, message);
+                        }
+                    }
+                }
                 ClientPacket::ObjectInteraction {
                     tile_object_id,
                     button,
@@ -543,6 +662,18 @@ impl Server {
                         ServerPacket::SelectionAck,
                     );
                 }
+                ClientPacket::MailRead { mail_id } => {
+                    if net.mark_mail_read(player_id, &mail_id) {
+                        self.plugin_wrapper
+                            .handle_mail_read(net, player_id, &mail_id);
+                    }
+                }
+                ClientPacket::MailClaim { mail_id } => {
+                    if net.claim_mail(player_id, &mail_id) {
+                        self.plugin_wrapper
+                            .handle_mail_claimed(net, player_id, &mail_id);
+                    }
+                }
                 ClientPacket::EncounterStart => {
                     if let Some(client) = net.get_client_mut(player_id) {
                         if let Some(info) = client.battle_tracker.front() {
@@ -557,8 +688,12 @@ impl Server {
                     }
                 }
                 ClientPacket::BattleResults { battle_stats } => {
+                    self.packet_orchestrator
+                        .borrow_mut()
+                        .clear_netplay_spectators(socket_address);
+
                     self.plugin_wrapper
-                        .handle_battle_results(net, player_id, &battle_stats);
+                        .handle_battle_report(net, player_id, &battle_stats);
                 }
             }
         } else {
@@ -610,6 +745,7 @@ impl Server {
             self.plugin_wrapper
                 .handle_player_disconnect(&mut self.net, player_id);
 
+            self.net.save_player_data(player_id);
             self.net.remove_player(player_id, warp_out);
 
             if self.config.args.log_connections {
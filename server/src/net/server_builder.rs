@@ -1,12 +1,13 @@
 use super::plugin_wrapper::PluginWrapper;
 use super::server::Server;
-use super::ServerConfig;
+use super::{PlayerDataStorage, ServerConfig};
 use crate::plugins::PluginInterface;
 use std::net::UdpSocket;
 
 pub struct ServerBuilder {
     config: ServerConfig,
     plugin_wrapper: PluginWrapper,
+    player_data_storage: Option<Box<dyn PlayerDataStorage>>,
 }
 
 impl ServerBuilder {
@@ -14,6 +15,7 @@ impl ServerBuilder {
         Self {
             config,
             plugin_wrapper: PluginWrapper::new(),
+            player_data_storage: None,
         }
     }
 
@@ -22,6 +24,14 @@ impl ServerBuilder {
         self
     }
 
+    pub fn with_player_data_storage(
+        mut self,
+        player_data_storage: Box<dyn PlayerDataStorage>,
+    ) -> Self {
+        self.player_data_storage = Some(player_data_storage);
+        self
+    }
+
     pub async fn start(self) -> Result<(), Box<dyn std::error::Error>> {
         let addr = format!("0.0.0.0:{}", self.config.args.port);
         let socket = UdpSocket::bind(addr)?;
@@ -32,8 +42,14 @@ impl ServerBuilder {
 
         let (message_sender, message_receiver) = flume::unbounded();
 
-        Server::new(self.config, socket, self.plugin_wrapper, message_sender)
-            .start(message_receiver)
-            .await
+        Server::new(
+            self.config,
+            socket,
+            self.plugin_wrapper,
+            self.player_data_storage,
+            message_sender,
+        )
+        .start(message_receiver)
+        .await
     }
 }
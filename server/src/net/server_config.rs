@@ -1,8 +1,10 @@
 use crate::args::Args;
+use packets::IdentityKey;
 
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
     pub max_silence_duration: f32,
     pub heartbeat_rate: f32,
+    pub identity_key: IdentityKey,
     pub args: Args,
 }
@@ -70,3 +70,13 @@ impl<T> WidgetTracker<T> {
         self.active_shop.take()
     }
 }
+
+impl<T: PartialEq> WidgetTracker<T> {
+    pub fn tracks(&self, owner: &T) -> bool {
+        self.textbox_queue.contains(owner)
+            || self.bbs_queue.contains(owner)
+            || self.shop_queue.contains(owner)
+            || self.active_bbs.as_ref() == Some(owner)
+            || self.active_shop.as_ref() == Some(owner)
+    }
+}
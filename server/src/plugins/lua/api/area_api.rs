@@ -1,4 +1,4 @@
-use super::lua_errors::create_area_error;
+use super::lua_errors::{create_area_error, create_area_size_error};
 use super::LuaApi;
 use crate::net::map::{Map, Tile};
 use crate::net::Direction;
@@ -31,13 +31,115 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         let mut net = api_ctx.net_ref.borrow_mut();
         let map = Map::from(data_str);
 
+        net.add_area(area_id_str.to_string(), map);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "create_area", |api_ctx, lua, params| {
+        let (area_id, options): (String, mlua::Table) = lua.unpack_multi(params)?;
+
+        let width: usize = options.get("width")?;
+        let height: usize = options.get("height")?;
+        let tile_width: Option<u32> = options.get("tile_width")?;
+        let tile_height: Option<u32> = options.get("tile_height")?;
+        let layer_count: Option<usize> = options.get("layer_count")?;
+        let name: Option<String> = options.get("name")?;
+        let tilesets: Option<Vec<mlua::Table>> = options.get("tilesets")?;
+
+        let layer_count = layer_count.unwrap_or(1);
+
+        if !Map::is_valid_size(width, height) || !Map::is_valid_layer_count(layer_count) {
+            return Err(create_area_size_error(width, height));
+        }
+
+        let mut map = Map::new(
+            width,
+            height,
+            tile_width.unwrap_or(64),
+            tile_height.unwrap_or(32),
+        );
+
+        map.set_name(name.unwrap_or_else(|| area_id.clone()));
+
+        for tileset in tilesets.unwrap_or_default() {
+            let first_gid: u32 = tileset.get("first_gid")?;
+            let path: String = tileset.get("path")?;
+
+            map.add_tileset(first_gid, path);
+        }
+
+        for i in 0..layer_count {
+            map.add_layer(format!("Layer {}", i + 1));
+        }
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+        net.add_area(area_id, map);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "add_tileset", |api_ctx, lua, params| {
+        let (area_id, path, first_gid): (mlua::String, String, u32) = lua.unpack_multi(params)?;
+        let area_id_str = area_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        if net.get_area(area_id_str).is_none() {
+            return Err(create_area_error(area_id_str));
+        }
+
+        net.add_area_tileset(area_id_str, first_gid, path);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "resize_area", |api_ctx, lua, params| {
+        let (area_id, width, height): (mlua::String, usize, usize) = lua.unpack_multi(params)?;
+        let area_id_str = area_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
         if let Some(area) = net.get_area_mut(area_id_str) {
-            area.set_map(map);
+            if !area.map_mut().resize(width, height) {
+                return Err(create_area_size_error(width, height));
+            }
+
+            lua.pack_multi(())
         } else {
-            net.add_area(area_id_str.to_string(), map);
+            Err(create_area_error(area_id_str))
         }
+    });
 
-        lua.pack_multi(())
+    lua_api.add_dynamic_function("Net", "add_layer", |api_ctx, lua, params| {
+        let (area_id, name): (mlua::String, Option<String>) = lua.unpack_multi(params)?;
+        let area_id_str = area_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        if let Some(area) = net.get_area_mut(area_id_str) {
+            let map = area.map_mut();
+            let name = name.unwrap_or_else(|| format!("Layer {}", map.layer_count() + 1));
+
+            lua.pack_multi(map.add_layer(name))
+        } else {
+            Err(create_area_error(area_id_str))
+        }
+    });
+
+    lua_api.add_dynamic_function("Net", "remove_layer", |api_ctx, lua, params| {
+        let (area_id, layer): (mlua::String, usize) = lua.unpack_multi(params)?;
+        let area_id_str = area_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        if let Some(area) = net.get_area_mut(area_id_str) {
+            area.map_mut().remove_layer(layer);
+
+            lua.pack_multi(())
+        } else {
+            Err(create_area_error(area_id_str))
+        }
     });
 
     lua_api.add_dynamic_function("Net", "clone_area", |api_ctx, lua, params| {
@@ -655,6 +757,32 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         }
     });
 
+    lua_api.add_dynamic_function("Net", "can_move_to", |api_ctx, lua, params| {
+        let (area_id, x, y, z): (mlua::String, f32, f32, f32) = lua.unpack_multi(params)?;
+        let area_id_str = area_id.to_str()?;
+
+        let net = api_ctx.net_ref.borrow();
+
+        if let Some(area) = net.get_area(area_id_str) {
+            lua.pack_multi(area.map().can_move_to(x, y, z))
+        } else {
+            Err(create_area_error(area_id_str))
+        }
+    });
+
+    lua_api.add_dynamic_function("Net", "get_elevation", |api_ctx, lua, params| {
+        let (area_id, x, y, layer): (mlua::String, f32, f32, i32) = lua.unpack_multi(params)?;
+        let area_id_str = area_id.to_str()?;
+
+        let net = api_ctx.net_ref.borrow();
+
+        if let Some(area) = net.get_area(area_id_str) {
+            lua.pack_multi(area.map().elevation_at(x, y, layer))
+        } else {
+            Err(create_area_error(area_id_str))
+        }
+    });
+
     lua_api.add_dynamic_function("Net", "set_tile", |api_ctx, lua, params| {
         let (area_id, x, y, z, gid, flip_horizontal, flip_vertical, rotate): (
             mlua::String,
@@ -691,6 +819,38 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         }
     });
 
+    lua_api.add_dynamic_function("Net", "set_tiles", |api_ctx, lua, params| {
+        let (area_id, x, y, z, width, tile_values): (
+            mlua::String,
+            i32,
+            i32,
+            i32,
+            usize,
+            Vec<mlua::Value>,
+        ) = lua.unpack_multi(params)?;
+        let area_id_str = area_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        if x < 0 || y < 0 || z < 0 {
+            return lua.pack_multi(());
+        }
+
+        let tiles = tile_values
+            .into_iter()
+            .map(tile_from_lua)
+            .collect::<mlua::Result<Vec<_>>>()?;
+
+        if let Some(area) = net.get_area_mut(area_id_str) {
+            area.map_mut()
+                .set_tiles(x as usize, y as usize, z as usize, width, &tiles);
+
+            lua.pack_multi(())
+        } else {
+            Err(create_area_error(area_id_str))
+        }
+    });
+
     lua_api.add_dynamic_function("Net", "provide_asset", |api_ctx, lua, params| {
         let (area_id, asset_path): (mlua::String, mlua::String) = lua.unpack_multi(params)?;
 
@@ -711,3 +871,32 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         lua.pack_multi(())
     });
 }
+
+/// Accepts a gid, or a table using the same keys as `Net.get_tile`
+fn tile_from_lua(value: mlua::Value) -> mlua::Result<Tile> {
+    let mlua::Value::Table(table) = value else {
+        let gid = match value {
+            mlua::Value::Integer(gid) => gid as u32,
+            mlua::Value::Number(gid) => gid as u32,
+            _ => {
+                return Err(mlua::Error::RuntimeError(String::from(
+                    "Expected a tile gid or table",
+                )))
+            }
+        };
+
+        return Ok(Tile::from(gid));
+    };
+
+    let gid: u32 = table.get("gid")?;
+    let flip_horizontal: Option<bool> = table.get("flippedHorizontal")?;
+    let flip_vertical: Option<bool> = table.get("flippedVertical")?;
+    let rotate: Option<bool> = table.get("rotated")?;
+
+    Ok(Tile {
+        gid,
+        flipped_horizontally: flip_horizontal.unwrap_or(false),
+        flipped_vertically: flip_vertical.unwrap_or(false),
+        flipped_anti_diagonally: rotate.unwrap_or(false),
+    })
+}
@@ -2,7 +2,8 @@ use packets::structures::ActorId;
 
 use super::lua_errors::{create_area_error, create_bot_error};
 use super::LuaApi;
-use crate::net::{Actor, Direction};
+use crate::net::map::MapObjectData;
+use crate::net::{Actor, Direction, DEFAULT_BOT_SPEED};
 
 pub fn inject_dynamic(lua_api: &mut LuaApi) {
     lua_api.add_dynamic_function("Net", "list_bots", |api_ctx, lua, params| {
@@ -205,6 +206,91 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         lua.pack_multi(())
     });
 
+    lua_api.add_dynamic_function("Net", "walk_bot_to", |api_ctx, lua, params| {
+        let (bot_id, x, y, z, speed): (ActorId, f32, f32, f32, Option<f32>) =
+            lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        if net.get_bot(bot_id).is_none() {
+            return Err(create_bot_error(bot_id));
+        }
+
+        net.walk_bot_to(bot_id, x, y, z, speed.unwrap_or(DEFAULT_BOT_SPEED));
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "patrol_bot", |api_ctx, lua, params| {
+        let (bot_id, object_id, speed): (ActorId, u32, Option<f32>) = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        let Some(bot) = net.get_bot(bot_id) else {
+            return Err(create_bot_error(bot_id));
+        };
+
+        let Some(area) = net.get_area(&bot.area_id) else {
+            return Err(create_area_error(&bot.area_id));
+        };
+
+        let map = area.map();
+
+        let Some(object) = map.get_object_by_id(object_id) else {
+            return Err(mlua::Error::RuntimeError(format!(
+                "No object matching {object_id:?} found in {:?}.",
+                bot.area_id
+            )));
+        };
+
+        let (points, closed) = match &object.data {
+            MapObjectData::Polyline { points } => (points, false),
+            MapObjectData::Polygon { points } => (points, true),
+            _ => {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "Object {object_id:?} is not a polyline or polygon."
+                )));
+            }
+        };
+
+        // points are stored in pixels relative to the object
+        let scale_x = 1.0 / (map.tile_width() as f32 * 0.5);
+        let scale_y = 1.0 / map.tile_height() as f32;
+        let layer = object.layer as i32;
+
+        let route = points
+            .iter()
+            .map(|(x, y)| {
+                let x = object.x + x * scale_x;
+                let y = object.y + y * scale_y;
+
+                (x, y, map.elevation_at(x, y, layer))
+            })
+            .collect();
+
+        net.patrol_bot(bot_id, route, closed, speed.unwrap_or(DEFAULT_BOT_SPEED));
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "stop_bot", |api_ctx, lua, params| {
+        let bot_id: ActorId = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        net.stop_bot(bot_id);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "is_bot_navigating", |api_ctx, lua, params| {
+        let bot_id: ActorId = lua.unpack_multi(params)?;
+
+        let net = api_ctx.net_ref.borrow();
+
+        lua.pack_multi(net.is_bot_navigating(bot_id))
+    });
+
     lua_api.add_dynamic_function("Net", "animate_bot", |api_ctx, lua, params| {
         let (bot_id, name, loop_option): (ActorId, mlua::String, Option<bool>) =
             lua.unpack_multi(params)?;
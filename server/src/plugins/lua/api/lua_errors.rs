@@ -4,6 +4,10 @@ pub fn create_area_error(id: &str) -> mlua::Error {
     mlua::Error::RuntimeError(format!("No area matching {:?} found.", id))
 }
 
+pub fn create_area_size_error(width: usize, height: usize) -> mlua::Error {
+    mlua::Error::RuntimeError(format!("Invalid area size {width}x{height}."))
+}
+
 pub fn create_bot_error(id: ActorId) -> mlua::Error {
     mlua::Error::RuntimeError(format!("No bot matching {:?} found.", id))
 }
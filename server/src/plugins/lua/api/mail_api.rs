@@ -0,0 +1,204 @@
+use super::lua_errors::create_player_error;
+use super::widget_api::parse_texture_animation_pair;
+use super::LuaApi;
+use crate::net::Net;
+use packets::structures::{ActorId, Mail, MailAttachment, MailCategory, PackageId};
+
+pub fn inject_dynamic(lua_api: &mut LuaApi) {
+    lua_api.add_dynamic_function("Net", "send_mail", |api_ctx, lua, params| {
+        let (recipient, mail_table): (mlua::Value, mlua::Table) = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        let mail = parse_mail(&mut net, mail_table)?;
+        let mail_id = mail.id.clone();
+
+        match recipient {
+            // a secret from get_player_secret, the player may be offline
+            mlua::Value::String(identity) => {
+                if let Err(err) = net.send_mail_to_identity(identity.as_bytes(), mail) {
+                    return Err(mlua::Error::RuntimeError(format!(
+                        "failed to deliver mail: {err}"
+                    )));
+                }
+            }
+            recipient => {
+                let player_id: ActorId = lua.unpack(recipient)?;
+
+                if net.get_player_data(player_id).is_none() {
+                    return Err(create_player_error(player_id));
+                }
+
+                net.send_mail(player_id, mail);
+            }
+        }
+
+        lua.pack_multi(mail_id)
+    });
+
+    lua_api.add_dynamic_function("Net", "list_mail", |api_ctx, lua, params| {
+        let player_id: ActorId = lua.unpack_multi(params)?;
+
+        let net = api_ctx.net_ref.borrow();
+
+        let Some(player_data) = net.get_player_data(player_id) else {
+            return Err(create_player_error(player_id));
+        };
+
+        let mail_list = player_data
+            .mail
+            .iter()
+            .map(|mail| create_mail_table(lua, mail))
+            .collect::<mlua::Result<Vec<_>>>()?;
+
+        lua.pack_multi(mail_list)
+    });
+
+    lua_api.add_dynamic_function("Net", "delete_mail", |api_ctx, lua, params| {
+        let (player_id, mail_id): (ActorId, mlua::String) = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        net.delete_mail(player_id, mail_id.to_str()?);
+
+        lua.pack_multi(())
+    });
+}
+
+fn parse_mail(net: &mut Net, table: mlua::Table) -> mlua::Result<Mail> {
+    let id = table
+        .get::<_, Option<String>>("id")?
+        .unwrap_or_else(|| format!("{:016x}", rand::random::<u64>()));
+
+    let category = match table.get::<_, Option<mlua::String>>("category")? {
+        Some(category) => match category.to_str()? {
+            "message" => MailCategory::Message,
+            "announcement" => MailCategory::Announcement,
+            "important" => MailCategory::Important,
+            "mission" => MailCategory::Mission,
+            category => {
+                return Err(mlua::Error::RuntimeError(format!(
+                    "unknown mail category {category:?}"
+                )));
+            }
+        },
+        None => MailCategory::default(),
+    };
+
+    let attachments = table
+        .get::<_, Option<Vec<mlua::Table>>>("attachments")?
+        .unwrap_or_default()
+        .into_iter()
+        .map(|attachment_table| parse_attachment(net, attachment_table))
+        .collect::<mlua::Result<Vec<_>>>()?;
+
+    Ok(Mail {
+        id,
+        category,
+        sender: table
+            .get::<_, Option<String>>("sender")?
+            .unwrap_or_default(),
+        subject: table
+            .get::<_, Option<String>>("subject")?
+            .unwrap_or_default(),
+        body: table.get::<_, Option<String>>("body")?.unwrap_or_default(),
+        mug: table
+            .get::<_, Option<mlua::Table>>("mug")?
+            .map(parse_texture_animation_pair)
+            .transpose()?,
+        attachments,
+        read: table.get::<_, Option<bool>>("read")?.unwrap_or_default(),
+        claimed: false,
+    })
+}
+
+fn parse_attachment(net: &mut Net, table: mlua::Table) -> mlua::Result<MailAttachment> {
+    let count = table.get::<_, Option<usize>>("count")?.unwrap_or(1);
+
+    if let Some(id) = table.get::<_, Option<String>>("item_id")? {
+        let Some(item_definition) = net.get_item(&id) else {
+            return Err(mlua::Error::RuntimeError(format!(
+                "no item found with id {id:?}"
+            )));
+        };
+
+        return Ok(MailAttachment::Item {
+            name: item_definition.name.clone(),
+            id,
+            count,
+        });
+    }
+
+    if let Some(package_id) = table.get::<_, Option<String>>("package_id")? {
+        return Ok(MailAttachment::Card {
+            package_id: PackageId::from(package_id),
+            code: table.get::<_, Option<String>>("code")?.unwrap_or_default(),
+            count,
+        });
+    }
+
+    if let Some(amount) = table.get::<_, Option<u32>>("money")? {
+        return Ok(MailAttachment::Money { amount });
+    }
+
+    Err(mlua::Error::RuntimeError(String::from(
+        "mail attachments require an item_id, package_id, or money field",
+    )))
+}
+
+fn create_mail_table<'lua>(lua: &'lua mlua::Lua, mail: &Mail) -> mlua::Result<mlua::Table<'lua>> {
+    let table = lua.create_table()?;
+    table.set("id", mail.id.as_str())?;
+
+    let category = match mail.category {
+        MailCategory::Message => "message",
+        MailCategory::Announcement => "announcement",
+        MailCategory::Important => "important",
+        MailCategory::Mission => "mission",
+    };
+
+    table.set("category", category)?;
+    table.set("sender", mail.sender.as_str())?;
+    table.set("subject", mail.subject.as_str())?;
+    table.set("body", mail.body.as_str())?;
+
+    if let Some(mug) = &mail.mug {
+        let mug_table = lua.create_table()?;
+        mug_table.set("texture_path", mug.texture.as_ref())?;
+        mug_table.set("animation_path", mug.animation.as_ref())?;
+        table.set("mug", mug_table)?;
+    }
+
+    let attachments = lua.create_table()?;
+
+    for (i, attachment) in mail.attachments.iter().enumerate() {
+        let attachment_table = lua.create_table()?;
+
+        match attachment {
+            MailAttachment::Item { id, count, .. } => {
+                attachment_table.set("item_id", id.as_str())?;
+                attachment_table.set("count", *count)?;
+            }
+            MailAttachment::Card {
+                package_id,
+                code,
+                count,
+            } => {
+                attachment_table.set("package_id", package_id.as_str())?;
+                attachment_table.set("code", code.as_str())?;
+                attachment_table.set("count", *count)?;
+            }
+            MailAttachment::Money { amount } => {
+                attachment_table.set("money", *amount)?;
+            }
+        }
+
+        attachments.set(i + 1, attachment_table)?;
+    }
+
+    table.set("attachments", attachments)?;
+    table.set("read", mail.read)?;
+    table.set("claimed", mail.claimed)?;
+
+    Ok(table)
+}
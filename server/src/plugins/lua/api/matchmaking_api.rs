@@ -0,0 +1,103 @@
+use packets::structures::ActorId;
+
+use super::lua_helpers::*;
+use super::LuaApi;
+use crate::net::MatchmakingQueueOptions;
+
+pub fn inject_dynamic(lua_api: &mut LuaApi) {
+    lua_api.add_dynamic_function("Net", "create_matchmaking_queue", |api_ctx, lua, params| {
+        use std::time::Duration;
+
+        let (queue_id, table): (String, Option<mlua::Table>) = lua.unpack_multi(params)?;
+
+        let mut options = MatchmakingQueueOptions::default();
+
+        if let Some(table) = table {
+            let team_size: Option<usize> = table.get("team_size")?;
+            let team_count: Option<usize> = table.get("team_count")?;
+            let rating_range: Option<f32> = table.get("rating_range")?;
+            let rating_range_growth: Option<f32> = table.get("rating_range_growth")?;
+            let timeout: Option<f32> = table.get("timeout")?;
+            let verify_results: Option<bool> = table.get("verify_results")?;
+            let data: Option<mlua::Value> = table.get("data")?;
+
+            options.team_size = team_size.unwrap_or(options.team_size);
+            options.team_count = team_count.unwrap_or(options.team_count);
+            options.restrictions_path = table.get("restrictions_path")?;
+            options.package_path = table.get("package_path")?;
+            options.data = data.map(|v| lua_value_to_string(v, "", 0));
+            options.rating_range = rating_range.unwrap_or(options.rating_range);
+            options.rating_range_growth =
+                rating_range_growth.unwrap_or(options.rating_range_growth);
+            options.timeout = timeout.map(|seconds| Duration::from_secs_f32(seconds.max(0.0)));
+            options.verify_results = verify_results.unwrap_or(options.verify_results);
+        }
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+        net.create_matchmaking_queue(queue_id.clone(), options);
+
+        let mut matchmaking_owners = api_ctx.matchmaking_owner_ref.borrow_mut();
+        matchmaking_owners.insert(queue_id, api_ctx.script_index);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "remove_matchmaking_queue", |api_ctx, lua, params| {
+        let queue_id: mlua::String = lua.unpack_multi(params)?;
+        let queue_id_str = queue_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+        net.remove_matchmaking_queue(queue_id_str);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "list_matchmaking_queue", |api_ctx, lua, params| {
+        let queue_id: mlua::String = lua.unpack_multi(params)?;
+        let queue_id_str = queue_id.to_str()?;
+
+        let net = api_ctx.net_ref.borrow();
+
+        if !net.has_matchmaking_queue(queue_id_str) {
+            return lua.pack_multi(mlua::Nil);
+        }
+
+        let player_ids = net.get_matchmaking_queue_players(queue_id_str);
+
+        lua.pack_multi(player_ids)
+    });
+
+    lua_api.add_dynamic_function("Net", "join_matchmaking_queue", |api_ctx, lua, params| {
+        let (queue_id, player_id, rating): (mlua::String, ActorId, Option<f32>) =
+            lua.unpack_multi(params)?;
+        let queue_id_str = queue_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+        let joined =
+            net.join_matchmaking_queue(queue_id_str, player_id, rating.unwrap_or_default());
+
+        lua.pack_multi(joined)
+    });
+
+    lua_api.add_dynamic_function("Net", "leave_matchmaking_queue", |api_ctx, lua, params| {
+        let player_id: ActorId = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+        let left = net.leave_matchmaking_queue(player_id);
+
+        lua.pack_multi(left)
+    });
+
+    lua_api.add_dynamic_function(
+        "Net",
+        "get_player_matchmaking_queue",
+        |api_ctx, lua, params| {
+            let player_id: ActorId = lua.unpack_multi(params)?;
+
+            let net = api_ctx.net_ref.borrow();
+            let queue_id = net.get_player_matchmaking_queue(player_id);
+
+            lua.pack_multi(queue_id)
+        },
+    );
+}
@@ -6,6 +6,8 @@ mod bot_api;
 mod logging_api;
 mod lua_errors;
 mod lua_helpers;
+mod mail_api;
+mod matchmaking_api;
 mod misc_api;
 mod object_api;
 mod player_api;
@@ -26,6 +28,7 @@ pub struct ApiContext<'lua_scope, 'a> {
     pub net_ref: &'lua_scope RefCell<&'a mut Net>,
     pub widget_tracker_ref: &'lua_scope RefCell<&'a mut HashMap<ActorId, WidgetTracker<usize>>>,
     pub battle_tracker_ref: &'lua_scope RefCell<&'a mut HashMap<ActorId, VecDeque<usize>>>,
+    pub matchmaking_owner_ref: &'lua_scope RefCell<&'a mut HashMap<String, usize>>,
     pub promise_manager_ref: &'lua_scope RefCell<&'a mut JobPromiseManager>,
 }
 
@@ -66,7 +69,9 @@ impl LuaApi {
         object_api::inject_dynamic(&mut lua_api);
         player_api::inject_dynamic(&mut lua_api);
         player_data_api::inject_dynamic(&mut lua_api);
+        mail_api::inject_dynamic(&mut lua_api);
         widget_api::inject_dynamic(&mut lua_api);
+        matchmaking_api::inject_dynamic(&mut lua_api);
         bot_api::inject_dynamic(&mut lua_api);
         sprite_api::inject_dynamic(&mut lua_api);
         synchronization_api::inject_dynamic(&mut lua_api);
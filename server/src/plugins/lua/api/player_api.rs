@@ -62,6 +62,31 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         }
     });
 
+    lua_api.add_dynamic_function(
+        "Net",
+        "get_player_connection_stats",
+        |api_ctx, lua, params| {
+            let player_id: ActorId = lua.unpack_multi(params)?;
+
+            let net = api_ctx.net_ref.borrow();
+
+            let Some(stats) = net.get_player_connection_stats(player_id) else {
+                return Err(create_player_error(player_id));
+            };
+
+            let table = lua.create_table()?;
+            table.set("rtt", stats.rtt.as_secs_f32())?;
+            table.set("rtt_variance", stats.rtt_variance.as_secs_f32())?;
+            table.set("packet_loss", stats.packet_loss)?;
+            table.set("send_rate", stats.send_rate)?;
+            table.set("delivery_rate", stats.delivery_rate)?;
+            table.set("congestion_window", stats.congestion_window)?;
+            table.set("bytes_in_flight", stats.bytes_in_flight)?;
+
+            lua.pack_multi(table)
+        },
+    );
+
     lua_api.add_dynamic_function("Net", "get_player_name", |api_ctx, lua, params| {
         let player_id: ActorId = lua.unpack_multi(params)?;
 
@@ -207,6 +232,33 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         lua.pack_multi(())
     });
 
+    lua_api.add_dynamic_function("Net", "send_chat_message", |api_ctx, lua, params| {
+        let (player_id, message, sender_name): (ActorId, String, Option<String>) =
+            lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        net.send_chat_message(player_id, None, sender_name.unwrap_or_default(), message);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "broadcast_chat_message", |api_ctx, lua, params| {
+        let (area_id, message, sender_name): (mlua::String, String, Option<String>) =
+            lua.unpack_multi(params)?;
+        let area_id = area_id.to_str()?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        if net.get_area(area_id).is_none() {
+            return Err(create_area_error(area_id));
+        }
+
+        net.broadcast_chat_message(area_id, None, sender_name.unwrap_or_default(), message);
+
+        lua.pack_multi(())
+    });
+
     lua_api.add_dynamic_function("Net", "set_player_map_color", |api_ctx, lua, params| {
         let (player_id, color_table): (ActorId, mlua::Table) = lua.unpack_multi(params)?;
 
@@ -479,14 +531,23 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
 
         let data_string = data.map(|v| lua_value_to_string(v, "", 0));
 
-        net.initiate_netplay(&player_ids, package_path, data_string);
+        net.initiate_netplay(&player_ids, package_path, data_string, false);
 
         lua.pack_multi(())
     });
 
     lua_api.add_dynamic_function("Net", "_initiate_netplay", |api_ctx, lua, params| {
-        let (player_ids, package_path, data): (Vec<ActorId>, Option<String>, Option<mlua::Value>) =
-            lua.unpack_multi(params)?;
+        let (player_ids, package_path, data, options): (
+            Vec<ActorId>,
+            Option<String>,
+            Option<mlua::Value>,
+            Option<mlua::Table>,
+        ) = lua.unpack_multi(params)?;
+
+        let verify_results = match options {
+            Some(options) => options.get::<_, Option<bool>>("verify_results")?,
+            None => None,
+        };
 
         let mut net = api_ctx.net_ref.borrow_mut();
         let mut battle_tracker = api_ctx.battle_tracker_ref.borrow_mut();
@@ -499,7 +560,30 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
 
         let data_string = data.map(|v| lua_value_to_string(v, "", 0));
 
-        net.initiate_netplay(&player_ids, package_path, data_string);
+        net.initiate_netplay(
+            &player_ids,
+            package_path,
+            data_string,
+            verify_results.unwrap_or_default(),
+        );
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "add_netplay_spectator", |api_ctx, lua, params| {
+        let (spectator_id, player_id): (ActorId, ActorId) = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+        net.add_netplay_spectator(spectator_id, player_id);
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "remove_netplay_spectator", |api_ctx, lua, params| {
+        let spectator_id: ActorId = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+        net.remove_netplay_spectator(spectator_id);
 
         lua.pack_multi(())
     });
@@ -1,7 +1,7 @@
 use super::lua_errors::create_player_error;
 use super::LuaApi;
 use crate::net::ItemDefinition;
-use packets::structures::{ActorId, BlockColor, Emotion, PackageId};
+use packets::structures::{ActorId, BattleReward, BlockColor, Emotion, PackageId};
 use std::borrow::Cow;
 
 pub fn inject_dynamic(lua_api: &mut LuaApi) {
@@ -19,6 +19,16 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         }
     });
 
+    lua_api.add_dynamic_function("Net", "save_player_data", |api_ctx, lua, params| {
+        let player_id: ActorId = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        net.save_player_data(player_id);
+
+        lua.pack_multi(())
+    });
+
     lua_api.add_dynamic_function("Net", "get_player_element", |api_ctx, lua, params| {
         let player_id: ActorId = lua.unpack_multi(params)?;
 
@@ -175,6 +185,42 @@ pub fn inject_dynamic(lua_api: &mut LuaApi) {
         lua.pack_multi(())
     });
 
+    lua_api.add_dynamic_function("Net", "add_battle_reward", |api_ctx, lua, params| {
+        let (player_id, text): (ActorId, String) = lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        net.add_battle_reward(player_id, BattleReward::Text(text));
+
+        lua.pack_multi(())
+    });
+
+    lua_api.add_dynamic_function("Net", "add_battle_reward_item", |api_ctx, lua, params| {
+        let (player_id, item_id, count): (ActorId, String, Option<isize>) =
+            lua.unpack_multi(params)?;
+
+        let mut net = api_ctx.net_ref.borrow_mut();
+
+        let Some(item_definition) = net.get_item(&item_id) else {
+            return Err(mlua::Error::RuntimeError(format!(
+                "no item found with id {item_id:?}"
+            )));
+        };
+
+        let name = item_definition.name.clone();
+        let count = count.unwrap_or(1);
+
+        // verified results are reported by every player, don't reward a side of a dispute
+        if net.is_latest_battle_disputed(player_id) {
+            return lua.pack_multi(false);
+        }
+
+        net.give_player_item(player_id, item_id, count);
+        net.add_battle_reward(player_id, BattleReward::Item { name, count });
+
+        lua.pack_multi(true)
+    });
+
     lua_api.add_dynamic_function("Net", "get_player_item_count", |api_ctx, lua, params| {
         let (player_id, item_id): (ActorId, mlua::String) = lua.unpack_multi(params)?;
         let item_id_str = item_id.to_str()?;
@@ -402,7 +402,9 @@ fn parse_textbox_options<'lua>(
     Ok(textbox_options)
 }
 
-fn parse_texture_animation_pair(table: mlua::Table) -> mlua::Result<TextureAnimPathPair<'static>> {
+pub(super) fn parse_texture_animation_pair(
+    table: mlua::Table,
+) -> mlua::Result<TextureAnimPathPair<'static>> {
     Ok(TextureAnimPathPair {
         texture: table.get::<_, String>("texture_path")?.into(),
         animation: table.get::<_, String>("animation_path")?.into(),
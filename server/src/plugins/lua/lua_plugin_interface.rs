@@ -1,18 +1,28 @@
 use super::api::{ApiContext, LuaApi};
+use crate::helpers::{normalize_path, FileWatcher};
 use crate::jobs::JobPromiseManager;
-use crate::net::{BattleStatistics, Net, WidgetTracker};
+use crate::net::{
+    BattleReward, BattleStatistics, BattleVerdict, MatchmakingDropReason, NavigationResult, Net,
+    WidgetTracker,
+};
 use crate::plugins::PluginInterface;
 use mlua::Lua;
-use packets::structures::{ActorId, PackageId};
+use packets::structures::{ActorId, ChatChannel, PackageId};
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::collections::VecDeque;
+use std::collections::{HashSet, VecDeque};
+use std::path::{Path, PathBuf};
 
 pub struct LuaPluginInterface {
     scripts: Vec<Lua>,
+    script_paths: Vec<PathBuf>,
+    script_dependencies: Vec<HashSet<PathBuf>>,
+    script_watcher: Option<FileWatcher>,
+    pending_reloads: HashSet<usize>,
     all_scripts: Vec<usize>,
     widget_trackers: HashMap<ActorId, WidgetTracker<usize>>,
     battle_trackers: HashMap<ActorId, VecDeque<usize>>,
+    matchmaking_owners: HashMap<String, usize>,
     promise_manager: JobPromiseManager,
     lua_api: LuaApi,
 }
@@ -21,14 +31,24 @@ impl LuaPluginInterface {
     pub fn new() -> LuaPluginInterface {
         LuaPluginInterface {
             scripts: Vec::new(),
+            script_paths: Vec::new(),
+            script_dependencies: Vec::new(),
+            script_watcher: None,
+            pending_reloads: HashSet::new(),
             all_scripts: Vec::new(),
             widget_trackers: HashMap::new(),
             battle_trackers: HashMap::new(),
+            matchmaking_owners: HashMap::new(),
             promise_manager: JobPromiseManager::new(),
             lua_api: LuaApi::new(),
         }
     }
 
+    /// Reloads scripts when any file they required changes
+    pub fn enable_hot_reload(&mut self) {
+        self.script_watcher = Some(FileWatcher::new_recursive("./scripts", "lua"));
+    }
+
     fn load_scripts(&mut self, net_ref: &mut Net) -> std::io::Result<()> {
         use std::fs::read_dir;
 
@@ -59,16 +79,24 @@ impl LuaPluginInterface {
         net_ref: &mut Net,
         script_path: std::path::PathBuf,
     ) -> mlua::Result<()> {
-        let net_ref = RefCell::new(net_ref);
-
         let script_index = self.scripts.len();
         self.scripts.push(Lua::new());
+        self.script_paths.push(script_path);
+        self.script_dependencies.push(HashSet::new());
         self.all_scripts.push(script_index);
 
-        let lua = self.scripts.last_mut().unwrap();
+        self.init_script(net_ref, script_index)
+    }
+
+    fn init_script(&mut self, net_ref: &mut Net, script_index: usize) -> mlua::Result<()> {
+        let net_ref = RefCell::new(net_ref);
+
+        let script_path = &self.script_paths[script_index];
+        let lua = &mut self.scripts[script_index];
 
         let widget_tracker_ref = RefCell::new(&mut self.widget_trackers);
         let battle_tracker_ref = RefCell::new(&mut self.battle_trackers);
+        let matchmaking_owner_ref = RefCell::new(&mut self.matchmaking_owners);
         let promise_manager_ref = RefCell::new(&mut self.promise_manager);
 
         let api_ctx = ApiContext {
@@ -76,6 +104,7 @@ impl LuaPluginInterface {
             net_ref: &net_ref,
             widget_tracker_ref: &widget_tracker_ref,
             battle_tracker_ref: &battle_tracker_ref,
+            matchmaking_owner_ref: &matchmaking_owner_ref,
             promise_manager_ref: &promise_manager_ref,
         };
 
@@ -87,7 +116,7 @@ impl LuaPluginInterface {
             .set_name("internal: deprecated_functions.lua")
             .exec()?;
 
-        self.lua_api.inject_dynamic(lua, api_ctx, |_| {
+        let result = self.lua_api.inject_dynamic(lua, api_ctx, |_| {
             let parent_path = script_path
                 .parent()
                 .unwrap_or_else(|| std::path::Path::new(""));
@@ -102,7 +131,12 @@ impl LuaPluginInterface {
             require.call::<&str, ()>(final_path)?;
 
             Ok(())
-        })?;
+        });
+
+        // track dependencies even on failure, allows us to retry after a fix
+        self.script_dependencies[script_index] = resolve_script_dependencies(lua, script_path);
+
+        result?;
 
         lua.load(include_str!("api/deprecated_callbacks.lua"))
             .set_name("internal: deprecated_callbacks.lua")
@@ -110,6 +144,127 @@ impl LuaPluginInterface {
 
         Ok(())
     }
+
+    /// Responses are routed by script index, swapping the state early would hand them to the new state
+    fn has_pending_callbacks(&self, script_index: usize) -> bool {
+        self.promise_manager.has_promises_owned_by(script_index)
+            || (self.widget_trackers.values()).any(|tracker| tracker.tracks(&script_index))
+            || (self.battle_trackers.values()).any(|tracker| tracker.contains(&script_index))
+    }
+
+    fn reload_changed_scripts(&mut self, net: &mut Net) {
+        let Some(script_watcher) = &mut self.script_watcher else {
+            return;
+        };
+
+        let changed_paths = script_watcher.poll();
+
+        for script_index in 0..self.scripts.len() {
+            let affected = changed_paths
+                .iter()
+                .any(|path| self.script_dependencies[script_index].contains(path));
+
+            if affected
+                && self.pending_reloads.insert(script_index)
+                && self.has_pending_callbacks(script_index)
+            {
+                log::info!(
+                    "Delaying reload of {:?} until its pending callbacks resolve",
+                    self.script_paths[script_index]
+                );
+            }
+        }
+
+        let mut ready_scripts: Vec<usize> = self
+            .pending_reloads
+            .iter()
+            .copied()
+            .filter(|&script_index| !self.has_pending_callbacks(script_index))
+            .collect();
+
+        ready_scripts.sort();
+
+        for script_index in ready_scripts {
+            self.pending_reloads.remove(&script_index);
+
+            log::info!("Reloading {:?}", self.script_paths[script_index]);
+
+            self.scripts[script_index] = Lua::new();
+
+            if let Err(err) = self.init_script(net, script_index) {
+                log::error!("{}", err);
+                continue;
+            }
+
+            handle_event(
+                &mut self.scripts,
+                &[script_index],
+                &mut self.widget_trackers,
+                &mut self.battle_trackers,
+                &mut self.matchmaking_owners,
+                &mut self.promise_manager,
+                &mut self.lua_api,
+                net,
+                |lua, callback| {
+                    let event = lua.create_table()?;
+
+                    callback.call(("script_reload", event))
+                },
+            );
+        }
+
+        // load scripts added since startup
+        let known_paths: HashSet<PathBuf> = self
+            .script_paths
+            .iter()
+            .map(|path| normalize_path(path))
+            .collect();
+
+        for path in changed_paths {
+            if known_paths.contains(&path) || !is_entry_script(&path) {
+                continue;
+            }
+
+            log::info!("Loading {path:?}");
+
+            if let Err(err) = self.load_script(net, Path::new(".").join(path)) {
+                log::error!("{}", err);
+            }
+        }
+    }
+}
+
+/// Resolves files loaded through require(), using the default ./?.lua search path
+fn resolve_script_dependencies(lua: &Lua, script_path: &Path) -> HashSet<PathBuf> {
+    let mut dependencies = HashSet::new();
+    dependencies.insert(normalize_path(script_path));
+
+    let Ok(package) = lua.globals().get::<_, mlua::Table>("package") else {
+        return dependencies;
+    };
+
+    let Ok(loaded) = package.get::<_, mlua::Table>("loaded") else {
+        return dependencies;
+    };
+
+    for (module_name, _) in loaded.pairs::<String, mlua::Value>().flatten() {
+        let path = PathBuf::from(module_name.replace('.', "/") + ".lua");
+
+        dependencies.insert(normalize_path(&path));
+    }
+
+    dependencies
+}
+
+fn is_entry_script(path: &Path) -> bool {
+    // matches scripts/*.lua and scripts/*/main.lua
+    let components: Vec<_> = path.components().collect();
+
+    match components.len() {
+        2 => true,
+        3 => path.file_name().is_some_and(|name| name == "main.lua"),
+        _ => false,
+    }
 }
 
 impl PluginInterface for LuaPluginInterface {
@@ -120,11 +275,14 @@ impl PluginInterface for LuaPluginInterface {
     }
 
     fn tick(&mut self, net: &mut Net, delta_time: f32) {
+        self.reload_changed_scripts(net);
+
         handle_event(
             &mut self.scripts,
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -143,6 +301,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -168,6 +327,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -187,6 +347,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -205,6 +366,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -223,6 +385,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -241,6 +404,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -262,6 +426,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -283,6 +448,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -324,6 +490,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -360,6 +527,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -384,12 +552,71 @@ impl PluginInterface for LuaPluginInterface {
         prevent_default.get()
     }
 
+    fn handle_player_chat(
+        &mut self,
+        net: &mut Net,
+        player_id: ActorId,
+        channel: ChatChannel,
+        message: &mut String,
+    ) -> bool {
+        use std::cell::Cell;
+        use std::rc::Rc;
+
+        let prevent_default = Rc::new(Cell::new(false));
+
+        handle_event(
+            &mut self.scripts,
+            &self.all_scripts,
+            &mut self.widget_trackers,
+            &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
+            &mut self.promise_manager,
+            &mut self.lua_api,
+            net,
+            |lua, callback| {
+                let prevent_default_reference = prevent_default.clone();
+
+                let event = lua.create_table()?;
+                event.set("player_id", player_id)?;
+                event.set("message", message.as_str())?;
+
+                match channel {
+                    ChatChannel::Area => {
+                        event.set("channel", "area")?;
+                    }
+                    ChatChannel::Whisper(target_id) => {
+                        event.set("channel", "whisper")?;
+                        event.set("target_id", target_id)?;
+                    }
+                }
+
+                event.set(
+                    "prevent_default",
+                    lua.create_function(move |_, _: ()| {
+                        prevent_default_reference.clone().set(true);
+                        Ok(())
+                    })?,
+                )?;
+
+                callback.call::<_, ()>(("player_chat", event.clone()))?;
+
+                // scripts can rewrite the message by assigning to event.message
+                *message = event.get("message")?;
+
+                Ok(())
+            },
+        );
+
+        prevent_default.get()
+    }
+
     fn handle_custom_warp(&mut self, net: &mut Net, player_id: ActorId, tile_object_id: u32) {
         handle_event(
             &mut self.scripts,
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -415,6 +642,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -441,6 +669,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -469,6 +698,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -498,6 +728,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -524,6 +755,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -552,6 +784,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -577,6 +810,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -602,6 +836,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -627,6 +862,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -659,6 +895,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -684,6 +921,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -709,6 +947,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -740,6 +979,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -759,6 +999,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -772,11 +1013,52 @@ impl PluginInterface for LuaPluginInterface {
         );
     }
 
+    fn handle_mail_read(&mut self, net: &mut Net, player_id: ActorId, mail_id: &str) {
+        handle_event(
+            &mut self.scripts,
+            &self.all_scripts,
+            &mut self.widget_trackers,
+            &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
+            &mut self.promise_manager,
+            &mut self.lua_api,
+            net,
+            |lua, callback| {
+                let event = lua.create_table()?;
+                event.set("player_id", player_id)?;
+                event.set("mail_id", mail_id)?;
+
+                callback.call(("mail_read", event))
+            },
+        );
+    }
+
+    fn handle_mail_claimed(&mut self, net: &mut Net, player_id: ActorId, mail_id: &str) {
+        handle_event(
+            &mut self.scripts,
+            &self.all_scripts,
+            &mut self.widget_trackers,
+            &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
+            &mut self.promise_manager,
+            &mut self.lua_api,
+            net,
+            |lua, callback| {
+                let event = lua.create_table()?;
+                event.set("player_id", player_id)?;
+                event.set("mail_id", mail_id)?;
+
+                callback.call(("mail_claimed", event))
+            },
+        );
+    }
+
     fn handle_battle_results(
         &mut self,
         net: &mut Net,
         player_id: ActorId,
         battle_stats: &BattleStatistics,
+        verdict: Option<BattleVerdict>,
     ) {
         let tracker = self.battle_trackers.get_mut(&player_id).unwrap();
 
@@ -790,6 +1072,7 @@ impl PluginInterface for LuaPluginInterface {
             &[script_index],
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -803,6 +1086,11 @@ impl PluginInterface for LuaPluginInterface {
                 event.set("ran", battle_stats.ran)?;
                 event.set("emotion", battle_stats.emotion.as_str())?;
                 event.set("turns", battle_stats.turns)?;
+                event.set("verdict", verdict.map(BattleVerdict::as_str))?;
+                event.set("hits_taken", battle_stats.hits_taken)?;
+                event.set("movements", battle_stats.movements)?;
+                event.set("counters", battle_stats.counters)?;
+                event.set("max_kill_chain", battle_stats.max_kill_chain)?;
 
                 // ally list
                 let mut neutral_tables = Vec::with_capacity(battle_stats.ally_survivors.len());
@@ -840,11 +1128,147 @@ impl PluginInterface for LuaPluginInterface {
 
                 event.set("neutral", neutral_tables)?;
 
+                // rewards from the encounter package, dropped when verified players disagree on the results
+                let rewards = match verdict {
+                    Some(BattleVerdict::Disagreed | BattleVerdict::Incomplete) => &[],
+                    _ => battle_stats.rewards.as_slice(),
+                };
+
+                let mut reward_tables = Vec::with_capacity(rewards.len());
+
+                for reward in rewards {
+                    let table = lua.create_table()?;
+
+                    match reward {
+                        BattleReward::Text(text) => {
+                            table.set("text", text.as_str())?;
+                        }
+                        BattleReward::Item { name, count } => {
+                            table.set("item_name", name.as_str())?;
+                            table.set("count", *count)?;
+                        }
+                    }
+
+                    reward_tables.push(table);
+                }
+
+                event.set("rewards", reward_tables)?;
+
+                // per player breakdowns, in the order the players were passed to the battle
+                let mut player_tables = Vec::with_capacity(battle_stats.players.len());
+
+                for player_stats in &battle_stats.players {
+                    let table = lua.create_table()?;
+                    table.set("damage_dealt", player_stats.damage_dealt)?;
+                    table.set("counters", player_stats.counters)?;
+                    table.set("cards_used", player_stats.cards_used)?;
+
+                    let card_usage_table = lua.create_table()?;
+
+                    for (package_id, count) in &player_stats.card_usage {
+                        card_usage_table.set(package_id.as_str(), *count)?;
+                    }
+
+                    table.set("card_usage", card_usage_table)?;
+
+                    player_tables.push(table);
+                }
+
+                event.set("players", player_tables)?;
+
                 callback.call(("battle_results", event))
             },
         );
     }
 
+    fn handle_match_found(&mut self, net: &mut Net, queue_id: &str, teams: &[Vec<ActorId>]) {
+        let Some(&script_index) = self.matchmaking_owners.get(queue_id) else {
+            return;
+        };
+
+        // results are sent to the script that created the queue
+        for player_id in teams.iter().flatten() {
+            if let Some(tracker) = self.battle_trackers.get_mut(player_id) {
+                tracker.push_back(script_index);
+            }
+        }
+
+        handle_event(
+            &mut self.scripts,
+            &[script_index],
+            &mut self.widget_trackers,
+            &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
+            &mut self.promise_manager,
+            &mut self.lua_api,
+            net,
+            |lua, callback| {
+                let event = lua.create_table()?;
+                event.set("queue_id", queue_id)?;
+                event.set("player_ids", teams.concat())?;
+                event.set("teams", teams.to_vec())?;
+
+                callback.call(("matchmaking_match", event))
+            },
+        );
+    }
+
+    fn handle_matchmaking_drop(
+        &mut self,
+        net: &mut Net,
+        queue_id: &str,
+        player_id: ActorId,
+        reason: MatchmakingDropReason,
+    ) {
+        let Some(&script_index) = self.matchmaking_owners.get(queue_id) else {
+            return;
+        };
+
+        handle_event(
+            &mut self.scripts,
+            &[script_index],
+            &mut self.widget_trackers,
+            &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
+            &mut self.promise_manager,
+            &mut self.lua_api,
+            net,
+            |lua, callback| {
+                let event = lua.create_table()?;
+                event.set("queue_id", queue_id)?;
+                event.set("player_id", player_id)?;
+                event.set("reason", reason.as_str())?;
+
+                callback.call(("matchmaking_drop", event))
+            },
+        );
+    }
+
+    fn handle_bot_navigation_end(
+        &mut self,
+        net: &mut Net,
+        bot_id: ActorId,
+        result: NavigationResult,
+    ) {
+        handle_event(
+            &mut self.scripts,
+            &self.all_scripts,
+            &mut self.widget_trackers,
+            &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
+            &mut self.promise_manager,
+            &mut self.lua_api,
+            net,
+            |lua, callback| {
+                let event = lua.create_table()?;
+                event.set("bot_id", bot_id)?;
+                event.set("result", result.as_str())?;
+
+                callback.call(("bot_navigation_end", event))
+            },
+        );
+    }
+
     fn handle_server_message(
         &mut self,
         net: &mut Net,
@@ -858,6 +1282,7 @@ impl PluginInterface for LuaPluginInterface {
             &self.all_scripts,
             &mut self.widget_trackers,
             &mut self.battle_trackers,
+            &mut self.matchmaking_owners,
             &mut self.promise_manager,
             &mut self.lua_api,
             net,
@@ -878,6 +1303,7 @@ fn handle_event<F>(
     event_listeners: &[usize],
     widget_tracker: &mut HashMap<ActorId, WidgetTracker<usize>>,
     battle_tracker: &mut HashMap<ActorId, VecDeque<usize>>,
+    matchmaking_owners: &mut HashMap<String, usize>,
     promise_manager: &mut JobPromiseManager,
     lua_api: &mut LuaApi,
     net: &mut Net,
@@ -891,6 +1317,7 @@ fn handle_event<F>(
         let net_ref = RefCell::new(net);
         let widget_tracker_ref = RefCell::new(widget_tracker);
         let battle_tracker_ref = RefCell::new(battle_tracker);
+        let matchmaking_owner_ref = RefCell::new(matchmaking_owners);
         let promise_manager_ref = RefCell::new(promise_manager);
 
         // loop over scripts
@@ -902,6 +1329,7 @@ fn handle_event<F>(
                 net_ref: &net_ref,
                 widget_tracker_ref: &widget_tracker_ref,
                 battle_tracker_ref: &battle_tracker_ref,
+                matchmaking_owner_ref: &matchmaking_owner_ref,
                 promise_manager_ref: &promise_manager_ref,
             };
 
@@ -927,3 +1355,19 @@ fn handle_event<F>(
         log::error!("{:#}", err);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entry_scripts() {
+        assert!(is_entry_script(Path::new("scripts/main.lua")));
+        assert!(is_entry_script(Path::new("scripts/shop.lua")));
+        assert!(is_entry_script(Path::new("scripts/shop/main.lua")));
+
+        assert!(!is_entry_script(Path::new("scripts/shop/items.lua")));
+        assert!(!is_entry_script(Path::new("scripts/shop/data/main.lua")));
+        assert!(!is_entry_script(Path::new("scripts")));
+    }
+}
@@ -1,5 +1,5 @@
-use crate::net::{BattleStatistics, Net};
-use packets::structures::{ActorId, PackageId};
+use crate::net::{BattleStatistics, BattleVerdict, MatchmakingDropReason, NavigationResult, Net};
+use packets::structures::{ActorId, ChatChannel, PackageId};
 
 pub trait PluginInterface {
     fn init(&mut self, net: &mut Net);
@@ -20,6 +20,14 @@ pub trait PluginInterface {
         animation_path: &str,
     ) -> bool;
     fn handle_player_emote(&mut self, net: &mut Net, player_id: ActorId, emote_id: &str) -> bool;
+    /// The message can be rewritten, returning true prevents the default relay
+    fn handle_player_chat(
+        &mut self,
+        net: &mut Net,
+        player_id: ActorId,
+        channel: ChatChannel,
+        message: &mut String,
+    ) -> bool;
     fn handle_custom_warp(&mut self, net: &mut Net, player_id: ActorId, tile_object_id: u32);
     fn handle_object_interaction(
         &mut self,
@@ -56,11 +64,29 @@ pub trait PluginInterface {
     fn handle_shop_purchase(&mut self, net: &mut Net, player_id: ActorId, item_id: &str);
     fn handle_shop_description_request(&mut self, net: &mut Net, player_id: ActorId, item_id: &str);
     fn handle_item_use(&mut self, net: &mut Net, player_id: ActorId, item_id: &str);
+    fn handle_mail_read(&mut self, net: &mut Net, player_id: ActorId, mail_id: &str);
+    /// Called after the attachments have been given to the player
+    fn handle_mail_claimed(&mut self, net: &mut Net, player_id: ActorId, mail_id: &str);
     fn handle_battle_results(
         &mut self,
         net: &mut Net,
         player_id: ActorId,
         battle_stats: &BattleStatistics,
+        verdict: Option<BattleVerdict>,
+    );
+    fn handle_match_found(&mut self, net: &mut Net, queue_id: &str, teams: &[Vec<ActorId>]);
+    fn handle_matchmaking_drop(
+        &mut self,
+        net: &mut Net,
+        queue_id: &str,
+        player_id: ActorId,
+        reason: MatchmakingDropReason,
+    );
+    fn handle_bot_navigation_end(
+        &mut self,
+        net: &mut Net,
+        bot_id: ActorId,
+        result: NavigationResult,
     );
     fn handle_server_message(
         &mut self,